@@ -838,32 +838,32 @@ impl<'a> Parser<'a> {
 // ======================================================================================
 
 /// Defines the `Expr` compiler.
-pub struct Compiler<'a> {
-    pub context: &'a Context,
-    pub builder: &'a Builder,
+pub struct Compiler<'a, 'ctx> {
+    pub context: &'ctx Context,
+    pub builder: &'a Builder<'ctx>,
     pub fpm: &'a PassManager,
-    pub module: &'a Module,
+    pub module: &'a Module<'ctx>,
     pub function: &'a Function,
 
-    variables: HashMap<String, PointerValue>,
-    fn_value_opt: Option<FunctionValue>
+    variables: HashMap<String, PointerValue<'ctx>>,
+    fn_value_opt: Option<FunctionValue<'ctx>>
 }
 
-impl<'a> Compiler<'a> {
+impl<'a, 'ctx> Compiler<'a, 'ctx> {
     /// Gets a defined function given its name.
     #[inline]
-    fn get_function(&self, name: &str) -> Option<FunctionValue> {
+    fn get_function(&self, name: &str) -> Option<FunctionValue<'ctx>> {
         self.module.get_function(name)
     }
 
     /// Returns the `FunctionValue` representing the function being compiled.
     #[inline]
-    fn fn_value(&self) -> FunctionValue {
+    fn fn_value(&self) -> FunctionValue<'ctx> {
         self.fn_value_opt.unwrap()
     }
 
     /// Cretes a new stack allocation instruction in the entry block of the function.
-    fn create_entry_block_alloca(&self, name: &str, entry: Option<&BasicBlock>) -> PointerValue {
+    fn create_entry_block_alloca(&self, name: &str, entry: Option<&BasicBlock<'ctx>>) -> PointerValue<'ctx> {
         let builder = self.context.create_builder();
 
         let owned_entry = self.fn_value().get_entry_basic_block();
@@ -878,7 +878,7 @@ impl<'a> Compiler<'a> {
     }
 
     /// Compiles the specified `Expr` into an LLVM `FloatValue`.
-    fn compile_expr(&mut self, expr: &Expr) -> Result<FloatValue, &'static str> {
+    fn compile_expr(&mut self, expr: &Expr) -> Result<FloatValue<'ctx>, &'static str> {
         match *expr {
             Expr::Number(nb) => Ok(self.context.f64_type().const_float(nb)),
 
@@ -963,7 +963,7 @@ impl<'a> Compiler<'a> {
 
                             match self.get_function(name.as_str()) {
                                 Some(fun) => {
-                                    match self.builder.build_call(&fun, &[ &lhs, &rhs ], "tmpbin", false).left() {
+                                    match self.builder.build_call(&fun, &[ &lhs, &rhs ], "tmpbin").try_as_basic_value().left() {
                                         Some(value) => Ok(value.into_float_value()),
                                         None => Err("Invalid call produced.")
                                     }
@@ -987,7 +987,7 @@ impl<'a> Compiler<'a> {
 
                         let argsv: Vec<&BasicValue> = compiled_args.iter().by_ref().map(|val| val as &BasicValue).collect();
 
-                        match self.builder.build_call(&fun, argsv.as_slice(), "tmp", false).left() {
+                        match self.builder.build_call(&fun, argsv.as_slice(), "tmp").try_as_basic_value().left() {
                             Some(value) => Ok(value.into_float_value()),
                             None => Err("Invalid call produced.")
                         }
@@ -1091,7 +1091,7 @@ impl<'a> Compiler<'a> {
     }
 
     /// Compiles the specified `Prototype` into an extern LLVM `FunctionValue`.
-    fn compile_prototype(&self, proto: &Prototype) -> Result<FunctionValue, &'static str> {
+    fn compile_prototype(&self, proto: &Prototype) -> Result<FunctionValue<'ctx>, &'static str> {
         let ret_type: &BasicType = &self.context.f64_type();
         let args_types = std::iter::repeat(ret_type).take(proto.args.len()).collect::<Vec<&BasicType>>();
         let args_types = args_types.as_slice();
@@ -1109,7 +1109,7 @@ impl<'a> Compiler<'a> {
     }
 
     /// Compiles the specified `Function` into an LLVM `FunctionValue`.
-    fn compile_fn(&mut self) -> Result<FunctionValue, &'static str> {
+    fn compile_fn(&mut self) -> Result<FunctionValue<'ctx>, &'static str> {
         let proto = &self.function.prototype;
         let function = self.compile_prototype(proto)?;
         let entry = self.context.append_basic_block(&function, "entry");
@@ -1137,21 +1137,26 @@ impl<'a> Compiler<'a> {
         self.builder.build_return(Some(&body));
 
         // return the whole thing after verification and optimization
-        if function.verify(true) {
-            self.fpm.run_on_function(&function);
+        match function.verify() {
+            Ok(()) => {
+                self.fpm.run_on_function(&function);
 
-            Ok(function)
-        } else {
-            unsafe {
-                function.delete();
-            }
+                Ok(function)
+            },
+            Err(err) => {
+                eprintln!("{}", err.get_message());
 
-            Err("Invalid generated function.")
+                unsafe {
+                    function.delete();
+                }
+
+                Err("Invalid generated function.")
+            }
         }
     }
 
     /// Compiles the specified `Function` in the given `Context` and using the specified `Builder`, `PassManager`, and `Module`.
-    pub fn compile(context: &'a Context, builder: &'a Builder, pass_manager: &'a PassManager, module: &'a Module, function: &Function) -> Result<FunctionValue, &'static str> {
+    pub fn compile(context: &'ctx Context, builder: &'a Builder<'ctx>, pass_manager: &'a PassManager, module: &'a Module<'ctx>, function: &Function) -> Result<FunctionValue<'ctx>, &'static str> {
         let mut compiler = Compiler {
             context: context,
             builder: builder,
@@ -1317,17 +1322,15 @@ pub fn main() {
         if is_anonymous {
             let ee = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
 
-            let addr = match ee.get_function_address(name.as_str()) {
-                Ok(addr) => addr,
+            let compiled_fn = match unsafe { ee.get_function::<unsafe extern "C" fn() -> f64>(name.as_str()) } {
+                Ok(f) => f,
                 Err(err) => {
                     println!("!> Error during execution: {:?}", err);
                     continue;
                 }
             };
 
-            let compiled_fn: extern "C" fn() -> f64 = unsafe { std::mem::transmute(addr) };
-
-            println!("=> {}", compiled_fn());
+            println!("=> {}", unsafe { compiled_fn.call() });
         }
     }
 }
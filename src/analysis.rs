@@ -0,0 +1,509 @@
+use llvm_sys::core::{LLVMGetBasicBlockTerminator, LLVMGetNumSuccessors, LLVMGetSuccessor};
+use llvm_sys::prelude::LLVMBasicBlockRef;
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use basic_block::BasicBlock;
+use values::FunctionValue;
+use ModuleMarker;
+
+// Blocks are numbered by their position in a reverse postorder walk of the CFG from
+// the entry block, so the entry block is always 0 and blocks unreachable from the
+// entry have no number at all.
+struct Cfg {
+    blocks: Vec<LLVMBasicBlockRef>,
+    indices: HashMap<LLVMBasicBlockRef, usize>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl Cfg {
+    fn compute(function: &FunctionValue) -> Self {
+        let mut cfg = Cfg {
+            blocks: Vec::new(),
+            indices: HashMap::new(),
+            predecessors: Vec::new(),
+        };
+
+        if function.count_basic_blocks() == 0 {
+            return cfg;
+        }
+
+        let entry = function.get_first_basic_block().expect("Function with basic blocks should have an entry block").basic_block;
+        let mut postorder = Vec::new();
+        let mut visited = HashMap::new();
+        // Each stack entry is a block along with the index of the next successor to visit
+        let mut stack = vec![(entry, 0)];
+
+        visited.insert(entry, ());
+
+        while let Some(&mut (bb, ref mut next)) = stack.last_mut() {
+            let successors = raw_successors(bb);
+
+            if let Some(&successor) = successors.get(*next) {
+                *next += 1;
+
+                if visited.insert(successor, ()).is_none() {
+                    stack.push((successor, 0));
+                }
+            } else {
+                postorder.push(bb);
+                stack.pop();
+            }
+        }
+
+        cfg.blocks = postorder.into_iter().rev().collect();
+        cfg.indices = cfg.blocks.iter().enumerate().map(|(index, &bb)| (bb, index)).collect();
+        cfg.predecessors = vec![Vec::new(); cfg.blocks.len()];
+
+        for (index, &bb) in cfg.blocks.iter().enumerate() {
+            for successor in raw_successors(bb) {
+                let successor = cfg.indices[&successor];
+
+                // Multiple edges to the same block (ie from a switch) are one CFG edge
+                if !cfg.predecessors[successor].contains(&index) {
+                    cfg.predecessors[successor].push(index);
+                }
+            }
+        }
+
+        cfg
+    }
+
+    fn index_of(&self, basic_block: &BasicBlock) -> Option<usize> {
+        self.indices.get(&basic_block.basic_block).cloned()
+    }
+}
+
+fn raw_successors(bb: LLVMBasicBlockRef) -> Vec<LLVMBasicBlockRef> {
+    unsafe {
+        let terminator = LLVMGetBasicBlockTerminator(bb);
+
+        if terminator.is_null() {
+            return Vec::new();
+        }
+
+        (0..LLVMGetNumSuccessors(terminator)).map(|index| LLVMGetSuccessor(terminator, index)).collect()
+    }
+}
+
+/// The dominator tree of a function's control flow graph. A block `a` dominates a
+/// block `b` if every path from the entry block to `b` goes through `a`.
+///
+/// The tree is computed with the iterative algorithm from Cooper, Harvey and Kennedy's
+/// "A Simple, Fast Dominance Algorithm", and is a snapshot: it must be recomputed after
+/// the function's control flow changes.
+///
+/// # Example
+///
+/// ```
+/// use inkwell::IntPredicate;
+/// use inkwell::analysis::DominatorTree;
+/// use inkwell::context::Context;
+///
+/// let context = Context::create();
+/// let module = context.create_module("dominators");
+/// let builder = context.create_builder();
+/// let i32_type = context.i32_type();
+/// let fn_type = i32_type.fn_type(&[&i32_type], false);
+/// let function = module.add_function("abs", &fn_type, None);
+/// let entry = context.append_basic_block(&function, "entry");
+/// let negate = context.append_basic_block(&function, "negate");
+/// let end = context.append_basic_block(&function, "end");
+///
+/// builder.position_at_end(&entry);
+///
+/// let param = function.get_first_param().unwrap().into_int_value();
+/// let zero = i32_type.const_int(0, false);
+/// let is_negative = builder.build_int_compare(IntPredicate::SLT, &param, &zero, "is_negative");
+///
+/// builder.build_conditional_branch(&is_negative, &negate, &end);
+/// builder.position_at_end(&negate);
+/// builder.build_unconditional_branch(&end);
+/// builder.position_at_end(&end);
+/// builder.build_return(Some(&param));
+///
+/// let dominator_tree = DominatorTree::compute(&function);
+///
+/// assert!(dominator_tree.dominates(&entry, &end));
+/// assert!(!dominator_tree.dominates(&negate, &end));
+/// assert_eq!(dominator_tree.get_immediate_dominator(&end).unwrap(), entry);
+/// assert_eq!(dominator_tree.get_dominance_frontier(&negate), vec![end]);
+/// ```
+pub struct DominatorTree<'ctx: 'm, 'm> {
+    cfg: Cfg,
+    idoms: Vec<usize>,
+    children: Vec<Vec<usize>>,
+    frontiers: Vec<Vec<usize>>,
+    // Pre and post order numbers of each block in the tree itself, which make
+    // dominance queries constant time
+    preorder: Vec<usize>,
+    postorder: Vec<usize>,
+    _marker: ModuleMarker<'ctx, 'm>,
+}
+
+impl<'ctx, 'm> DominatorTree<'ctx, 'm> {
+    pub fn compute(function: &FunctionValue<'ctx, 'm>) -> Self {
+        let cfg = Cfg::compute(function);
+        let len = cfg.blocks.len();
+        let mut idoms: Vec<Option<usize>> = vec![None; len];
+
+        if len > 0 {
+            idoms[0] = Some(0);
+        }
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for block in 1..len {
+                let mut new_idom = None;
+
+                for &predecessor in &cfg.predecessors[block] {
+                    if idoms[predecessor].is_none() {
+                        continue;
+                    }
+
+                    new_idom = Some(match new_idom {
+                        Some(new_idom) => intersect(&idoms, predecessor, new_idom),
+                        None => predecessor,
+                    });
+                }
+
+                if new_idom.is_some() && idoms[block] != new_idom {
+                    idoms[block] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        // Every reachable block has a processed predecessor in reverse postorder
+        let idoms: Vec<usize> = idoms.into_iter().map(|idom| idom.expect("Reachable block should have an immediate dominator")).collect();
+        let mut children = vec![Vec::new(); len];
+
+        for block in 1..len {
+            children[idoms[block]].push(block);
+        }
+
+        let mut frontiers = vec![Vec::new(); len];
+
+        for block in 0..len {
+            if cfg.predecessors[block].len() < 2 {
+                continue;
+            }
+
+            for &predecessor in &cfg.predecessors[block] {
+                let mut runner = predecessor;
+
+                while runner != idoms[block] {
+                    if !frontiers[runner].contains(&block) {
+                        frontiers[runner].push(block);
+                    }
+
+                    if runner == 0 {
+                        break;
+                    }
+
+                    runner = idoms[runner];
+                }
+            }
+        }
+
+        let mut preorder = vec![0; len];
+        let mut postorder = vec![0; len];
+
+        if len > 0 {
+            let mut counter = 0;
+            let mut stack = vec![(0, 0)];
+
+            preorder[0] = counter;
+
+            while let Some(&mut (block, ref mut next)) = stack.last_mut() {
+                counter += 1;
+
+                if let Some(&child) = children[block].get(*next) {
+                    *next += 1;
+                    preorder[child] = counter;
+                    stack.push((child, 0));
+                } else {
+                    postorder[block] = counter;
+                    stack.pop();
+                }
+            }
+        }
+
+        DominatorTree {
+            cfg,
+            idoms,
+            children,
+            frontiers,
+            preorder,
+            postorder,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns whether the block is reachable from the function's entry block.
+    pub fn is_reachable(&self, basic_block: &BasicBlock<'ctx, 'm>) -> bool {
+        self.cfg.index_of(basic_block).is_some()
+    }
+
+    /// Returns whether `a` dominates `b`. Every block dominates itself, and as in LLVM,
+    /// a block unreachable from the entry is considered dominated by every block.
+    pub fn dominates(&self, a: &BasicBlock<'ctx, 'm>, b: &BasicBlock<'ctx, 'm>) -> bool {
+        let b = match self.cfg.index_of(b) {
+            Some(b) => b,
+            None => return true,
+        };
+        let a = match self.cfg.index_of(a) {
+            Some(a) => a,
+            None => return false,
+        };
+
+        self.dominates_index(a, b)
+    }
+
+    fn dominates_index(&self, a: usize, b: usize) -> bool {
+        self.preorder[a] <= self.preorder[b] && self.postorder[b] <= self.postorder[a]
+    }
+
+    /// Returns whether `a` dominates `b` and they are different blocks.
+    pub fn strictly_dominates(&self, a: &BasicBlock<'ctx, 'm>, b: &BasicBlock<'ctx, 'm>) -> bool {
+        a != b && self.dominates(a, b)
+    }
+
+    /// Gets the entry block of the function, which is the root of the tree.
+    pub fn get_root(&self) -> Option<BasicBlock<'ctx, 'm>> {
+        self.cfg.blocks.first().and_then(|&bb| BasicBlock::new(bb))
+    }
+
+    /// Gets the closest strict dominator of a block. Returns `None` for the entry
+    /// block and blocks which are unreachable.
+    pub fn get_immediate_dominator(&self, basic_block: &BasicBlock<'ctx, 'm>) -> Option<BasicBlock<'ctx, 'm>> {
+        match self.cfg.index_of(basic_block) {
+            Some(0) | None => None,
+            Some(index) => BasicBlock::new(self.cfg.blocks[self.idoms[index]]),
+        }
+    }
+
+    /// Gets the blocks which the given block immediately dominates.
+    pub fn get_children(&self, basic_block: &BasicBlock<'ctx, 'm>) -> Vec<BasicBlock<'ctx, 'm>> {
+        self.to_basic_blocks(basic_block, &self.children)
+    }
+
+    /// Gets the dominance frontier of a block: the blocks which it doesn't strictly
+    /// dominate, but which have a predecessor it does dominate. These are where phi
+    /// nodes are needed for values defined in the block.
+    pub fn get_dominance_frontier(&self, basic_block: &BasicBlock<'ctx, 'm>) -> Vec<BasicBlock<'ctx, 'm>> {
+        self.to_basic_blocks(basic_block, &self.frontiers)
+    }
+
+    fn to_basic_blocks(&self, basic_block: &BasicBlock<'ctx, 'm>, indices: &[Vec<usize>]) -> Vec<BasicBlock<'ctx, 'm>> {
+        match self.cfg.index_of(basic_block) {
+            Some(index) => indices[index].iter().filter_map(|&index| BasicBlock::new(self.cfg.blocks[index])).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+// Walks two blocks up the partially built tree until they meet, relying on a
+// dominator always coming before the blocks it dominates in reverse postorder
+fn intersect(idoms: &[Option<usize>], mut finger1: usize, mut finger2: usize) -> usize {
+    while finger1 != finger2 {
+        while finger1 > finger2 {
+            finger1 = idoms[finger1].expect("Processed block should have an immediate dominator");
+        }
+
+        while finger2 > finger1 {
+            finger2 = idoms[finger2].expect("Processed block should have an immediate dominator");
+        }
+    }
+
+    finger1
+}
+
+/// A natural loop: a header block which dominates every block in the loop, and the
+/// blocks which can reach one of the loop's back edges to the header without going
+/// through the header.
+pub struct Loop<'ctx: 'm, 'm> {
+    header: LLVMBasicBlockRef,
+    blocks: Vec<LLVMBasicBlockRef>,
+    latches: Vec<LLVMBasicBlockRef>,
+    parent: Option<usize>,
+    depth: u32,
+    _marker: ModuleMarker<'ctx, 'm>,
+}
+
+/// The natural loops of a function and how they nest, discovered from the back
+/// edges of a `DominatorTree`. Like the tree, this is a snapshot of the function.
+///
+/// # Example
+///
+/// ```
+/// use inkwell::IntPredicate;
+/// use inkwell::analysis::{DominatorTree, LoopInfo};
+/// use inkwell::context::Context;
+///
+/// let context = Context::create();
+/// let module = context.create_module("loops");
+/// let builder = context.create_builder();
+/// let void_type = context.void_type();
+/// let i32_type = context.i32_type();
+/// let fn_type = void_type.fn_type(&[&i32_type], false);
+/// let function = module.add_function("count_down", &fn_type, None);
+/// let entry = context.append_basic_block(&function, "entry");
+/// let body = context.append_basic_block(&function, "body");
+/// let end = context.append_basic_block(&function, "end");
+///
+/// builder.position_at_end(&entry);
+/// builder.build_unconditional_branch(&body);
+/// builder.position_at_end(&body);
+///
+/// let param = function.get_first_param().unwrap().into_int_value();
+/// let zero = i32_type.const_int(0, false);
+/// let done = builder.build_int_compare(IntPredicate::EQ, &param, &zero, "done");
+///
+/// builder.build_conditional_branch(&done, &end, &body);
+/// builder.position_at_end(&end);
+/// builder.build_return(None);
+///
+/// let dominator_tree = DominatorTree::compute(&function);
+/// let loop_info = LoopInfo::compute(&dominator_tree);
+///
+/// assert_eq!(loop_info.get_loop_depth(&body), 1);
+/// assert_eq!(loop_info.get_loop_depth(&end), 0);
+/// assert_eq!(loop_info.get_loop_for(&body).unwrap().get_header(), body);
+/// ```
+pub struct LoopInfo<'ctx: 'm, 'm> {
+    loops: Vec<Loop<'ctx, 'm>>,
+    // The innermost loop containing each block
+    innermost: HashMap<LLVMBasicBlockRef, usize>,
+}
+
+impl<'ctx, 'm> LoopInfo<'ctx, 'm> {
+    pub fn compute(dominator_tree: &DominatorTree<'ctx, 'm>) -> Self {
+        let cfg = &dominator_tree.cfg;
+        let len = cfg.blocks.len();
+        let mut loop_blocks: Vec<(usize, Vec<usize>, Vec<usize>)> = Vec::new();
+
+        // Headers come before their latches in reverse postorder, so looking at them
+        // in order also finds outer loops before the loops nested inside them
+        for header in 0..len {
+            let latches: Vec<usize> = cfg.predecessors[header].iter().cloned().filter(|&predecessor| dominator_tree.dominates_index(header, predecessor)).collect();
+
+            if latches.is_empty() {
+                continue;
+            }
+
+            let mut in_loop = vec![false; len];
+            let mut worklist = latches.clone();
+
+            in_loop[header] = true;
+
+            while let Some(block) = worklist.pop() {
+                if in_loop[block] {
+                    continue;
+                }
+
+                in_loop[block] = true;
+                worklist.extend(cfg.predecessors[block].iter().cloned().filter(|&predecessor| !in_loop[predecessor]));
+            }
+
+            let blocks = (0..len).filter(|&block| in_loop[block]).collect();
+
+            loop_blocks.push((header, blocks, latches));
+        }
+
+        let mut loops: Vec<Loop<'ctx, 'm>> = Vec::with_capacity(loop_blocks.len());
+
+        // A loop's parent is the smallest other loop containing its header. Since
+        // outer loops come first, each parent's depth is final before it's needed
+        for (index, &(header, ref blocks, ref latches)) in loop_blocks.iter().enumerate() {
+            let parent = (0..index).filter(|&other| loop_blocks[other].1.binary_search(&header).is_ok())
+                                   .min_by_key(|&other| loop_blocks[other].1.len());
+            let depth = parent.map_or(1, |parent| loops[parent].depth + 1);
+
+            loops.push(Loop {
+                header: cfg.blocks[header],
+                blocks: blocks.iter().map(|&block| cfg.blocks[block]).collect(),
+                latches: latches.iter().map(|&block| cfg.blocks[block]).collect(),
+                parent,
+                depth,
+                _marker: PhantomData,
+            });
+        }
+
+        let mut innermost = HashMap::new();
+
+        for (index, loop_) in loops.iter().enumerate() {
+            for &block in &loop_.blocks {
+                let current = innermost.entry(block).or_insert(index);
+
+                if loop_.blocks.len() < loops[*current].blocks.len() {
+                    *current = index;
+                }
+            }
+        }
+
+        LoopInfo {
+            loops,
+            innermost,
+        }
+    }
+
+    /// Gets every loop in the function, with outer loops before the loops nested inside them.
+    pub fn get_loops(&self) -> &[Loop<'ctx, 'm>] {
+        &self.loops
+    }
+
+    /// Gets the loops which aren't nested inside any other loop.
+    pub fn get_top_level_loops(&self) -> Vec<&Loop<'ctx, 'm>> {
+        self.loops.iter().filter(|loop_| loop_.parent.is_none()).collect()
+    }
+
+    /// Gets the loop which the given loop is directly nested in, if any.
+    pub fn get_parent_loop(&self, loop_: &Loop<'ctx, 'm>) -> Option<&Loop<'ctx, 'm>> {
+        loop_.parent.map(|parent| &self.loops[parent])
+    }
+
+    /// Gets the innermost loop containing the given block, if any.
+    pub fn get_loop_for(&self, basic_block: &BasicBlock<'ctx, 'm>) -> Option<&Loop<'ctx, 'm>> {
+        self.innermost.get(&basic_block.basic_block).map(|&loop_| &self.loops[loop_])
+    }
+
+    /// Gets the number of loops containing the given block, which is zero outside of any loop.
+    pub fn get_loop_depth(&self, basic_block: &BasicBlock<'ctx, 'm>) -> u32 {
+        self.get_loop_for(basic_block).map_or(0, |loop_| loop_.depth)
+    }
+
+    /// Returns whether the block is the header of a loop.
+    pub fn is_loop_header(&self, basic_block: &BasicBlock<'ctx, 'm>) -> bool {
+        self.get_loop_for(basic_block).is_some_and(|loop_| loop_.header == basic_block.basic_block)
+    }
+}
+
+impl<'ctx, 'm> Loop<'ctx, 'm> {
+    pub fn get_header(&self) -> BasicBlock<'ctx, 'm> {
+        BasicBlock::new(self.header).expect("Loop header should always be valid")
+    }
+
+    /// Gets the blocks of this loop, including those of any nested loops, in reverse postorder.
+    pub fn get_blocks(&self) -> Vec<BasicBlock<'ctx, 'm>> {
+        self.blocks.iter().filter_map(|&bb| BasicBlock::new(bb)).collect()
+    }
+
+    /// Gets the blocks with a back edge to the header.
+    pub fn get_latches(&self) -> Vec<BasicBlock<'ctx, 'm>> {
+        self.latches.iter().filter_map(|&bb| BasicBlock::new(bb)).collect()
+    }
+
+    pub fn contains(&self, basic_block: &BasicBlock<'ctx, 'm>) -> bool {
+        self.blocks.contains(&basic_block.basic_block)
+    }
+
+    /// Gets the number of loops this loop is nested in, counting itself.
+    pub fn get_depth(&self) -> u32 {
+        self.depth
+    }
+}
@@ -0,0 +1,248 @@
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use llvm_sys::core::{LLVMGetEnumAttributeKindForName, LLVMGetLastEnumAttributeKind, LLVMGetEnumAttributeKind, LLVMGetEnumAttributeValue, LLVMGetStringAttributeKind, LLVMGetStringAttributeValue, LLVMIsEnumAttribute, LLVMIsStringAttribute};
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use llvm_sys::prelude::LLVMAttributeRef;
+#[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+use llvm_sys::LLVMAttribute;
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use std::slice;
+
+/// An `Attribute` is either an enum attribute such as `noinline` or `dereferenceable(8)`,
+/// identified by a kind id and an integer value, or a string attribute, which is an
+/// arbitrary key/value pair such as `"no-frame-pointer-elim"="true"`.
+///
+/// Attributes are created with `Context::create_enum_attribute` and
+/// `Context::create_string_attribute`.
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Attribute {
+    pub(crate) attribute: LLVMAttributeRef,
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+impl Attribute {
+    pub(crate) fn new(attribute: LLVMAttributeRef) -> Self {
+        assert!(!attribute.is_null());
+
+        Attribute {
+            attribute,
+        }
+    }
+
+    pub fn is_enum(&self) -> bool {
+        unsafe {
+            LLVMIsEnumAttribute(self.attribute) == 1
+        }
+    }
+
+    pub fn is_string(&self) -> bool {
+        unsafe {
+            LLVMIsStringAttribute(self.attribute) == 1
+        }
+    }
+
+    /// Gets the kind id of an enum attribute from its name, such as "noinline",
+    /// or 0 if no such attribute exists.
+    pub fn get_named_enum_kind_id(name: &str) -> u32 {
+        unsafe {
+            LLVMGetEnumAttributeKindForName(name.as_ptr() as *const i8, name.len())
+        }
+    }
+
+    pub fn get_last_enum_kind_id() -> u32 {
+        unsafe {
+            LLVMGetLastEnumAttributeKind()
+        }
+    }
+
+    // SubTypes: Only apply to enum attributes
+    pub fn get_enum_kind_id(&self) -> u32 {
+        assert!(self.is_enum());
+
+        unsafe {
+            LLVMGetEnumAttributeKind(self.attribute)
+        }
+    }
+
+    // SubTypes: Only apply to enum attributes
+    pub fn get_enum_value(&self) -> u64 {
+        assert!(self.is_enum());
+
+        unsafe {
+            LLVMGetEnumAttributeValue(self.attribute)
+        }
+    }
+
+    // SubTypes: Only apply to string attributes
+    // The returned bytes are not nul terminated
+    pub fn get_string_kind_id(&self) -> &[u8] {
+        assert!(self.is_string());
+
+        let mut length = 0;
+        let ptr = unsafe {
+            LLVMGetStringAttributeKind(self.attribute, &mut length)
+        };
+
+        unsafe {
+            slice::from_raw_parts(ptr as *const u8, length as usize)
+        }
+    }
+
+    // SubTypes: Only apply to string attributes
+    // The returned bytes are not nul terminated
+    pub fn get_string_value(&self) -> &[u8] {
+        assert!(self.is_string());
+
+        let mut length = 0;
+        let ptr = unsafe {
+            LLVMGetStringAttributeValue(self.attribute, &mut length)
+        };
+
+        unsafe {
+            slice::from_raw_parts(ptr as *const u8, length as usize)
+        }
+    }
+}
+
+/// The kinds of enum attribute which can be applied through `add_attribute_kind`. Those
+/// which LLVM 3.7 and 3.8 can't express through their C API are only available from LLVM 3.9.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AttributeKind {
+    ZExt,
+    SExt,
+    NoReturn,
+    InReg,
+    StructRet,
+    NoUnwind,
+    NoAlias,
+    ByVal,
+    Nest,
+    ReadNone,
+    ReadOnly,
+    NoInline,
+    AlwaysInline,
+    OptimizeForSize,
+    StackProtect,
+    StackProtectReq,
+    NoCapture,
+    NoRedZone,
+    NoImplicitFloat,
+    Naked,
+    InlineHint,
+    ReturnsTwice,
+    UWTable,
+    NonLazyBind,
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    NonNull,
+    /// The number of bytes known to be dereferenceable through a pointer.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    Dereferenceable(u64),
+}
+
+impl AttributeKind {
+    /// Gets the name of this attribute as it appears in textual IR, such as "noinline".
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            AttributeKind::ZExt => "zeroext",
+            AttributeKind::SExt => "signext",
+            AttributeKind::NoReturn => "noreturn",
+            AttributeKind::InReg => "inreg",
+            AttributeKind::StructRet => "sret",
+            AttributeKind::NoUnwind => "nounwind",
+            AttributeKind::NoAlias => "noalias",
+            AttributeKind::ByVal => "byval",
+            AttributeKind::Nest => "nest",
+            AttributeKind::ReadNone => "readnone",
+            AttributeKind::ReadOnly => "readonly",
+            AttributeKind::NoInline => "noinline",
+            AttributeKind::AlwaysInline => "alwaysinline",
+            AttributeKind::OptimizeForSize => "optsize",
+            AttributeKind::StackProtect => "ssp",
+            AttributeKind::StackProtectReq => "sspreq",
+            AttributeKind::NoCapture => "nocapture",
+            AttributeKind::NoRedZone => "noredzone",
+            AttributeKind::NoImplicitFloat => "noimplicitfloat",
+            AttributeKind::Naked => "naked",
+            AttributeKind::InlineHint => "inlinehint",
+            AttributeKind::ReturnsTwice => "returns_twice",
+            AttributeKind::UWTable => "uwtable",
+            AttributeKind::NonLazyBind => "nonlazybind",
+            #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+            AttributeKind::NonNull => "nonnull",
+            #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+            AttributeKind::Dereferenceable(_) => "dereferenceable",
+        }
+    }
+
+    /// Gets the kind id of this attribute, for use with `Context::create_enum_attribute`.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_kind_id(&self) -> u32 {
+        Attribute::get_named_enum_kind_id(self.get_name())
+    }
+
+    /// Gets the integer value of this attribute, which is 0 unless it carries one,
+    /// such as the byte count of `dereferenceable`.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_value(&self) -> u64 {
+        match *self {
+            AttributeKind::Dereferenceable(bytes) => bytes,
+            _ => 0,
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    pub(crate) fn as_llvm_attribute(&self) -> LLVMAttribute {
+        use llvm_sys::*;
+
+        match *self {
+            AttributeKind::ZExt => LLVMZExtAttribute,
+            AttributeKind::SExt => LLVMSExtAttribute,
+            AttributeKind::NoReturn => LLVMNoReturnAttribute,
+            AttributeKind::InReg => LLVMInRegAttribute,
+            AttributeKind::StructRet => LLVMStructRetAttribute,
+            AttributeKind::NoUnwind => LLVMNoUnwindAttribute,
+            AttributeKind::NoAlias => LLVMNoAliasAttribute,
+            AttributeKind::ByVal => LLVMByValAttribute,
+            AttributeKind::Nest => LLVMNestAttribute,
+            AttributeKind::ReadNone => LLVMReadNoneAttribute,
+            AttributeKind::ReadOnly => LLVMReadOnlyAttribute,
+            AttributeKind::NoInline => LLVMNoInlineAttribute,
+            AttributeKind::AlwaysInline => LLVMAlwaysInlineAttribute,
+            AttributeKind::OptimizeForSize => LLVMOptimizeForSizeAttribute,
+            AttributeKind::StackProtect => LLVMStackProtectAttribute,
+            AttributeKind::StackProtectReq => LLVMStackProtectReqAttribute,
+            AttributeKind::NoCapture => LLVMNoCaptureAttribute,
+            AttributeKind::NoRedZone => LLVMNoRedZoneAttribute,
+            AttributeKind::NoImplicitFloat => LLVMNoImplicitFloatAttribute,
+            AttributeKind::Naked => LLVMNakedAttribute,
+            AttributeKind::InlineHint => LLVMInlineHintAttribute,
+            AttributeKind::ReturnsTwice => LLVMReturnsTwice,
+            AttributeKind::UWTable => LLVMUWTable,
+            AttributeKind::NonLazyBind => LLVMNonLazyBind,
+        }
+    }
+}
+
+/// Where an attribute applies: to the return value, to a parameter (zero indexed)
+/// or to the function itself.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AttributeLoc {
+    Return,
+    Param(u32),
+    Function,
+}
+
+impl AttributeLoc {
+    pub(crate) fn get_index(&self) -> u32 {
+        match *self {
+            AttributeLoc::Return => 0,
+            AttributeLoc::Param(index) => {
+                assert!(index <= u32::MAX - 2, "Param index must be <= u32::MAX - 2");
+
+                index + 1
+            },
+            AttributeLoc::Function => u32::MAX,
+        }
+    }
+}
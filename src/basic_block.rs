@@ -1,21 +1,26 @@
-use llvm_sys::core::{LLVMGetBasicBlockParent, LLVMGetBasicBlockTerminator, LLVMGetNextBasicBlock, LLVMInsertBasicBlock, LLVMIsABasicBlock, LLVMIsConstant, LLVMMoveBasicBlockAfter, LLVMMoveBasicBlockBefore, LLVMPrintTypeToString, LLVMPrintValueToString, LLVMTypeOf, LLVMDeleteBasicBlock, LLVMGetPreviousBasicBlock, LLVMRemoveBasicBlockFromParent, LLVMGetFirstInstruction, LLVMGetLastInstruction, LLVMGetTypeContext, LLVMBasicBlockAsValue};
-use llvm_sys::prelude::{LLVMValueRef, LLVMBasicBlockRef};
+use llvm_sys::core::{LLVMGetBasicBlockParent, LLVMGetBasicBlockTerminator, LLVMGetNextBasicBlock, LLVMInsertBasicBlockInContext, LLVMIsABasicBlock, LLVMIsConstant, LLVMMoveBasicBlockAfter, LLVMMoveBasicBlockBefore, LLVMPrintTypeToString, LLVMPrintValueToString, LLVMTypeOf, LLVMDeleteBasicBlock, LLVMGetPreviousBasicBlock, LLVMRemoveBasicBlockFromParent, LLVMGetFirstInstruction, LLVMGetLastInstruction, LLVMGetNextInstruction, LLVMGetPreviousInstruction, LLVMGetNumSuccessors, LLVMGetSuccessor, LLVMGetFirstUse, LLVMGetNextUse, LLVMGetUser, LLVMIsATerminatorInst, LLVMGetInstructionParent, LLVMGetTypeContext, LLVMBasicBlockAsValue};
+use llvm_sys::prelude::{LLVMValueRef, LLVMBasicBlockRef, LLVMUseRef};
 
 use context::{Context, ContextRef};
+use support::LLVMString;
+use ModuleMarker;
 use values::{FunctionValue, InstructionValue};
 
 use std::fmt;
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::ptr;
 use std::rc::Rc;
 
 // Apparently BasicBlocks count as LabelTypeKinds, which is
 // why they're allow to be casted to values?
 #[derive(PartialEq, Eq)]
-pub struct BasicBlock {
+pub struct BasicBlock<'ctx: 'm, 'm> {
     pub(crate) basic_block: LLVMBasicBlockRef,
+    _marker: ModuleMarker<'ctx, 'm>,
 }
 
-impl BasicBlock {
+impl<'ctx, 'm> BasicBlock<'ctx, 'm> {
     pub(crate) fn new(basic_block: LLVMBasicBlockRef) -> Option<Self> {
         if basic_block.is_null() {
             return None;
@@ -25,10 +30,10 @@ impl BasicBlock {
             assert!(!LLVMIsABasicBlock(basic_block as LLVMValueRef).is_null()) // NOTE: There is a LLVMBasicBlockAsValue but it might be the same as casting
         }
 
-        Some(BasicBlock { basic_block })
+        Some(BasicBlock { basic_block, _marker: PhantomData })
     }
 
-    pub fn get_parent(&self) -> Option<FunctionValue> {
+    pub fn get_parent(&self) -> Option<FunctionValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetBasicBlockParent(self.basic_block)
         };
@@ -36,7 +41,7 @@ impl BasicBlock {
         FunctionValue::new(value)
     }
 
-    pub fn get_previous_basic_block(&self) -> Option<BasicBlock> {
+    pub fn get_previous_basic_block(&self) -> Option<BasicBlock<'ctx, 'm>> {
         let bb = unsafe {
             LLVMGetPreviousBasicBlock(self.basic_block)
         };
@@ -44,7 +49,7 @@ impl BasicBlock {
         BasicBlock::new(bb)
     }
 
-    pub fn get_next_basic_block(&self) -> Option<BasicBlock> {
+    pub fn get_next_basic_block(&self) -> Option<BasicBlock<'ctx, 'm>> {
         let bb = unsafe {
             LLVMGetNextBasicBlock(self.basic_block)
         };
@@ -54,7 +59,7 @@ impl BasicBlock {
 
     // REVIEW: If we wanted the return type could be Option<Either<BasicValueEnum, InstructionValue>>
     // if getting a value over an instruction is preferable
-    pub fn get_terminator(&self) -> Option<InstructionValue> {
+    pub fn get_terminator(&self) -> Option<InstructionValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetBasicBlockTerminator(self.basic_block)
         };
@@ -66,29 +71,29 @@ impl BasicBlock {
         Some(InstructionValue::new(value))
     }
 
-    pub fn move_before(&self, basic_block: &BasicBlock) {
+    pub fn move_before(&self, basic_block: &BasicBlock<'ctx, 'm>) {
         unsafe {
             LLVMMoveBasicBlockBefore(self.basic_block, basic_block.basic_block)
         }
     }
 
-    pub fn move_after(&self, basic_block: &BasicBlock) {
+    pub fn move_after(&self, basic_block: &BasicBlock<'ctx, 'm>) {
         unsafe {
             LLVMMoveBasicBlockAfter(self.basic_block, basic_block.basic_block)
         }
     }
 
-    pub fn prepend_basic_block(&self, name: &str) -> BasicBlock {
+    pub fn prepend_basic_block(&self, name: &str) -> BasicBlock<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let bb = unsafe {
-            LLVMInsertBasicBlock(self.basic_block, c_string.as_ptr())
+            LLVMInsertBasicBlockInContext(LLVMGetTypeContext(LLVMTypeOf(LLVMBasicBlockAsValue(self.basic_block))), self.basic_block, c_string.as_ptr())
         };
 
         BasicBlock::new(bb).expect("Prepending basic block should never fail")
     }
 
-    pub fn get_first_instruction(&self) -> Option<InstructionValue> {
+    pub fn get_first_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetFirstInstruction(self.basic_block)
         };
@@ -100,7 +105,7 @@ impl BasicBlock {
         Some(InstructionValue::new(value))
     }
 
-    pub fn get_last_instruction(&self) -> Option<InstructionValue> {
+    pub fn get_last_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetLastInstruction(self.basic_block)
         };
@@ -112,6 +117,155 @@ impl BasicBlock {
         Some(InstructionValue::new(value))
     }
 
+    /// Gets an iterator over the instructions in this `BasicBlock`. The next
+    /// instruction is looked up before the current one is yielded, so it is
+    /// fine to erase instructions while iterating.
+    pub fn instructions(&self) -> InstructionIter<'ctx, 'm> {
+        let (first, last) = unsafe {
+            (LLVMGetFirstInstruction(self.basic_block), LLVMGetLastInstruction(self.basic_block))
+        };
+
+        InstructionIter {
+            front: first,
+            back: last,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the `BasicBlock`s this block's terminator may branch to.
+    /// A block which branches to the same successor more than once yields it for each edge.
+    pub fn successors(&self) -> SuccessorIter<'ctx, 'm> {
+        let terminator = unsafe {
+            LLVMGetBasicBlockTerminator(self.basic_block)
+        };
+        let num_successors = if terminator.is_null() {
+            0
+        } else {
+            unsafe {
+                LLVMGetNumSuccessors(terminator)
+            }
+        };
+
+        SuccessorIter {
+            terminator,
+            index: 0,
+            num_successors,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the `BasicBlock`s whose terminators may branch to this block.
+    /// A predecessor which branches here more than once yields itself for each edge.
+    pub fn predecessors(&self) -> PredecessorIter<'ctx, 'm> {
+        let use_ = unsafe {
+            LLVMGetFirstUse(LLVMBasicBlockAsValue(self.basic_block))
+        };
+
+        PredecessorIter {
+            use_,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Splits this `BasicBlock` in two at the given instruction. The instruction and
+    /// everything after it are moved into a new block inserted directly after this one,
+    /// and this block is terminated by an unconditional branch to the new block.
+    /// Phi nodes in the successors are updated to refer to the new block.
+    ///
+    /// Returns `None` if this block has no parent function or terminator, or if the
+    /// instruction isn't a non-phi instruction of this block.
+    ///
+    /// Only available with the `llvm3-9` and `llvm4-0` features, since the C API
+    /// can't detach an instruction from its block (`LLVMInstructionRemoveFromParent`)
+    /// before LLVM 3.9.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("split");
+    /// let builder = context.create_builder();
+    /// let i32_type = context.i32_type();
+    /// let fn_type = i32_type.fn_type(&[&i32_type], false);
+    /// let function = module.add_function("double", &fn_type, None);
+    /// let entry = context.append_basic_block(&function, "entry");
+    ///
+    /// builder.position_at_end(&entry);
+    ///
+    /// let param = function.get_first_param().unwrap().into_int_value();
+    /// let sum = builder.build_int_add(&param, &param, "sum");
+    ///
+    /// builder.build_return(Some(&sum));
+    ///
+    /// let tail = entry.split_at(&sum.as_instruction().unwrap(), "tail").unwrap();
+    ///
+    /// assert_eq!(entry.successors().next().unwrap(), tail);
+    /// assert_eq!(tail.predecessors().next().unwrap(), entry);
+    /// assert_eq!(tail.get_first_instruction().unwrap(), sum.as_instruction().unwrap());
+    /// ```
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn split_at(&self, instruction: &InstructionValue<'ctx, 'm>, name: &str) -> Option<BasicBlock<'ctx, 'm>> {
+        use llvm_sys::core::{LLVMAppendBasicBlockInContext, LLVMBuildBr, LLVMCreateBuilderInContext, LLVMDisposeBuilder, LLVMGetNextInstruction, LLVMGetValueName, LLVMInsertBasicBlockInContext, LLVMInsertIntoBuilderWithName, LLVMInstructionRemoveFromParent, LLVMPositionBuilderAtEnd, LLVMReplaceAllUsesWith};
+        use std::ffi::CStr;
+        use values::{AsValueRef, InstructionOpcode};
+
+        let parent = self.get_parent()?;
+
+        if self.get_terminator().is_none() || instruction.get_parent().as_ref() != Some(self) {
+            return None;
+        }
+
+        if instruction.get_opcode() == InstructionOpcode::Phi {
+            return None;
+        }
+
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+        let block_value = unsafe {
+            LLVMBasicBlockAsValue(self.basic_block)
+        };
+
+        unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(block_value));
+            let next_bb = LLVMGetNextBasicBlock(self.basic_block);
+            let new_bb = if next_bb.is_null() {
+                LLVMAppendBasicBlockInContext(context, parent.as_value_ref(), c_string.as_ptr())
+            } else {
+                LLVMInsertBasicBlockInContext(context, next_bb, c_string.as_ptr())
+            };
+            let new_block_value = LLVMBasicBlockAsValue(new_bb);
+
+            // Replacing a block's uses also rewrites the phi nodes of its successors, so
+            // round tripping through the still empty new block leaves every branch pointing
+            // here while the successors' phi nodes now refer to the new block
+            LLVMReplaceAllUsesWith(block_value, new_block_value);
+            LLVMReplaceAllUsesWith(new_block_value, block_value);
+
+            let builder = LLVMCreateBuilderInContext(context);
+            let mut current = instruction.as_value_ref();
+
+            LLVMPositionBuilderAtEnd(builder, new_bb);
+
+            while !current.is_null() {
+                let next = LLVMGetNextInstruction(current);
+                // Inserting into a builder overwrites the name, so it must be copied first
+                let name = CStr::from_ptr(LLVMGetValueName(current)).to_owned();
+
+                LLVMInstructionRemoveFromParent(current);
+                LLVMInsertIntoBuilderWithName(builder, current, name.as_ptr());
+
+                current = next;
+            }
+
+            LLVMPositionBuilderAtEnd(builder, self.basic_block);
+            LLVMBuildBr(builder, new_bb);
+            LLVMDisposeBuilder(builder);
+
+            BasicBlock::new(new_bb)
+        }
+    }
+
     // SubTypes: Don't need to call get_parent for a BasicBlock<HasParent>
     pub fn remove_from_function(&self) {
         // This method is UB if the parent no longer exists, so we must check for parent (or encode into type system)
@@ -129,7 +283,7 @@ impl BasicBlock {
         // }
     }
 
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         let context = unsafe {
             LLVMGetTypeContext(LLVMTypeOf(LLVMBasicBlockAsValue(self.basic_block)))
         };
@@ -139,13 +293,13 @@ impl BasicBlock {
     }
 }
 
-impl fmt::Debug for BasicBlock {
+impl<'ctx, 'm> fmt::Debug for BasicBlock<'ctx, 'm> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let llvm_value = unsafe {
-            CStr::from_ptr(LLVMPrintValueToString(self.basic_block as LLVMValueRef))
+            LLVMString::new(LLVMPrintValueToString(self.basic_block as LLVMValueRef))
         };
         let llvm_type = unsafe {
-            CStr::from_ptr(LLVMPrintTypeToString(LLVMTypeOf(self.basic_block as LLVMValueRef)))
+            LLVMString::new(LLVMPrintTypeToString(LLVMTypeOf(self.basic_block as LLVMValueRef)))
         };
         let is_const = unsafe {
             LLVMIsConstant(self.basic_block as LLVMValueRef) == 1
@@ -154,3 +308,114 @@ impl fmt::Debug for BasicBlock {
         write!(f, "BasicBlock {{\n    address: {:?}\n    is_const: {:?}\n    llvm_value: {:?}\n    llvm_type: {:?}\n}}", self.basic_block, is_const, llvm_value, llvm_type)
     }
 }
+
+pub struct InstructionIter<'ctx: 'm, 'm> {
+    front: LLVMValueRef,
+    back: LLVMValueRef,
+    _marker: ModuleMarker<'ctx, 'm>,
+}
+
+impl<'ctx, 'm> Iterator for InstructionIter<'ctx, 'm> {
+    type Item = InstructionValue<'ctx, 'm>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front.is_null() {
+            return None;
+        }
+
+        let instruction = self.front;
+
+        if instruction == self.back {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.front = unsafe {
+                LLVMGetNextInstruction(instruction)
+            };
+        }
+
+        Some(InstructionValue::new(instruction))
+    }
+}
+
+impl<'ctx, 'm> DoubleEndedIterator for InstructionIter<'ctx, 'm> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.back.is_null() {
+            return None;
+        }
+
+        let instruction = self.back;
+
+        if instruction == self.front {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.back = unsafe {
+                LLVMGetPreviousInstruction(instruction)
+            };
+        }
+
+        Some(InstructionValue::new(instruction))
+    }
+}
+
+pub struct SuccessorIter<'ctx: 'm, 'm> {
+    terminator: LLVMValueRef,
+    index: u32,
+    num_successors: u32,
+    _marker: ModuleMarker<'ctx, 'm>,
+}
+
+impl<'ctx, 'm> Iterator for SuccessorIter<'ctx, 'm> {
+    type Item = BasicBlock<'ctx, 'm>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.num_successors {
+            return None;
+        }
+
+        let bb = unsafe {
+            LLVMGetSuccessor(self.terminator, self.index)
+        };
+
+        self.index += 1;
+
+        BasicBlock::new(bb)
+    }
+}
+
+pub struct PredecessorIter<'ctx: 'm, 'm> {
+    use_: LLVMUseRef,
+    _marker: ModuleMarker<'ctx, 'm>,
+}
+
+impl<'ctx, 'm> Iterator for PredecessorIter<'ctx, 'm> {
+    type Item = BasicBlock<'ctx, 'm>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Besides terminators, a block may be used by blockaddress constants
+        while !self.use_.is_null() {
+            let user = unsafe {
+                LLVMGetUser(self.use_)
+            };
+
+            self.use_ = unsafe {
+                LLVMGetNextUse(self.use_)
+            };
+
+            let is_terminator = unsafe {
+                !LLVMIsATerminatorInst(user).is_null()
+            };
+
+            if is_terminator {
+                let bb = unsafe {
+                    LLVMGetInstructionParent(user)
+                };
+
+                return BasicBlock::new(bb);
+            }
+        }
+
+        None
+    }
+}
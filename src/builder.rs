@@ -1,40 +1,90 @@
-use either::Either;
-use llvm_sys::core::{LLVMBuildAdd, LLVMBuildAlloca, LLVMBuildAnd, LLVMBuildArrayAlloca, LLVMBuildArrayMalloc, LLVMBuildBr, LLVMBuildCall, LLVMBuildCast, LLVMBuildCondBr, LLVMBuildExtractValue, LLVMBuildFAdd, LLVMBuildFCmp, LLVMBuildFDiv, LLVMBuildFence, LLVMBuildFMul, LLVMBuildFNeg, LLVMBuildFree, LLVMBuildFSub, LLVMBuildGEP, LLVMBuildICmp, LLVMBuildInsertValue, LLVMBuildIsNotNull, LLVMBuildIsNull, LLVMBuildLoad, LLVMBuildMalloc, LLVMBuildMul, LLVMBuildNeg, LLVMBuildNot, LLVMBuildOr, LLVMBuildPhi, LLVMBuildPointerCast, LLVMBuildRet, LLVMBuildRetVoid, LLVMBuildStore, LLVMBuildSub, LLVMBuildUDiv, LLVMBuildUnreachable, LLVMBuildXor, LLVMDisposeBuilder, LLVMGetElementType, LLVMGetInsertBlock, LLVMGetReturnType, LLVMGetTypeKind, LLVMInsertIntoBuilder, LLVMPositionBuilderAtEnd, LLVMTypeOf, LLVMSetTailCall, LLVMBuildExtractElement, LLVMBuildInsertElement, LLVMBuildIntToPtr, LLVMBuildPtrToInt, LLVMInsertIntoBuilderWithName, LLVMClearInsertionPosition, LLVMCreateBuilder, LLVMPositionBuilder, LLVMPositionBuilderBefore, LLVMBuildAggregateRet, LLVMBuildStructGEP, LLVMBuildInBoundsGEP, LLVMBuildPtrDiff, LLVMBuildNSWAdd, LLVMBuildNUWAdd, LLVMBuildNSWSub, LLVMBuildNUWSub, LLVMBuildNSWMul, LLVMBuildNUWMul, LLVMBuildSDiv, LLVMBuildSRem, LLVMBuildURem, LLVMBuildFRem, LLVMBuildNSWNeg, LLVMBuildNUWNeg, LLVMBuildFPToUI, LLVMBuildFPToSI, LLVMBuildSIToFP, LLVMBuildUIToFP, LLVMBuildFPTrunc, LLVMBuildFPExt, LLVMBuildIntCast, LLVMBuildFPCast, LLVMBuildSExtOrBitCast, LLVMBuildZExtOrBitCast, LLVMBuildTruncOrBitCast, LLVMBuildSwitch, LLVMAddCase, LLVMBuildShl, LLVMBuildAShr, LLVMBuildLShr, LLVMBuildGlobalString, LLVMBuildGlobalStringPtr, LLVMBuildExactSDiv, LLVMBuildTrunc, LLVMBuildSExt, LLVMBuildZExt};
-use llvm_sys::prelude::{LLVMBuilderRef, LLVMValueRef};
-use llvm_sys::{LLVMOpcode, LLVMTypeKind, LLVMAtomicOrdering};
+use llvm_sys::core::{LLVMBuildAdd, LLVMBuildAlloca, LLVMBuildAnd, LLVMBuildArrayAlloca, LLVMBuildArrayMalloc, LLVMBuildBr, LLVMBuildCall, LLVMBuildCast, LLVMBuildCondBr, LLVMBuildExtractValue, LLVMBuildFAdd, LLVMBuildFCmp, LLVMBuildFDiv, LLVMBuildFence, LLVMBuildFMul, LLVMBuildFNeg, LLVMBuildFree, LLVMBuildFSub, LLVMBuildGEP, LLVMBuildICmp, LLVMBuildInsertValue, LLVMBuildIsNotNull, LLVMBuildIsNull, LLVMBuildLoad, LLVMBuildMalloc, LLVMBuildMul, LLVMBuildNeg, LLVMBuildNot, LLVMBuildOr, LLVMBuildPhi, LLVMBuildPointerCast, LLVMBuildRet, LLVMBuildRetVoid, LLVMBuildStore, LLVMBuildSub, LLVMBuildUDiv, LLVMBuildUnreachable, LLVMBuildXor, LLVMDisposeBuilder, LLVMGetElementType, LLVMGetInsertBlock, LLVMGetReturnType, LLVMGetTypeKind, LLVMInsertIntoBuilder, LLVMPositionBuilderAtEnd, LLVMTypeOf, LLVMBuildExtractElement, LLVMBuildInsertElement, LLVMBuildIntToPtr, LLVMBuildPtrToInt, LLVMInsertIntoBuilderWithName, LLVMClearInsertionPosition, LLVMCreateBuilder, LLVMPositionBuilder, LLVMPositionBuilderBefore, LLVMBuildAggregateRet, LLVMBuildStructGEP, LLVMBuildInBoundsGEP, LLVMBuildPtrDiff, LLVMBuildNSWAdd, LLVMBuildNUWAdd, LLVMBuildNSWSub, LLVMBuildNUWSub, LLVMBuildNSWMul, LLVMBuildNUWMul, LLVMBuildSDiv, LLVMBuildSRem, LLVMBuildURem, LLVMBuildFRem, LLVMBuildNSWNeg, LLVMBuildNUWNeg, LLVMBuildFPToUI, LLVMBuildFPToSI, LLVMBuildSIToFP, LLVMBuildUIToFP, LLVMBuildFPTrunc, LLVMBuildFPExt, LLVMBuildIntCast, LLVMBuildFPCast, LLVMBuildSExtOrBitCast, LLVMBuildZExtOrBitCast, LLVMBuildTruncOrBitCast, LLVMBuildSwitch, LLVMAddCase, LLVMBuildShl, LLVMBuildAShr, LLVMBuildLShr, LLVMBuildGlobalString, LLVMBuildGlobalStringPtr, LLVMBuildExactSDiv, LLVMBuildTrunc, LLVMBuildSExt, LLVMBuildZExt, LLVMBuildAtomicRMW, LLVMBuildInvoke, LLVMBuildLandingPad, LLVMBuildResume, LLVMAddClause, LLVMSetCleanup, LLVMBuildSelect, LLVMBuildShuffleVector, LLVMBuildVAArg, LLVMGetIntTypeWidth, LLVMGetBasicBlockParent, LLVMGetGlobalParent, LLVMSetCurrentDebugLocation, LLVMGetCurrentDebugLocation, LLVMGetTypeContext, LLVMInt1TypeInContext, LLVMInt32TypeInContext, LLVMPrintTypeToString, LLVMCountParamTypes, LLVMGetParamTypes, LLVMIsFunctionVarArg, LLVMCountStructElementTypes, LLVMGetStructElementTypes, LLVMGetArrayLength, LLVMGetVectorSize, LLVMTypeIsSized, LLVMGetInstructionOpcode, LLVMIsATerminatorInst, LLVMIsConstant, LLVMIsAConstantInt, LLVMConstIntGetZExtValue, LLVMGetPointerAddressSpace, LLVMStructGetTypeAtIndex, LLVMSetValueName, LLVMInt64TypeInContext, LLVMConstInt, LLVMMDNodeInContext, LLVMConstNull, LLVMPointerType, LLVMReplaceAllUsesWith, LLVMInstructionEraseFromParent};
+use llvm_sys::prelude::{LLVMBuilderRef, LLVMTypeRef, LLVMValueRef};
+use llvm_sys::{LLVMOpcode, LLVMTypeKind};
 
-use {IntPredicate, FloatPredicate};
+use {AtomicOrdering, AtomicRMWBinOp, FenceOrdering, IntPredicate, FloatPredicate};
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use {CmpXchgFailureOrdering, CmpXchgOrderings};
 use basic_block::BasicBlock;
-use values::{AggregateValue, AsValueRef, BasicValue, BasicValueEnum, PhiValue, FunctionValue, FloatValue, IntValue, PointerValue, VectorValue, InstructionValue, GlobalValue};
-use types::{AsTypeRef, BasicType, PointerType, IntType, FloatType};
-
+use intrinsics::Intrinsic;
+use support::LLVMString;
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use values::StructValue;
+use values::{AggregateValue, AsValueRef, BasicValue, BasicValueEnum, CallSiteValue, PhiValue, FunctionValue, FloatValue, IntValue, PointerValue, VectorValue, InstructionValue, InstructionOpcode, GlobalValue, MetadataValue};
+use types::{AsTypeRef, BasicType, BasicTypeEnum, PointerType, IntType, FloatType};
+
+use std::cell::Cell;
 use std::ffi::CString;
+use std::marker::PhantomData;
+use std::ptr;
+
+/// Builds instructions into the basic blocks of a `Module`.
+///
+/// Once a `Builder` has been positioned in a block, it can't be used after that block's
+/// module has been dropped:
+///
+/// ```compile_fail
+/// use inkwell::context::Context;
+///
+/// let context = Context::create();
+/// let builder = context.create_builder();
+///
+/// {
+///     let module = context.create_module("my_module");
+///     let function = module.add_function("my_fn", &context.void_type().fn_type(&[], false), None);
+///
+///     builder.position_at_end(&context.append_basic_block(&function, "entry"));
+/// }
+///
+/// builder.build_return(None);
+/// ```
+pub struct Builder<'ctx: 'm, 'm> {
+    builder: LLVMBuilderRef,
+    _disposer: BuilderDisposer<'ctx>,
+    // Invariant so that positioning the builder in a block ties it to that block's module
+    _marker: PhantomData<fn(&'m ()) -> &'m ()>,
+}
 
-pub struct Builder {
+// Disposes of the LLVM builder, which only refers to its context. This is kept apart from
+// Builder so that dropping a Builder doesn't require the modules it was positioned in to
+// still be alive
+struct BuilderDisposer<'ctx> {
     builder: LLVMBuilderRef,
+    _marker: PhantomData<fn(&'ctx ()) -> &'ctx ()>,
+}
+
+impl<'ctx> Drop for BuilderDisposer<'ctx> {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMDisposeBuilder(self.builder);
+        }
+    }
 }
 
-impl Builder {
+impl<'ctx, 'm> Builder<'ctx, 'm> {
     pub(crate) fn new(builder: LLVMBuilderRef) -> Self {
         assert!(!builder.is_null());
 
         Builder {
-            builder: builder
+            builder: builder,
+            _disposer: BuilderDisposer {
+                builder: builder,
+                _marker: PhantomData,
+            },
+            _marker: PhantomData,
         }
     }
 
-    pub fn create() -> Self {
-        let builder = unsafe {
-            LLVMCreateBuilder()
-        };
-
-        Builder::new(builder)
+    /// Turns this `Builder` into a `CheckedBuilder`, which validates each instruction before
+    /// building it.
+    pub fn into_checked(self) -> CheckedBuilder<'ctx, 'm> {
+        CheckedBuilder::new(self)
     }
 
     // REVIEW: Would probably make this API a bit simpler by taking Into<Option<&BasicValue>>
     // So that you could just do build_return(&value) or build_return(None)
     // Is that frowned upon?
-    pub fn build_return(&self, value: Option<&BasicValue>) -> InstructionValue {
+    pub fn build_return(&self, value: Option<&BasicValue<'ctx, 'm>>) -> InstructionValue<'ctx, 'm> {
         // let value = unsafe {
         //     value.map_or(LLVMBuildRetVoid(self.builder), |value| LLVMBuildRet(self.builder, value.value))
         // };
@@ -49,7 +99,7 @@ impl Builder {
         InstructionValue::new(value)
     }
 
-    pub fn build_aggregate_return(&self, values: &[&BasicValue]) -> InstructionValue {
+    pub fn build_aggregate_return(&self, values: &[&BasicValue<'ctx, 'm>]) -> InstructionValue<'ctx, 'm> {
         let mut args: Vec<LLVMValueRef> = values.iter()
                                                 .map(|val| val.as_value_ref())
                                                 .collect();
@@ -60,7 +110,50 @@ impl Builder {
         InstructionValue::new(value)
     }
 
-    pub fn build_call(&self, function: &FunctionValue, args: &[&BasicValue], name: &str, tail_call: bool) -> Either<BasicValueEnum, InstructionValue> {
+    pub fn build_call(&self, function: &FunctionValue<'ctx, 'm>, args: &[&BasicValue<'ctx, 'm>], name: &str) -> CallSiteValue<'ctx, 'm> {
+        self.build_call_site(function.as_value_ref(), args, name)
+    }
+
+    /// Calls the function `function_pointer` points to, such as one loaded from a vtable.
+    ///
+    /// Returns `Err(BuilderError::TypeMismatch)` if `function_pointer` doesn't point to a function,
+    /// which LLVM can't build a call to at all.
+    // SubType: <F>(&self, function_pointer: &PointerValue<F>, ...) -> CallSiteValue
+    pub fn build_indirect_call(&self, function_pointer: &PointerValue<'ctx, 'm>, args: &[&BasicValue<'ctx, 'm>], name: &str) -> Result<CallSiteValue<'ctx, 'm>, BuilderError> {
+        let is_fn_ptr = unsafe {
+            matches!(LLVMGetTypeKind(element_type_of(function_pointer.as_value_ref())), LLVMTypeKind::LLVMFunctionTypeKind)
+        };
+
+        if !is_fn_ptr {
+            return Err(type_mismatch("a pointer to a function", type_of(function_pointer.as_value_ref())));
+        }
+
+        Ok(self.build_call_site(function_pointer.as_value_ref(), args, name))
+    }
+
+    fn build_call_site(&self, callee: LLVMValueRef, args: &[&BasicValue<'ctx, 'm>], name: &str) -> CallSiteValue<'ctx, 'm> {
+        // LLVM gets upset when void calls are named because they don't return anything
+        let name = unsafe {
+            match LLVMGetTypeKind(LLVMGetReturnType(LLVMGetElementType(LLVMTypeOf(callee)))) {
+                LLVMTypeKind::LLVMVoidTypeKind => "",
+                _ => name,
+            }
+        };
+
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+        let mut args: Vec<LLVMValueRef> = args.iter()
+                                              .map(|val| val.as_value_ref())
+                                              .collect();
+        let value = unsafe {
+            LLVMBuildCall(self.builder, callee, args.as_mut_ptr(), args.len() as u32, c_string.as_ptr())
+        };
+
+        CallSiteValue::new(value)
+    }
+
+    /// Calls `function` like `build_call`, but continues execution in `then_block` if it returns
+    /// normally and in `catch_block` if it unwinds. `catch_block` must begin with a landing pad.
+    pub fn build_invoke(&self, function: &FunctionValue<'ctx, 'm>, args: &[&BasicValue<'ctx, 'm>], then_block: &BasicBlock<'ctx, 'm>, catch_block: &BasicBlock<'ctx, 'm>, name: &str) -> CallSiteValue<'ctx, 'm> {
         // LLVM gets upset when void calls are named because they don't return anything
         let name = unsafe {
             match LLVMGetTypeKind(LLVMGetReturnType(LLVMGetElementType(LLVMTypeOf(function.as_value_ref())))) {
@@ -74,25 +167,47 @@ impl Builder {
                                               .map(|val| val.as_value_ref())
                                               .collect();
         let value = unsafe {
-            LLVMBuildCall(self.builder, function.as_value_ref(), args.as_mut_ptr(), args.len() as u32, c_string.as_ptr())
+            LLVMBuildInvoke(self.builder, function.as_value_ref(), args.as_mut_ptr(), args.len() as u32, then_block.basic_block, catch_block.basic_block, c_string.as_ptr())
+        };
+
+        CallSiteValue::new(value)
+    }
+
+    /// Builds a landing pad yielding a value of `exception_type` (usually `{ i8*, i32 }`).
+    /// Each clause is either a catch clause, given as a pointer to a type info global
+    /// (or a null pointer to catch everything), or a filter clause, given as a constant array
+    /// of such pointers. `personality_function` is also set as the parent function's personality.
+    pub fn build_landing_pad(&self, exception_type: &BasicType<'ctx>, personality_function: &FunctionValue<'ctx, 'm>, clauses: &[&BasicValue<'ctx, 'm>], is_cleanup: bool, name: &str) -> BasicValueEnum<'ctx, 'm> {
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+
+        let value = unsafe {
+            LLVMBuildLandingPad(self.builder, exception_type.as_type_ref(), personality_function.as_value_ref(), clauses.len() as u32, c_string.as_ptr())
         };
 
-        if tail_call {
+        for clause in clauses {
             unsafe {
-                LLVMSetTailCall(value, true as i32)
+                LLVMAddClause(value, clause.as_value_ref())
             }
         }
 
         unsafe {
-            match LLVMGetTypeKind(LLVMTypeOf(value)) {
-                LLVMTypeKind::LLVMVoidTypeKind => Either::Right(InstructionValue::new(value)),
-                _ => Either::Left(BasicValueEnum::new(value)),
-            }
+            LLVMSetCleanup(value, is_cleanup as i32)
         }
+
+        BasicValueEnum::new(value)
+    }
+
+    /// Resumes propagation of an in-flight exception, usually the value produced by a landing pad.
+    pub fn build_resume(&self, value: &BasicValue<'ctx, 'm>) -> InstructionValue<'ctx, 'm> {
+        let val = unsafe {
+            LLVMBuildResume(self.builder, value.as_value_ref())
+        };
+
+        InstructionValue::new(val)
     }
 
     // REVIEW: Doesn't GEP work on array too?
-    pub fn build_gep(&self, ptr: &PointerValue, ordered_indexes: &[&IntValue], name: &str) -> PointerValue {
+    pub fn build_gep(&self, ptr: &PointerValue<'ctx, 'm>, ordered_indexes: &[&IntValue<'ctx, 'm>], name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let mut index_values: Vec<LLVMValueRef> = ordered_indexes.iter()
@@ -106,7 +221,7 @@ impl Builder {
     }
 
     // REVIEW: Doesn't GEP work on array too?
-    pub fn build_in_bounds_gep(&self, ptr: &PointerValue, ordered_indexes: &[&IntValue], name: &str) -> PointerValue {
+    pub fn build_in_bounds_gep(&self, ptr: &PointerValue<'ctx, 'm>, ordered_indexes: &[&IntValue<'ctx, 'm>], name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let mut index_values: Vec<LLVMValueRef> = ordered_indexes.iter()
@@ -120,7 +235,7 @@ impl Builder {
     }
 
     // REVIEW: Shouldn't this take a StructValue? Or does it still need to be PointerValue<StructValue>?
-    pub fn build_struct_gep(&self, ptr: &PointerValue, index: u32, name: &str) -> PointerValue {
+    pub fn build_struct_gep(&self, ptr: &PointerValue<'ctx, 'm>, index: u32, name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -130,7 +245,7 @@ impl Builder {
         PointerValue::new(value)
     }
 
-    pub fn build_ptr_diff(&self, lhs_ptr: &PointerValue, rhs_ptr: &PointerValue, name: &str) -> IntValue {
+    pub fn build_ptr_diff(&self, lhs_ptr: &PointerValue<'ctx, 'm>, rhs_ptr: &PointerValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -140,7 +255,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_phi<T: BasicType>(&self, type_: &T, name: &str) -> PhiValue {
+    pub fn build_phi<T: BasicType<'ctx>>(&self, type_: &T, name: &str) -> PhiValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -150,7 +265,7 @@ impl Builder {
         PhiValue::new(value)
     }
 
-    pub fn build_store<V: BasicValue>(&self, ptr: &PointerValue, value: &V) -> InstructionValue {
+    pub fn build_store<V: BasicValue<'ctx, 'm>>(&self, ptr: &PointerValue<'ctx, 'm>, value: &V) -> InstructionValue<'ctx, 'm> {
         let value = unsafe {
             LLVMBuildStore(self.builder, value.as_value_ref(), ptr.as_value_ref())
         };
@@ -158,7 +273,7 @@ impl Builder {
         InstructionValue::new(value)
     }
 
-    pub fn build_load(&self, ptr: &PointerValue, name: &str) -> BasicValueEnum {
+    pub fn build_load(&self, ptr: &PointerValue<'ctx, 'm>, name: &str) -> BasicValueEnum<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -169,7 +284,7 @@ impl Builder {
     }
 
     // TODOC: Stack allocation
-    pub fn build_alloca<T: BasicType>(&self, type_: &T, name: &str) -> PointerValue {
+    pub fn build_alloca<T: BasicType<'ctx>>(&self, type_: &T, name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -180,7 +295,7 @@ impl Builder {
     }
 
     // TODOC: Stack allocation
-    pub fn build_array_alloca<T: BasicType>(&self, type_: &T, size: &IntValue, name: &str) -> PointerValue {
+    pub fn build_array_alloca<T: BasicType<'ctx>>(&self, type_: &T, size: &IntValue<'ctx, 'm>, name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -192,7 +307,7 @@ impl Builder {
 
     // TODOC: Heap allocation
     // FIXME: Not working
-    pub fn build_malloc<T: BasicType>(&self, type_: &T, name: &str) -> PointerValue {
+    pub fn build_malloc<T: BasicType<'ctx>>(&self, type_: &T, name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -204,7 +319,7 @@ impl Builder {
 
     // TODOC: Heap allocation
     // FIXME: Not working
-    pub fn build_array_malloc<T: BasicType>(&self, type_: &T, size: &IntValue, name: &str) -> PointerValue {
+    pub fn build_array_malloc<T: BasicType<'ctx>>(&self, type_: &T, size: &IntValue<'ctx, 'm>, name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -215,7 +330,7 @@ impl Builder {
     }
 
     // SubType: <P>(&self, ptr: &PointerValue<P>) -> InstructionValue {
-    pub fn build_free(&self, ptr: &PointerValue) -> InstructionValue {
+    pub fn build_free(&self, ptr: &PointerValue<'ctx, 'm>) -> InstructionValue<'ctx, 'm> {
         let val = unsafe {
             LLVMBuildFree(self.builder, ptr.as_value_ref())
         };
@@ -223,7 +338,7 @@ impl Builder {
         InstructionValue::new(val)
     }
 
-    pub fn insert_instruction(&self, instruction: &InstructionValue, name: Option<&str>) {
+    pub fn insert_instruction(&self, instruction: &InstructionValue<'ctx, 'm>, name: Option<&str>) {
         match name {
             Some(name) => {
                 let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
@@ -238,7 +353,7 @@ impl Builder {
         }
     }
 
-    pub fn get_insert_block(&self) -> Option<BasicBlock> {
+    pub fn get_insert_block(&self) -> Option<BasicBlock<'ctx, 'm>> {
         let bb = unsafe {
             LLVMGetInsertBlock(self.builder)
         };
@@ -246,10 +361,135 @@ impl Builder {
         BasicBlock::new(bb)
     }
 
+    /// Sets the debug location (a `DILocation` metadata node) attached to every instruction
+    /// built from now on.
+    ///
+    /// LLVM 3.7 - 4.0 have no C API for creating debug info (ie `DIBuilder`), so inkwell has no
+    /// `DebugInfoBuilder`. Nor can one be built on `MetadataValue::create_node`: since LLVM 3.7,
+    /// debug info is made of dedicated node kinds rather than generic tuples, and the C API can't
+    /// attach a `DISubprogram` to a function. Debug info metadata such as locations, subprograms
+    /// and variables has to come from existing IR, such as a module parsed from IR with
+    /// `Context::create_module_from_ir` which the instructions being built are linked against.
+    /// Variables are then described with `build_dbg_declare` and `build_dbg_value`.
+    pub fn set_current_debug_location(&self, location: &MetadataValue<'ctx, 'm>) {
+        unsafe {
+            LLVMSetCurrentDebugLocation(self.builder, location.as_value_ref())
+        }
+    }
+
+    pub fn get_current_debug_location(&self) -> Option<MetadataValue<'ctx, 'm>> {
+        let location = unsafe {
+            LLVMGetCurrentDebugLocation(self.builder)
+        };
+
+        if location.is_null() {
+            return None;
+        }
+
+        let location = MetadataValue::new(location);
+
+        // Some LLVM versions return an empty node rather than null when no location is set
+        if location.get_node_size() == 0 {
+            return None;
+        }
+
+        Some(location)
+    }
+
+    /// Stops attaching a debug location to newly built instructions.
+    pub fn unset_current_debug_location(&self) {
+        unsafe {
+            LLVMSetCurrentDebugLocation(self.builder, ptr::null_mut())
+        }
+    }
+
+    /// Describes the source variable `variable` (a `DILocalVariable` metadata node) as living
+    /// in the memory `address` points to, usually an alloca, using `llvm.dbg.declare`.
+    /// `expression` is a `DIExpression` metadata node, which is empty in the common case.
+    ///
+    /// Like other debug info, the metadata has to come from existing IR (see
+    /// `set_current_debug_location`), and a debug location should be set before calling this,
+    /// as LLVM requires one on debug intrinsic calls in functions with debug info.
+    pub fn build_dbg_declare(&self, address: &PointerValue<'ctx, 'm>, variable: &MetadataValue<'ctx, 'm>, expression: &MetadataValue<'ctx, 'm>) -> InstructionValue<'ctx, 'm> {
+        let function = self.get_intrinsic_function(Intrinsic::DbgDeclare, &[]);
+        let address = self.wrap_in_metadata(address);
+
+        self.build_metadata_call(&function, &mut [address, variable.as_value_ref(), expression.as_value_ref()])
+    }
+
+    /// Describes the source variable `variable` (a `DILocalVariable` metadata node) as having
+    /// the value `value` from this point on, using `llvm.dbg.value`. `offset` is the offset into
+    /// the variable in bits and `expression` is a `DIExpression` metadata node, which is empty in
+    /// the common case.
+    ///
+    /// `value` may be a constant, such as a variable's initial value. As with `build_dbg_declare`,
+    /// the metadata has to come from existing IR, and a debug location should be set before
+    /// calling this.
+    pub fn build_dbg_value(&self, value: &BasicValue<'ctx, 'm>, offset: u64, variable: &MetadataValue<'ctx, 'm>, expression: &MetadataValue<'ctx, 'm>) -> InstructionValue<'ctx, 'm> {
+        let function = self.get_intrinsic_function(Intrinsic::DbgValue, &[]);
+        let offset = unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(function.as_value_ref()));
+
+            LLVMConstInt(LLVMInt64TypeInContext(context), offset, false as i32)
+        };
+
+        let is_constant = unsafe {
+            LLVMIsConstant(value.as_value_ref()) == 1
+        };
+
+        if !is_constant {
+            let value = self.wrap_in_metadata(value);
+
+            return self.build_metadata_call(&function, &mut [value, offset, variable.as_value_ref(), expression.as_value_ref()]);
+        }
+
+        // The C API wraps a constant in a node, ie `metadata !{i32 1}`, which the intrinsic doesn't accept.
+        // Instead, a function local stand in is passed and replaced by the constant, which LLVM turns
+        // into `metadata i32 1`
+        let stand_in = unsafe {
+            let pointer = LLVMConstNull(LLVMPointerType(LLVMTypeOf(value.as_value_ref()), 0));
+            let c_string = CString::new("").expect("Conversion to CString failed unexpectedly");
+
+            LLVMBuildLoad(self.builder, pointer, c_string.as_ptr())
+        };
+        let mut values = [stand_in];
+        let stand_in_metadata = unsafe {
+            LLVMMDNodeInContext(LLVMGetTypeContext(LLVMTypeOf(stand_in)), values.as_mut_ptr(), 1)
+        };
+        let call = self.build_metadata_call(&function, &mut [stand_in_metadata, offset, variable.as_value_ref(), expression.as_value_ref()]);
+
+        unsafe {
+            LLVMReplaceAllUsesWith(stand_in, value.as_value_ref());
+            LLVMInstructionEraseFromParent(stand_in);
+        }
+
+        call
+    }
+
+    // A single function local value is passed to a metadata parameter as is, ie `metadata i32* %x`,
+    // rather than wrapped in a node
+    fn wrap_in_metadata(&self, value: &BasicValue<'ctx, 'm>) -> LLVMValueRef {
+        let mut values = [value.as_value_ref()];
+
+        unsafe {
+            LLVMMDNodeInContext(LLVMGetTypeContext(LLVMTypeOf(values[0])), values.as_mut_ptr(), 1)
+        }
+    }
+
+    // Metadata arguments aren't BasicValues, so they can't go through build_call
+    fn build_metadata_call(&self, function: &FunctionValue<'ctx, 'm>, args: &mut [LLVMValueRef]) -> InstructionValue<'ctx, 'm> {
+        let c_string = CString::new("").expect("Conversion to CString failed unexpectedly");
+        let value = unsafe {
+            LLVMBuildCall(self.builder, function.as_value_ref(), args.as_mut_ptr(), args.len() as u32, c_string.as_ptr())
+        };
+
+        InstructionValue::new(value)
+    }
+
     // TODO: Possibly make this generic over sign via struct metadata or subtypes
     // SubType: <I: IntSubType>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
     //     if I::sign() == Unsigned { LLVMBuildUDiv() } else { LLVMBuildSDiv() }
-    pub fn build_int_unsigned_div(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_unsigned_div(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -261,7 +501,7 @@ impl Builder {
 
     // TODO: Possibly make this generic over sign via struct metadata or subtypes
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_signed_div(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_signed_div(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -273,7 +513,7 @@ impl Builder {
 
     // TODO: Possibly make this generic over sign via struct metadata or subtypes
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_exact_signed_div(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_exact_signed_div(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -285,7 +525,7 @@ impl Builder {
 
     // TODO: Possibly make this generic over sign via struct metadata or subtypes
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_unsigned_rem(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_unsigned_rem(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -298,7 +538,7 @@ impl Builder {
 
     // TODO: Possibly make this generic over sign via struct metadata or subtypes
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_signed_rem(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_signed_rem(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -308,7 +548,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_int_s_extend(&self, int_value: &IntValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_int_s_extend(&self, int_value: &IntValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -318,7 +558,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_int_s_extend_or_bit_cast(&self, int_value: &IntValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_int_s_extend_or_bit_cast(&self, int_value: &IntValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -328,7 +568,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_int_z_extend(&self, int_value: &IntValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_int_z_extend(&self, int_value: &IntValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
        let value = unsafe {
@@ -338,7 +578,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_int_z_extend_or_bit_cast(&self, int_value: &IntValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_int_z_extend_or_bit_cast(&self, int_value: &IntValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
        let value = unsafe {
@@ -348,7 +588,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_int_truncate(&self, int_value: &IntValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_int_truncate(&self, int_value: &IntValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
        let value = unsafe {
@@ -358,7 +598,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_int_truncate_or_bit_cast(&self, int_value: &IntValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_int_truncate_or_bit_cast(&self, int_value: &IntValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
        let value = unsafe {
@@ -368,7 +608,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_float_rem(&self, lhs: &FloatValue, rhs: &FloatValue, name: &str) -> FloatValue {
+    pub fn build_float_rem(&self, lhs: &FloatValue<'ctx, 'm>, rhs: &FloatValue<'ctx, 'm>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -379,7 +619,7 @@ impl Builder {
     }
 
     // REVIEW: Consolidate these two casts into one via subtypes
-    pub fn build_float_to_unsigned_int(&self, float: &FloatValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_float_to_unsigned_int(&self, float: &FloatValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -389,7 +629,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_float_to_signed_int(&self, float: &FloatValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_float_to_signed_int(&self, float: &FloatValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -400,7 +640,7 @@ impl Builder {
     }
 
     // REVIEW: Consolidate these two casts into one via subtypes
-    pub fn build_unsigned_int_to_float(&self, int: &IntValue, float_type: &FloatType, name: &str) -> FloatValue {
+    pub fn build_unsigned_int_to_float(&self, int: &IntValue<'ctx, 'm>, float_type: &FloatType<'ctx>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -410,7 +650,7 @@ impl Builder {
         FloatValue::new(value)
     }
 
-    pub fn build_signed_int_to_float(&self, int: &FloatValue, float_type: &FloatType, name: &str) -> FloatValue {
+    pub fn build_signed_int_to_float(&self, int: &IntValue<'ctx, 'm>, float_type: &FloatType<'ctx>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -420,7 +660,7 @@ impl Builder {
         FloatValue::new(value)
     }
 
-    pub fn build_float_trunc(&self, float: &FloatValue, float_type: &FloatType, name: &str) -> FloatValue {
+    pub fn build_float_trunc(&self, float: &FloatValue<'ctx, 'm>, float_type: &FloatType<'ctx>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -430,7 +670,7 @@ impl Builder {
         FloatValue::new(value)
     }
 
-    pub fn build_float_ext(&self, float: &FloatValue, float_type: &FloatType, name: &str) -> FloatValue {
+    pub fn build_float_ext(&self, float: &FloatValue<'ctx, 'm>, float_type: &FloatType<'ctx>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -440,7 +680,7 @@ impl Builder {
         FloatValue::new(value)
     }
 
-    pub fn build_float_cast(&self, float: &FloatValue, float_type: &FloatType, name: &str) -> FloatValue {
+    pub fn build_float_cast(&self, float: &FloatValue<'ctx, 'm>, float_type: &FloatType<'ctx>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -451,7 +691,7 @@ impl Builder {
     }
 
     // SubType: <L, R>(&self, lhs: &IntValue<L>, rhs: &IntType<R>, name: &str) -> IntValue<R> {
-    pub fn build_int_cast(&self, int: &IntValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_int_cast(&self, int: &IntValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -461,7 +701,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_float_div(&self, lhs: &FloatValue, rhs: &FloatValue, name: &str) -> FloatValue {
+    pub fn build_float_div(&self, lhs: &FloatValue<'ctx, 'm>, rhs: &FloatValue<'ctx, 'm>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -472,7 +712,7 @@ impl Builder {
     }
 
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_add(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_add(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -484,7 +724,7 @@ impl Builder {
 
     // REVIEW: Possibly incorperate into build_int_add via flag param
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_nsw_add(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nsw_add(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -496,7 +736,7 @@ impl Builder {
 
     // REVIEW: Possibly incorperate into build_int_add via flag param
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_nuw_add(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nuw_add(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -507,7 +747,7 @@ impl Builder {
     }
 
     // SubType: <F>(&self, lhs: &FloatValue<F>, rhs: &FloatValue<F>, name: &str) -> FloatValue<F> {
-    pub fn build_float_add(&self, lhs: &FloatValue, rhs: &FloatValue, name: &str) -> FloatValue {
+    pub fn build_float_add(&self, lhs: &FloatValue<'ctx, 'm>, rhs: &FloatValue<'ctx, 'm>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -518,7 +758,7 @@ impl Builder {
     }
 
     // SubType: (&self, lhs: &IntValue<bool>, rhs: &IntValue<bool>, name: &str) -> IntValue<bool> {
-    pub fn build_xor(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_xor(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -529,7 +769,7 @@ impl Builder {
     }
 
     // SubType: (&self, lhs: &IntValue<bool>, rhs: &IntValue<bool>, name: &str) -> IntValue<bool> {
-    pub fn build_and(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_and(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -540,7 +780,7 @@ impl Builder {
     }
 
     // SubType: (&self, lhs: &IntValue<bool>, rhs: &IntValue<bool>, name: &str) -> IntValue<bool> {
-    pub fn build_or(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_or(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -593,7 +833,7 @@ impl Builder {
     ///
     /// builder.build_return(Some(&shift));
     /// ```
-    pub fn build_left_shift(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_left_shift(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -662,7 +902,7 @@ impl Builder {
     ///
     /// builder.build_return(Some(&shift));
     /// ```
-    pub fn build_right_shift(&self, lhs: &IntValue, rhs: &IntValue, sign_extend: bool, name: &str) -> IntValue {
+    pub fn build_right_shift(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, sign_extend: bool, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -677,7 +917,7 @@ impl Builder {
     }
 
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_sub(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_sub(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -688,7 +928,7 @@ impl Builder {
     }
 
     // REVIEW: Possibly incorperate into build_int_sub via flag param
-    pub fn build_int_nsw_sub(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nsw_sub(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -700,7 +940,7 @@ impl Builder {
 
     // REVIEW: Possibly incorperate into build_int_sub via flag param
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_nuw_sub(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nuw_sub(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -711,7 +951,7 @@ impl Builder {
     }
 
     // SubType: <F>(&self, lhs: &FloatValue<F>, rhs: &FloatValue<F>, name: &str) -> FloatValue<F> {
-    pub fn build_float_sub(&self, lhs: &FloatValue, rhs: &FloatValue, name: &str) -> FloatValue {
+    pub fn build_float_sub(&self, lhs: &FloatValue<'ctx, 'm>, rhs: &FloatValue<'ctx, 'm>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -722,7 +962,7 @@ impl Builder {
     }
 
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_mul(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_mul(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -734,7 +974,7 @@ impl Builder {
 
     // REVIEW: Possibly incorperate into build_int_mul via flag param
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_nsw_mul(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nsw_mul(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -746,7 +986,7 @@ impl Builder {
 
     // REVIEW: Possibly incorperate into build_int_mul via flag param
     // SubType: <I>(&self, lhs: &IntValue<I>, rhs: &IntValue<I>, name: &str) -> IntValue<I> {
-    pub fn build_int_nuw_mul(&self, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nuw_mul(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -757,7 +997,7 @@ impl Builder {
     }
 
     // SubType: <F>(&self, lhs: &FloatValue<F>, rhs: &FloatValue<F>, name: &str) -> FloatValue<F> {
-    pub fn build_float_mul(&self, lhs: &FloatValue, rhs: &FloatValue, name: &str) -> FloatValue {
+    pub fn build_float_mul(&self, lhs: &FloatValue<'ctx, 'm>, rhs: &FloatValue<'ctx, 'm>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -767,7 +1007,7 @@ impl Builder {
         FloatValue::new(value)
     }
 
-    pub fn build_cast(&self, op: LLVMOpcode, from_value: &BasicValue, to_type: &BasicType, name: &str) -> BasicValueEnum {
+    pub fn build_cast(&self, op: LLVMOpcode, from_value: &BasicValue<'ctx, 'm>, to_type: &BasicType<'ctx>, name: &str) -> BasicValueEnum<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -778,7 +1018,7 @@ impl Builder {
     }
 
     // SubType: <F, T>(&self, from: &PointerValue<F>, to: &PointerType<T>, name: &str) -> PointerValue<T> {
-    pub fn build_pointer_cast(&self, from: &PointerValue, to: &PointerType, name: &str) -> PointerValue {
+    pub fn build_pointer_cast(&self, from: &PointerValue<'ctx, 'm>, to: &PointerType<'ctx>, name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -789,7 +1029,7 @@ impl Builder {
     }
 
     // SubType: <I>(&self, op, lhs: &IntValue<I>, rhs: &IntValue<I>, name) -> IntValue<bool> { ?
-    pub fn build_int_compare(&self, op: IntPredicate, lhs: &IntValue, rhs: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_compare(&self, op: IntPredicate, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -800,7 +1040,7 @@ impl Builder {
     }
 
     // SubType: <F>(&self, op, lhs: &FloatValue<F>, rhs: &FloatValue<F>, name) -> IntValue<bool> { ?
-    pub fn build_float_compare(&self, op: FloatPredicate, lhs: &FloatValue, rhs: &FloatValue, name: &str) -> IntValue {
+    pub fn build_float_compare(&self, op: FloatPredicate, lhs: &FloatValue<'ctx, 'm>, rhs: &FloatValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -810,7 +1050,7 @@ impl Builder {
         IntValue::new(value)
     }
 
-    pub fn build_unconditional_branch(&self, destination_block: &BasicBlock) -> InstructionValue {
+    pub fn build_unconditional_branch(&self, destination_block: &BasicBlock<'ctx, 'm>) -> InstructionValue<'ctx, 'm> {
         let value = unsafe {
             LLVMBuildBr(self.builder, destination_block.basic_block)
         };
@@ -818,7 +1058,7 @@ impl Builder {
         InstructionValue::new(value)
     }
 
-    pub fn build_conditional_branch(&self, comparison: &IntValue, then_block: &BasicBlock, else_block: &BasicBlock) -> InstructionValue {
+    pub fn build_conditional_branch(&self, comparison: &IntValue<'ctx, 'm>, then_block: &BasicBlock<'ctx, 'm>, else_block: &BasicBlock<'ctx, 'm>) -> InstructionValue<'ctx, 'm> {
         let value = unsafe {
             LLVMBuildCondBr(self.builder, comparison.as_value_ref(), then_block.basic_block, else_block.basic_block)
         };
@@ -827,7 +1067,7 @@ impl Builder {
     }
 
     // SubType: <I>(&self, value: &IntValue<I>, name) -> IntValue<I> {
-    pub fn build_int_neg(&self, value: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_neg(&self, value: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -839,7 +1079,7 @@ impl Builder {
 
     // REVIEW: Possibly incorperate into build_int_neg via flag and subtypes
     // SubType: <I>(&self, value: &IntValue<I>, name) -> IntValue<I> {
-    pub fn build_int_nsw_neg(&self, value: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nsw_neg(&self, value: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -850,7 +1090,7 @@ impl Builder {
     }
 
     // SubType: <I>(&self, value: &IntValue<I>, name) -> IntValue<I> {
-    pub fn build_int_nuw_neg(&self, value: &IntValue, name: &str) -> IntValue {
+    pub fn build_int_nuw_neg(&self, value: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -861,7 +1101,7 @@ impl Builder {
     }
 
     // SubType: <F>(&self, value: &FloatValue<F>, name) -> FloatValue<F> {
-    pub fn build_float_neg(&self, value: &FloatValue, name: &str) -> FloatValue {
+    pub fn build_float_neg(&self, value: &FloatValue<'ctx, 'm>, name: &str) -> FloatValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -872,7 +1112,7 @@ impl Builder {
     }
 
     // SubType: <I>(&self, value: &IntValue<I>, name) -> IntValue<bool> { ?
-    pub fn build_not(&self, value: &IntValue, name: &str) -> IntValue {
+    pub fn build_not(&self, value: &IntValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -884,19 +1124,19 @@ impl Builder {
 
     // REVIEW: What if instruction and basic_block are completely unrelated?
     // It'd be great if we could get the BB from the instruction behind the scenes
-    pub fn position_at(&self, basic_block: &BasicBlock, instruction: &InstructionValue) {
+    pub fn position_at(&self, basic_block: &BasicBlock<'ctx, 'm>, instruction: &InstructionValue<'ctx, 'm>) {
         unsafe {
             LLVMPositionBuilder(self.builder, basic_block.basic_block, instruction.as_value_ref())
         }
     }
 
-    pub fn position_before(&self, instruction: &InstructionValue) {
+    pub fn position_before(&self, instruction: &InstructionValue<'ctx, 'm>) {
         unsafe {
             LLVMPositionBuilderBefore(self.builder, instruction.as_value_ref())
         }
     }
 
-    pub fn position_at_end(&self, basic_block: &BasicBlock) {
+    pub fn position_at_end(&self, basic_block: &BasicBlock<'ctx, 'm>) {
         unsafe {
             LLVMPositionBuilderAtEnd(self.builder, basic_block.basic_block);
         }
@@ -905,7 +1145,7 @@ impl Builder {
     // REVIEW: How does LLVM treat out of bound index? Maybe we should return an Option?
     // or is that only in bounds GEP
     // REVIEW: Should this be AggregatePointerValue?
-    pub fn build_extract_value(&self, value: &AggregateValue, index: u32, name: &str) -> BasicValueEnum {
+    pub fn build_extract_value(&self, value: &AggregateValue<'ctx, 'm>, index: u32, name: &str) -> BasicValueEnum<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -916,7 +1156,7 @@ impl Builder {
     }
 
     // REVIEW: Should this be AggregatePointerValue instead of just PointerValue?
-    pub fn build_insert_value(&self, value: &BasicValue, ptr: &PointerValue, index: u32, name: &str) -> InstructionValue {
+    pub fn build_insert_value(&self, value: &BasicValue<'ctx, 'm>, ptr: &PointerValue<'ctx, 'm>, index: u32, name: &str) -> InstructionValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -926,7 +1166,7 @@ impl Builder {
         InstructionValue::new(value)
     }
 
-    pub fn build_extract_element(&self, vector: &VectorValue, index: &IntValue, name: &str) -> BasicValueEnum {
+    pub fn build_extract_element(&self, vector: &VectorValue<'ctx, 'm>, index: &IntValue<'ctx, 'm>, name: &str) -> BasicValueEnum<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -936,7 +1176,7 @@ impl Builder {
         BasicValueEnum::new(value)
     }
 
-    pub fn build_insert_element(&self, vector: &VectorValue, element: &BasicValue, index: &IntValue, name: &str) -> BasicValueEnum {
+    pub fn build_insert_element(&self, vector: &VectorValue<'ctx, 'm>, element: &BasicValue<'ctx, 'm>, index: &IntValue<'ctx, 'm>, name: &str) -> BasicValueEnum<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -946,7 +1186,131 @@ impl Builder {
         BasicValueEnum::new(value)
     }
 
-    pub fn build_unreachable(&self) -> InstructionValue {
+    /// Builds a shuffle vector whose elements are picked from `left` and `right` according to
+    /// `mask`, a constant vector of i32 indices into their concatenation (or undef).
+    pub fn build_shuffle_vector(&self, left: &VectorValue<'ctx, 'm>, right: &VectorValue<'ctx, 'm>, mask: &VectorValue<'ctx, 'm>, name: &str) -> VectorValue<'ctx, 'm> {
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+
+        let value = unsafe {
+            LLVMBuildShuffleVector(self.builder, left.as_value_ref(), right.as_value_ref(), mask.as_value_ref(), c_string.as_ptr())
+        };
+
+        VectorValue::new(value)
+    }
+
+    /// Picks `then_value` or `else_value` depending on `condition`. The condition may be an `i1`,
+    /// or a vector of `i1`s, in which case the values must be vectors of the same length and
+    /// the selection is done per element. Other conditions are left for `Module::verify` to
+    /// report, or a `CheckedBuilder` to reject up front.
+    // SubType: <IMV: IntMathValue<bool>, BV: BasicValue>(&self, condition: &IMV, then_value: &BV, else_value: &BV, name) -> BV {
+    pub fn build_select(&self, condition: &BasicValue<'ctx, 'm>, then_value: &BasicValue<'ctx, 'm>, else_value: &BasicValue<'ctx, 'm>, name: &str) -> BasicValueEnum<'ctx, 'm> {
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+
+        let value = unsafe {
+            LLVMBuildSelect(self.builder, condition.as_value_ref(), then_value.as_value_ref(), else_value.as_value_ref(), c_string.as_ptr())
+        };
+
+        BasicValueEnum::new(value)
+    }
+
+    /// Reads the next argument of type `type_` from `list`, a `va_list` which has been set up
+    /// with the `llvm.va_start` intrinsic.
+    // SubType: <P>(&self, list: &PointerValue<P>, type_: &BT, name) -> BT::Value {
+    pub fn build_va_arg(&self, list: &PointerValue<'ctx, 'm>, type_: &BasicType<'ctx>, name: &str) -> BasicValueEnum<'ctx, 'm> {
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+
+        let value = unsafe {
+            LLVMBuildVAArg(self.builder, list.as_value_ref(), type_.as_type_ref(), c_string.as_ptr())
+        };
+
+        BasicValueEnum::new(value)
+    }
+
+    // Declares an intrinsic in the module the builder is currently positioned in
+    fn get_intrinsic_function(&self, intrinsic: Intrinsic, overloaded_types: &[BasicTypeEnum<'ctx>]) -> FunctionValue<'ctx, 'm> {
+        let module = unsafe {
+            let basic_block = LLVMGetInsertBlock(self.builder);
+
+            assert!(!basic_block.is_null(), "Builder must be positioned in a basic block to call intrinsics");
+
+            LLVMGetGlobalParent(LLVMGetBasicBlockParent(basic_block))
+        };
+
+        intrinsic.get_declaration(module, overloaded_types)
+    }
+
+    /// Copies `size` bytes from `src` to `dest`, which must not overlap, using `llvm.memcpy`.
+    pub fn build_memcpy(&self, dest: &PointerValue<'ctx, 'm>, src: &PointerValue<'ctx, 'm>, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> InstructionValue<'ctx, 'm> {
+        self.build_mem_transfer(Intrinsic::Memcpy, dest, src, size, align, is_volatile)
+    }
+
+    /// Copies `size` bytes from `src` to `dest`, which may overlap, using `llvm.memmove`.
+    pub fn build_memmove(&self, dest: &PointerValue<'ctx, 'm>, src: &PointerValue<'ctx, 'm>, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> InstructionValue<'ctx, 'm> {
+        self.build_mem_transfer(Intrinsic::Memmove, dest, src, size, align, is_volatile)
+    }
+
+    fn build_mem_transfer(&self, intrinsic: Intrinsic, dest: &PointerValue<'ctx, 'm>, src: &PointerValue<'ctx, 'm>, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> InstructionValue<'ctx, 'm> {
+        let function = self.get_intrinsic_function(intrinsic, &[dest.get_type().into(), src.get_type().into(), size.get_type().into()]);
+        let (align, is_volatile) = self.mem_intrinsic_flags(size, align, is_volatile);
+
+        self.build_call(&function, &[dest, src, size, &align, &is_volatile], "").as_instruction()
+    }
+
+    /// Sets `size` bytes starting at `dest` to the i8 `value` using `llvm.memset`.
+    pub fn build_memset(&self, dest: &PointerValue<'ctx, 'm>, value: &IntValue<'ctx, 'm>, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> InstructionValue<'ctx, 'm> {
+        let function = self.get_intrinsic_function(Intrinsic::Memset, &[dest.get_type().into(), size.get_type().into()]);
+        let (align, is_volatile) = self.mem_intrinsic_flags(size, align, is_volatile);
+
+        self.build_call(&function, &[dest, value, size, &align, &is_volatile], "").as_instruction()
+    }
+
+    // The i32 align and i1 is_volatile arguments shared by the mem* intrinsics
+    fn mem_intrinsic_flags(&self, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> (IntValue<'ctx, 'm>, IntValue<'ctx, 'm>) {
+        let (i32_type, bool_type) = unsafe {
+            let context = LLVMGetTypeContext(size.get_type().as_type_ref());
+
+            (IntType::new(LLVMInt32TypeInContext(context)), IntType::new(LLVMInt1TypeInContext(context)))
+        };
+
+        (i32_type.const_int(align as u64, false), bool_type.const_int(is_volatile as u64, false))
+    }
+
+    /// Adds two integers, returning the (wrapped) result and an `i1` which is set if the
+    /// addition overflowed.
+    pub fn build_int_add_with_overflow(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, is_signed: bool, name: &str) -> (IntValue<'ctx, 'm>, IntValue<'ctx, 'm>) {
+        let intrinsic = if is_signed { Intrinsic::SAddWithOverflow } else { Intrinsic::UAddWithOverflow };
+
+        self.build_int_op_with_overflow(intrinsic, lhs, rhs, name)
+    }
+
+    /// Subtracts two integers, returning the (wrapped) result and an `i1` which is set if the
+    /// subtraction overflowed.
+    pub fn build_int_sub_with_overflow(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, is_signed: bool, name: &str) -> (IntValue<'ctx, 'm>, IntValue<'ctx, 'm>) {
+        let intrinsic = if is_signed { Intrinsic::SSubWithOverflow } else { Intrinsic::USubWithOverflow };
+
+        self.build_int_op_with_overflow(intrinsic, lhs, rhs, name)
+    }
+
+    /// Multiplies two integers, returning the (wrapped) result and an `i1` which is set if the
+    /// multiplication overflowed.
+    pub fn build_int_mul_with_overflow(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, is_signed: bool, name: &str) -> (IntValue<'ctx, 'm>, IntValue<'ctx, 'm>) {
+        let intrinsic = if is_signed { Intrinsic::SMulWithOverflow } else { Intrinsic::UMulWithOverflow };
+
+        self.build_int_op_with_overflow(intrinsic, lhs, rhs, name)
+    }
+
+    fn build_int_op_with_overflow(&self, intrinsic: Intrinsic, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> (IntValue<'ctx, 'm>, IntValue<'ctx, 'm>) {
+        let function = self.get_intrinsic_function(intrinsic, &[lhs.get_type().into()]);
+        let pair = self.build_call(&function, &[lhs, rhs], "").try_as_basic_value().left().expect("Overflow intrinsics always return a value").into_struct_value();
+        let result = self.build_extract_value(&pair, 0, name).into_int_value();
+        // An unnamed result leaves the overflow bit unnamed too, rather than named ".overflow"
+        let overflow_name = if name.is_empty() { String::new() } else { format!("{}.overflow", name) };
+        let overflow = self.build_extract_value(&pair, 1, &overflow_name).into_int_value();
+
+        (result, overflow)
+    }
+
+    pub fn build_unreachable(&self) -> InstructionValue<'ctx, 'm> {
         let val = unsafe {
             LLVMBuildUnreachable(self.builder)
         };
@@ -955,19 +1319,67 @@ impl Builder {
     }
 
     // REVIEW: Not sure if this should return InstructionValue or an actual value
-    // TODO: Better name for num?
-    pub fn build_fence(&self, atmoic_ordering: LLVMAtomicOrdering, num: i32, name: &str) -> InstructionValue {
+    pub fn build_fence(&self, fence_ordering: FenceOrdering, single_thread: bool, name: &str) -> InstructionValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let val = unsafe {
-            LLVMBuildFence(self.builder, atmoic_ordering, num, c_string.as_ptr())
+            LLVMBuildFence(self.builder, fence_ordering.as_llvm_ordering(), single_thread as i32, c_string.as_ptr())
         };
 
         InstructionValue::new(val)
     }
 
+    /// Atomically applies `op` to the integer pointed to by `ptr` and `value`, storing the result
+    /// back into `ptr` and returning the value that was previously stored there.
+    // REVIEW: LLVM requires ptr's element type to be the same integer type as value
+    // SubType: <I>(&self, op, ptr: &PointerValue<I>, value: &IntValue<I>, ...) -> IntValue<I> {
+    pub fn build_atomicrmw(&self, op: AtomicRMWBinOp, ptr: &PointerValue<'ctx, 'm>, value: &IntValue<'ctx, 'm>, ordering: AtomicOrdering, single_thread: bool, name: &str) -> IntValue<'ctx, 'm> {
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+
+        // Unlike the other builders, LLVMBuildAtomicRMW doesn't take a name
+        let val = unsafe {
+            let val = LLVMBuildAtomicRMW(self.builder, op.as_llvm_bin_op(), ptr.as_value_ref(), value.as_value_ref(), ordering.as_llvm_ordering(), single_thread as i32);
+
+            LLVMSetValueName(val, c_string.as_ptr());
+
+            val
+        };
+
+        IntValue::new(val)
+    }
+
+    /// Atomically compares the value pointed to by `ptr` with `cmp` and, if they are equal, stores
+    /// `new` into `ptr`. Returns a `{ value, i1 }` struct holding the previously stored value and
+    /// whether the exchange succeeded.
+    ///
+    /// Returns `Err(BuilderError::StrongerFailureOrdering)` if the failure ordering is
+    /// stronger than the success ordering, which LLVM doesn't allow.
+    ///
+    /// The LLVM 3.7 and 3.8 C APIs have no way to build a `cmpxchg`, so this method is
+    /// only available with the `llvm3-9` and `llvm4-0` features.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn build_cmpxchg<V: BasicValue<'ctx, 'm>>(&self, ptr: &PointerValue<'ctx, 'm>, cmp: &V, new: &V, orderings: CmpXchgOrderings, single_thread: bool, name: &str) -> Result<StructValue<'ctx, 'm>, BuilderError> {
+        use llvm_sys::core::LLVMBuildAtomicCmpXchg;
+
+        if orderings.failure.is_stronger_than(orderings.success) {
+            return Err(BuilderError::StrongerFailureOrdering { success: orderings.success, failure: orderings.failure });
+        }
+
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+
+        let val = unsafe {
+            let val = LLVMBuildAtomicCmpXchg(self.builder, ptr.as_value_ref(), cmp.as_value_ref(), new.as_value_ref(), orderings.success.as_llvm_ordering(), orderings.failure.as_llvm_ordering(), single_thread as i32);
+
+            LLVMSetValueName(val, c_string.as_ptr());
+
+            val
+        };
+
+        Ok(StructValue::new(val))
+    }
+
     // SubType: <P>(&self, ptr: &PointerValue<P>, name) -> IntValue<bool> {
-    pub fn build_is_null(&self, ptr: &PointerValue, name: &str) -> IntValue {
+    pub fn build_is_null(&self, ptr: &PointerValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let val = unsafe {
@@ -978,7 +1390,7 @@ impl Builder {
     }
 
     // SubType: <P>(&self, ptr: &PointerValue<P>, name) -> IntValue<bool> {
-    pub fn build_is_not_null(&self, ptr: &PointerValue, name: &str) -> IntValue {
+    pub fn build_is_not_null(&self, ptr: &PointerValue<'ctx, 'm>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let val = unsafe {
@@ -989,7 +1401,7 @@ impl Builder {
     }
 
     // SubType: <I, P>(&self, int: &IntValue<I>, ptr_type: &PointerType<P>, name) -> PointerValue<P> {
-    pub fn build_int_to_ptr(&self, int: &IntValue, ptr_type: &PointerType, name: &str) -> PointerValue {
+    pub fn build_int_to_ptr(&self, int: &IntValue<'ctx, 'm>, ptr_type: &PointerType<'ctx>, name: &str) -> PointerValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -1000,7 +1412,7 @@ impl Builder {
     }
 
     // SubType: <I, P>(&self, ptr: &PointerValue<P>, int_type: &IntType<I>, name) -> IntValue<I> {
-    pub fn build_ptr_to_int(&self, ptr: &PointerValue, int_type: &IntType, name: &str) -> IntValue {
+    pub fn build_ptr_to_int(&self, ptr: &PointerValue<'ctx, 'm>, int_type: &IntType<'ctx>, name: &str) -> IntValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -1019,7 +1431,7 @@ impl Builder {
     // REVIEW: Returning InstructionValue is the safe move here; but if the value means something
     // (IE the result of the switch) it should probably return BasicValueEnum?
     // SubTypes: I think value and case values must be the same subtype (maybe). Case value might need to be constants
-    pub fn build_switch(&self, value: &IntValue, else_block: &BasicBlock, cases: &[(&IntValue, &BasicBlock)]) -> InstructionValue {
+    pub fn build_switch(&self, value: &IntValue<'ctx, 'm>, else_block: &BasicBlock<'ctx, 'm>, cases: &[(&IntValue<'ctx, 'm>, &BasicBlock<'ctx, 'm>)]) -> InstructionValue<'ctx, 'm> {
         let switch_value = unsafe {
             LLVMBuildSwitch(self.builder, value.as_value_ref(), else_block.basic_block, cases.len() as u32)
         };
@@ -1033,7 +1445,7 @@ impl Builder {
         InstructionValue::new(switch_value)
     }
 
-    pub fn build_global_string(&self, value: &str, name: &str) -> GlobalValue {
+    pub fn build_global_string(&self, value: &str, name: &str) -> GlobalValue<'ctx, 'm> {
         let c_string_value = CString::new(value).expect("Conversion to CString failed unexpectedly");
         let c_string_name = CString::new(name).expect("Conversion to CString failed unexpectedly");
         let value = unsafe {
@@ -1043,7 +1455,7 @@ impl Builder {
         GlobalValue::new(value)
     }
 
-    pub fn build_global_string_ptr(&self, value: &str, name: &str) -> GlobalValue {
+    pub fn build_global_string_ptr(&self, value: &str, name: &str) -> GlobalValue<'ctx, 'm> {
         let c_string_value = CString::new(value).expect("Conversion to CString failed unexpectedly");
         let c_string_name = CString::new(name).expect("Conversion to CString failed unexpectedly");
         let value = unsafe {
@@ -1054,10 +1466,1126 @@ impl Builder {
     }
 }
 
-impl Drop for Builder {
-    fn drop(&mut self) {
-        unsafe {
-            LLVMDisposeBuilder(self.builder);
+impl<'m> Builder<'static, 'm> {
+    pub fn create() -> Self {
+        let builder = unsafe {
+            LLVMCreateBuilder()
+        };
+
+        Builder::new(builder)
+    }
+}
+
+/// A mistake caught by a `CheckedBuilder` before it reached LLVM.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BuilderError {
+    /// The builder is not positioned in a basic block which belongs to a function.
+    UnsetPosition,
+    /// The insertion point is at the end of a block which already has a terminator.
+    BlockTerminated,
+    /// A terminator was requested somewhere other than the end of a block.
+    MisplacedTerminator,
+    /// Phi nodes must be grouped together at the start of a block.
+    MisplacedPhi,
+    /// Landing pads must be the first non-phi instruction in a block.
+    MisplacedLandingPad,
+    /// The instruction to position at does not belong to the given basic block.
+    ForeignInstruction,
+    /// A branch target belongs to a different function than the one being built.
+    ForeignBasicBlock,
+    /// An operand does not have the type the instruction requires.
+    TypeMismatch { expected: String, found: String },
+    /// The cast cannot convert a value of one type into the other.
+    InvalidCast { from: String, to: String },
+    /// An aggregate, struct field or vector element index is out of range.
+    IndexOutOfRange { index: u32, len: u32 },
+    /// A call passes the wrong number of arguments.
+    ArgumentCountMismatch { expected: u32, found: u32 },
+    /// An aggregate return passes a different number of values than the return type has elements.
+    ReturnCountMismatch { expected: u32, found: u32 },
+    /// A landing pad which is not a cleanup has no clauses, so it could never be entered.
+    EmptyLandingPad,
+    /// A `cmpxchg`'s failure ordering is stronger than its success ordering.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    StrongerFailureOrdering { success: AtomicOrdering, failure: CmpXchgFailureOrdering },
+}
+
+// What is about to be inserted, which determines where in a block it may go
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum InsertKind {
+    Phi,
+    LandingPad,
+    Instruction,
+    Terminator,
+}
+
+fn type_of(value: LLVMValueRef) -> LLVMTypeRef {
+    unsafe {
+        LLVMTypeOf(value)
+    }
+}
+
+fn element_type_of(ptr_or_vec: LLVMValueRef) -> LLVMTypeRef {
+    unsafe {
+        LLVMGetElementType(LLVMTypeOf(ptr_or_vec))
+    }
+}
+
+fn type_to_string(type_: LLVMTypeRef) -> String {
+    unsafe {
+        LLVMString::new(LLVMPrintTypeToString(type_)).to_string()
+    }
+}
+
+fn type_mismatch(expected: &str, found: LLVMTypeRef) -> BuilderError {
+    BuilderError::TypeMismatch {
+        expected: expected.to_string(),
+        found: type_to_string(found),
+    }
+}
+
+fn check_type(expected: LLVMTypeRef, found: LLVMTypeRef) -> Result<(), BuilderError> {
+    if expected == found {
+        return Ok(());
+    }
+
+    Err(BuilderError::TypeMismatch {
+        expected: type_to_string(expected),
+        found: type_to_string(found),
+    })
+}
+
+fn check_cast(from: LLVMTypeRef, to: LLVMTypeRef, is_valid: bool) -> Result<(), BuilderError> {
+    if is_valid {
+        return Ok(());
+    }
+
+    Err(BuilderError::InvalidCast {
+        from: type_to_string(from),
+        to: type_to_string(to),
+    })
+}
+
+fn check_index(index: u32, len: u32) -> Result<(), BuilderError> {
+    if index < len {
+        return Ok(());
+    }
+
+    Err(BuilderError::IndexOutOfRange { index, len })
+}
+
+fn int_width(type_: LLVMTypeRef) -> u32 {
+    unsafe {
+        LLVMGetIntTypeWidth(type_)
+    }
+}
+
+fn float_width(type_: LLVMTypeRef) -> u32 {
+    let kind = unsafe {
+        LLVMGetTypeKind(type_)
+    };
+
+    match kind {
+        LLVMTypeKind::LLVMHalfTypeKind => 16,
+        LLVMTypeKind::LLVMFloatTypeKind => 32,
+        LLVMTypeKind::LLVMDoubleTypeKind => 64,
+        LLVMTypeKind::LLVMX86_FP80TypeKind => 80,
+        // fp128 and ppc_fp128 are the same width but different formats, so neither
+        // extends or truncates into the other
+        LLVMTypeKind::LLVMFP128TypeKind |
+        LLVMTypeKind::LLVMPPC_FP128TypeKind => 128,
+        _ => 0,
+    }
+}
+
+fn is_int(type_: LLVMTypeRef) -> bool {
+    unsafe {
+        matches!(LLVMGetTypeKind(type_), LLVMTypeKind::LLVMIntegerTypeKind)
+    }
+}
+
+fn is_float(type_: LLVMTypeRef) -> bool {
+    float_width(type_) != 0
+}
+
+fn is_pointer(type_: LLVMTypeRef) -> bool {
+    unsafe {
+        matches!(LLVMGetTypeKind(type_), LLVMTypeKind::LLVMPointerTypeKind)
+    }
+}
+
+fn check_int(value: LLVMValueRef) -> Result<(), BuilderError> {
+    if !is_int(type_of(value)) {
+        return Err(type_mismatch("an integer", type_of(value)));
+    }
+
+    Ok(())
+}
+
+fn check_pointer(value: LLVMValueRef) -> Result<(), BuilderError> {
+    if !is_pointer(type_of(value)) {
+        return Err(type_mismatch("a pointer", type_of(value)));
+    }
+
+    Ok(())
+}
+
+// The element type and length of a vector type, or the type itself and None for a scalar
+fn scalar_type(type_: LLVMTypeRef) -> (LLVMTypeRef, Option<u32>) {
+    unsafe {
+        match LLVMGetTypeKind(type_) {
+            LLVMTypeKind::LLVMVectorTypeKind => (LLVMGetElementType(type_), Some(LLVMGetVectorSize(type_))),
+            _ => (type_, None),
         }
     }
 }
+
+// The width in bits of an int, float or vector of them, or 0 for any other type
+fn bit_width(type_: LLVMTypeRef) -> u32 {
+    let (scalar, len) = scalar_type(type_);
+    let width = if is_int(scalar) { int_width(scalar) } else { float_width(scalar) };
+
+    width * len.unwrap_or(1)
+}
+
+// Mirrors the rules LLVM's CastInst::castIsValid applies to each cast opcode
+fn is_valid_cast(op: &LLVMOpcode, from: LLVMTypeRef, to: LLVMTypeRef) -> bool {
+    let (from_scalar, from_len) = scalar_type(from);
+    let (to_scalar, to_len) = scalar_type(to);
+
+    if from_len != to_len && !matches!(*op, LLVMOpcode::LLVMBitCast) {
+        return false;
+    }
+
+    match *op {
+        LLVMOpcode::LLVMTrunc => is_int(from_scalar) && is_int(to_scalar) && int_width(to_scalar) < int_width(from_scalar),
+        LLVMOpcode::LLVMZExt |
+        LLVMOpcode::LLVMSExt => is_int(from_scalar) && is_int(to_scalar) && int_width(to_scalar) > int_width(from_scalar),
+        LLVMOpcode::LLVMFPTrunc => is_float(from_scalar) && is_float(to_scalar) && float_width(to_scalar) < float_width(from_scalar),
+        LLVMOpcode::LLVMFPExt => is_float(from_scalar) && is_float(to_scalar) && float_width(to_scalar) > float_width(from_scalar),
+        LLVMOpcode::LLVMFPToUI |
+        LLVMOpcode::LLVMFPToSI => is_float(from_scalar) && is_int(to_scalar),
+        LLVMOpcode::LLVMUIToFP |
+        LLVMOpcode::LLVMSIToFP => is_int(from_scalar) && is_float(to_scalar),
+        LLVMOpcode::LLVMPtrToInt => is_pointer(from_scalar) && is_int(to_scalar),
+        LLVMOpcode::LLVMIntToPtr => is_int(from_scalar) && is_pointer(to_scalar),
+        LLVMOpcode::LLVMBitCast => {
+            if is_pointer(from_scalar) || is_pointer(to_scalar) {
+                return is_pointer(from_scalar) && is_pointer(to_scalar) && from_len == to_len && unsafe {
+                    LLVMGetPointerAddressSpace(from_scalar) == LLVMGetPointerAddressSpace(to_scalar)
+                };
+            }
+
+            bit_width(from) != 0 && bit_width(from) == bit_width(to)
+        },
+        LLVMOpcode::LLVMAddrSpaceCast => is_pointer(from_scalar) && is_pointer(to_scalar) && unsafe {
+            LLVMGetPointerAddressSpace(from_scalar) != LLVMGetPointerAddressSpace(to_scalar)
+        },
+        _ => false,
+    }
+}
+
+// Checks that each GEP index is an integer and that struct fields are indexed by an
+// in range constant i32. The first index steps over the pointer itself
+fn check_gep_indices(ptr: LLVMValueRef, indexes: &[&IntValue]) -> Result<(), BuilderError> {
+    let mut indexed_type = element_type_of(ptr);
+
+    for (i, index) in indexes.iter().enumerate() {
+        check_int(index.as_value_ref())?;
+
+        if i == 0 {
+            continue;
+        }
+
+        indexed_type = unsafe {
+            match LLVMGetTypeKind(indexed_type) {
+                LLVMTypeKind::LLVMStructTypeKind => {
+                    let index_type = type_of(index.as_value_ref());
+
+                    if LLVMIsAConstantInt(index.as_value_ref()).is_null() || int_width(index_type) != 32 {
+                        return Err(type_mismatch("a constant i32 struct index", index_type));
+                    }
+
+                    let field = LLVMConstIntGetZExtValue(index.as_value_ref());
+                    let field_count = LLVMCountStructElementTypes(indexed_type);
+
+                    check_index(field.min(u32::MAX as u64) as u32, field_count)?;
+
+                    LLVMStructGetTypeAtIndex(indexed_type, field as u32)
+                },
+                LLVMTypeKind::LLVMArrayTypeKind |
+                LLVMTypeKind::LLVMVectorTypeKind => LLVMGetElementType(indexed_type),
+                _ => return Err(type_mismatch("an array, vector or struct to index into", indexed_type)),
+            }
+        };
+    }
+
+    Ok(())
+}
+
+// Checks a vector element index, which must be an integer and, if constant, in range
+fn check_element_index(vector: &VectorValue, index: &IntValue) -> Result<(), BuilderError> {
+    check_int(index.as_value_ref())?;
+
+    unsafe {
+        if !LLVMIsAConstantInt(index.as_value_ref()).is_null() {
+            let index = LLVMConstIntGetZExtValue(index.as_value_ref());
+
+            check_index(index.min(u32::MAX as u64) as u32, LLVMGetVectorSize(type_of(vector.as_value_ref())))?;
+        }
+    }
+
+    Ok(())
+}
+
+// The mem* intrinsics take pointers and are only overloaded on an i32 or i64 length
+fn check_mem_operands(pointers: &[&PointerValue], size: &IntValue) -> Result<(), BuilderError> {
+    for pointer in pointers {
+        check_pointer(pointer.as_value_ref())?;
+    }
+
+    let size_type = type_of(size.as_value_ref());
+
+    if !is_int(size_type) || !matches!(int_width(size_type), 32 | 64) {
+        return Err(type_mismatch("an i32 or i64 length", size_type));
+    }
+
+    Ok(())
+}
+
+fn is_bool(type_: LLVMTypeRef) -> bool {
+    let kind = unsafe {
+        LLVMGetTypeKind(type_)
+    };
+
+    match kind {
+        LLVMTypeKind::LLVMIntegerTypeKind => int_width(type_) == 1,
+        _ => false,
+    }
+}
+
+// The element types of a struct or array type, or None for any other type
+fn aggregate_element_types(type_: LLVMTypeRef) -> Option<Vec<LLVMTypeRef>> {
+    unsafe {
+        match LLVMGetTypeKind(type_) {
+            LLVMTypeKind::LLVMStructTypeKind => {
+                let count = LLVMCountStructElementTypes(type_);
+                let mut element_types = vec![ptr::null_mut(); count as usize];
+
+                LLVMGetStructElementTypes(type_, element_types.as_mut_ptr());
+
+                Some(element_types)
+            },
+            LLVMTypeKind::LLVMArrayTypeKind => Some(vec![LLVMGetElementType(type_); LLVMGetArrayLength(type_) as usize]),
+            _ => None,
+        }
+    }
+}
+
+fn is_phi(instruction: &InstructionValue) -> bool {
+    let opcode = unsafe {
+        LLVMGetInstructionOpcode(instruction.as_value_ref())
+    };
+
+    matches!(opcode, LLVMOpcode::LLVMPHI)
+}
+
+// Checks the arguments against the parameters of `fn_type`
+fn check_call_args(fn_type: LLVMTypeRef, args: &[&BasicValue]) -> Result<(), BuilderError> {
+    let (param_types, is_var_arg) = unsafe {
+        let count = LLVMCountParamTypes(fn_type);
+        let mut param_types = vec![ptr::null_mut(); count as usize];
+
+        LLVMGetParamTypes(fn_type, param_types.as_mut_ptr());
+
+        (param_types, LLVMIsFunctionVarArg(fn_type) == 1)
+    };
+
+    if args.len() < param_types.len() || (!is_var_arg && args.len() > param_types.len()) {
+        return Err(BuilderError::ArgumentCountMismatch {
+            expected: param_types.len() as u32,
+            found: args.len() as u32,
+        });
+    }
+
+    for (param_type, arg) in param_types.iter().zip(args) {
+        check_type(*param_type, type_of(arg.as_value_ref()))?;
+    }
+
+    Ok(())
+}
+
+/// A `Builder` which checks operand types, the insertion position and whether the current
+/// block is already terminated before building each instruction, returning a `BuilderError`
+/// instead of handing LLVM invalid IR.
+///
+/// # Example
+///
+/// ```
+/// use inkwell::builder::BuilderError;
+/// use inkwell::context::Context;
+///
+/// let context = Context::create();
+/// let module = context.create_module("my_module");
+/// let builder = context.create_checked_builder();
+/// let i32_type = context.i32_type();
+/// let i64_type = context.i64_type();
+/// let fn_type = i32_type.fn_type(&[&i32_type, &i64_type], false);
+/// let function = module.add_function("add", &fn_type, None);
+/// let lhs = function.get_first_param().unwrap().into_int_value();
+/// let rhs = function.get_nth_param(1).unwrap().into_int_value();
+///
+/// builder.position_at_end(&function.append_basic_block("entry"));
+///
+/// assert_eq!(builder.build_int_add(&lhs, &rhs, "sum"), Err(BuilderError::TypeMismatch {
+///     expected: "i32".into(),
+///     found: "i64".into(),
+/// }));
+/// ```
+pub struct CheckedBuilder<'ctx: 'm, 'm> {
+    builder: Builder<'ctx, 'm>,
+    // The C API cannot tell where in the block the builder is positioned, so it is tracked
+    // here. None means new instructions are appended to the end of the block
+    insert_before: Cell<Option<InstructionValue<'ctx, 'm>>>,
+}
+
+// Binary operations whose operands must have the same type
+macro_rules! checked_binary_ops {
+    ($value_type:ident: $($method:ident),*) => {
+        $(
+            pub fn $method(&self, lhs: &$value_type<'ctx, 'm>, rhs: &$value_type<'ctx, 'm>, name: &str) -> Result<$value_type<'ctx, 'm>, BuilderError> {
+                self.check_position(InsertKind::Instruction)?;
+
+                check_type(type_of(lhs.as_value_ref()), type_of(rhs.as_value_ref()))?;
+
+                Ok(self.builder.$method(lhs, rhs, name))
+            }
+        )*
+    }
+}
+
+// Unary operations which only depend on the insertion position
+macro_rules! checked_unary_ops {
+    ($value_type:ident: $($method:ident),*) => {
+        $(
+            pub fn $method(&self, value: &$value_type<'ctx, 'm>, name: &str) -> Result<$value_type<'ctx, 'm>, BuilderError> {
+                self.check_position(InsertKind::Instruction)?;
+
+                Ok(self.builder.$method(value, name))
+            }
+        )*
+    }
+}
+
+// Casts which are only valid when `$is_valid(from_width, to_width)` holds
+macro_rules! checked_casts {
+    ($value_type:ident, $target_type:ident, $width:ident, $result_type:ident: $($method:ident => $is_valid:expr),*) => {
+        $(
+            pub fn $method(&self, value: &$value_type<'ctx, 'm>, target_type: &$target_type<'ctx>, name: &str) -> Result<$result_type<'ctx, 'm>, BuilderError> {
+                self.check_position(InsertKind::Instruction)?;
+
+                let (from, to) = (type_of(value.as_value_ref()), target_type.as_type_ref());
+                let is_valid: fn(u32, u32) -> bool = $is_valid;
+
+                check_cast(from, to, is_valid($width(from), $width(to)))?;
+
+                Ok(self.builder.$method(value, target_type, name))
+            }
+        )*
+    }
+}
+
+// Casts between any two values of the given types
+macro_rules! checked_conversions {
+    ($value_type:ident, $target_type:ident, $result_type:ident: $($method:ident),*) => {
+        $(
+            pub fn $method(&self, value: &$value_type<'ctx, 'm>, target_type: &$target_type<'ctx>, name: &str) -> Result<$result_type<'ctx, 'm>, BuilderError> {
+                self.check_position(InsertKind::Instruction)?;
+
+                Ok(self.builder.$method(value, target_type, name))
+            }
+        )*
+    }
+}
+
+impl<'ctx, 'm> CheckedBuilder<'ctx, 'm> {
+    pub(crate) fn new(builder: Builder<'ctx, 'm>) -> Self {
+        CheckedBuilder {
+            builder,
+            insert_before: Cell::new(None),
+        }
+    }
+
+    // Returns the current block if `kind` may be inserted at the builder's position
+    fn check_position(&self, kind: InsertKind) -> Result<BasicBlock<'ctx, 'm>, BuilderError> {
+        let basic_block = match self.builder.get_insert_block() {
+            Some(basic_block) => basic_block,
+            None => return Err(BuilderError::UnsetPosition),
+        };
+
+        if basic_block.get_parent().is_none() {
+            return Err(BuilderError::UnsetPosition);
+        }
+
+        let previous = match self.insert_before.get() {
+            Some(next) => {
+                if kind == InsertKind::Terminator {
+                    return Err(BuilderError::MisplacedTerminator);
+                }
+
+                if kind != InsertKind::Phi && is_phi(&next) {
+                    return Err(BuilderError::MisplacedPhi);
+                }
+
+                next.get_previous_instruction()
+            },
+            None => {
+                if basic_block.get_terminator().is_some() {
+                    return Err(BuilderError::BlockTerminated);
+                }
+
+                basic_block.get_last_instruction()
+            },
+        };
+
+        let follows_non_phi = previous.is_some_and(|previous| !is_phi(&previous));
+
+        match kind {
+            InsertKind::Phi if follows_non_phi => Err(BuilderError::MisplacedPhi),
+            InsertKind::LandingPad if follows_non_phi => Err(BuilderError::MisplacedLandingPad),
+            _ => Ok(basic_block),
+        }
+    }
+
+    // Checks that the blocks belong to the same function as `current_block`
+    fn check_same_function(current_block: &BasicBlock<'ctx, 'm>, basic_blocks: &[&BasicBlock<'ctx, 'm>]) -> Result<(), BuilderError> {
+        let function = current_block.get_parent();
+
+        if basic_blocks.iter().any(|basic_block| basic_block.get_parent() != function) {
+            return Err(BuilderError::ForeignBasicBlock);
+        }
+
+        Ok(())
+    }
+
+    fn check_pointee(ptr: &PointerValue<'ctx, 'm>, value: LLVMValueRef) -> Result<(), BuilderError> {
+        check_type(element_type_of(ptr.as_value_ref()), type_of(value))
+    }
+
+    fn check_sized(type_: LLVMTypeRef, expected: &str) -> Result<(), BuilderError> {
+        let is_sized = unsafe {
+            match LLVMGetTypeKind(type_) {
+                LLVMTypeKind::LLVMFunctionTypeKind |
+                LLVMTypeKind::LLVMVoidTypeKind |
+                LLVMTypeKind::LLVMLabelTypeKind |
+                LLVMTypeKind::LLVMMetadataTypeKind => false,
+                _ => LLVMTypeIsSized(type_) == 1,
+            }
+        };
+
+        if !is_sized {
+            return Err(type_mismatch(expected, type_));
+        }
+
+        Ok(())
+    }
+
+    pub fn position_at(&self, basic_block: &BasicBlock<'ctx, 'm>, instruction: &InstructionValue<'ctx, 'm>) -> Result<(), BuilderError> {
+        if instruction.get_parent().as_ref() != Some(basic_block) {
+            return Err(BuilderError::ForeignInstruction);
+        }
+
+        self.builder.position_at(basic_block, instruction);
+        self.insert_before.set(Some(*instruction));
+
+        Ok(())
+    }
+
+    pub fn position_before(&self, instruction: &InstructionValue<'ctx, 'm>) -> Result<(), BuilderError> {
+        if instruction.get_parent().is_none() {
+            return Err(BuilderError::ForeignInstruction);
+        }
+
+        self.builder.position_before(instruction);
+        self.insert_before.set(Some(*instruction));
+
+        Ok(())
+    }
+
+    pub fn position_at_end(&self, basic_block: &BasicBlock<'ctx, 'm>) {
+        self.builder.position_at_end(basic_block);
+        self.insert_before.set(None);
+    }
+
+    pub fn clear_insertion_position(&self) {
+        self.builder.clear_insertion_position();
+        self.insert_before.set(None);
+    }
+
+    pub fn get_insert_block(&self) -> Option<BasicBlock<'ctx, 'm>> {
+        self.builder.get_insert_block()
+    }
+
+    pub fn insert_instruction(&self, instruction: &InstructionValue<'ctx, 'm>, name: Option<&str>) -> Result<(), BuilderError> {
+        let is_terminator = unsafe {
+            !LLVMIsATerminatorInst(instruction.as_value_ref()).is_null()
+        };
+        let kind = if is_terminator {
+            InsertKind::Terminator
+        } else if is_phi(instruction) {
+            InsertKind::Phi
+        } else {
+            InsertKind::Instruction
+        };
+
+        self.check_position(kind)?;
+        self.builder.insert_instruction(instruction, name);
+
+        Ok(())
+    }
+
+    pub fn set_current_debug_location(&self, location: &MetadataValue<'ctx, 'm>) {
+        self.builder.set_current_debug_location(location)
+    }
+
+    pub fn get_current_debug_location(&self) -> Option<MetadataValue<'ctx, 'm>> {
+        self.builder.get_current_debug_location()
+    }
+
+    pub fn unset_current_debug_location(&self) {
+        self.builder.unset_current_debug_location()
+    }
+
+    pub fn build_return(&self, value: Option<&BasicValue<'ctx, 'm>>) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        let basic_block = self.check_position(InsertKind::Terminator)?;
+        let return_type = unsafe {
+            LLVMGetReturnType(element_type_of(basic_block.get_parent().unwrap().as_value_ref()))
+        };
+
+        match value {
+            Some(value) => check_type(return_type, type_of(value.as_value_ref()))?,
+            None => {
+                let is_void = unsafe {
+                    matches!(LLVMGetTypeKind(return_type), LLVMTypeKind::LLVMVoidTypeKind)
+                };
+
+                if !is_void {
+                    return Err(BuilderError::TypeMismatch {
+                        expected: type_to_string(return_type),
+                        found: "void".into(),
+                    });
+                }
+            },
+        }
+
+        Ok(self.builder.build_return(value))
+    }
+
+    pub fn build_aggregate_return(&self, values: &[&BasicValue<'ctx, 'm>]) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        let basic_block = self.check_position(InsertKind::Terminator)?;
+        let return_type = unsafe {
+            LLVMGetReturnType(element_type_of(basic_block.get_parent().unwrap().as_value_ref()))
+        };
+        let element_types = match aggregate_element_types(return_type) {
+            Some(element_types) => element_types,
+            None => return Err(type_mismatch("an aggregate return type", return_type)),
+        };
+
+        if element_types.len() != values.len() {
+            return Err(BuilderError::ReturnCountMismatch {
+                expected: element_types.len() as u32,
+                found: values.len() as u32,
+            });
+        }
+
+        for (element_type, value) in element_types.iter().zip(values) {
+            check_type(*element_type, type_of(value.as_value_ref()))?;
+        }
+
+        Ok(self.builder.build_aggregate_return(values))
+    }
+
+    pub fn build_call(&self, function: &FunctionValue<'ctx, 'm>, args: &[&BasicValue<'ctx, 'm>], name: &str) -> Result<CallSiteValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_call_args(element_type_of(function.as_value_ref()), args)?;
+
+        Ok(self.builder.build_call(function, args, name))
+    }
+
+    pub fn build_indirect_call(&self, function_pointer: &PointerValue<'ctx, 'm>, args: &[&BasicValue<'ctx, 'm>], name: &str) -> Result<CallSiteValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        let fn_type = element_type_of(function_pointer.as_value_ref());
+        let is_fn_type = unsafe {
+            matches!(LLVMGetTypeKind(fn_type), LLVMTypeKind::LLVMFunctionTypeKind)
+        };
+
+        if !is_fn_type {
+            return Err(type_mismatch("a pointer to a function", type_of(function_pointer.as_value_ref())));
+        }
+
+        check_call_args(fn_type, args)?;
+
+        self.builder.build_indirect_call(function_pointer, args, name)
+    }
+
+    pub fn build_invoke(&self, function: &FunctionValue<'ctx, 'm>, args: &[&BasicValue<'ctx, 'm>], then_block: &BasicBlock<'ctx, 'm>, catch_block: &BasicBlock<'ctx, 'm>, name: &str) -> Result<CallSiteValue<'ctx, 'm>, BuilderError> {
+        let basic_block = self.check_position(InsertKind::Terminator)?;
+
+        CheckedBuilder::check_same_function(&basic_block, &[then_block, catch_block])?;
+        check_call_args(element_type_of(function.as_value_ref()), args)?;
+
+        Ok(self.builder.build_invoke(function, args, then_block, catch_block, name))
+    }
+
+    pub fn build_landing_pad(&self, exception_type: &BasicType<'ctx>, personality_function: &FunctionValue<'ctx, 'm>, clauses: &[&BasicValue<'ctx, 'm>], is_cleanup: bool, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::LandingPad)?;
+
+        CheckedBuilder::check_sized(exception_type.as_type_ref(), "a sized type")?;
+
+        if clauses.is_empty() && !is_cleanup {
+            return Err(BuilderError::EmptyLandingPad);
+        }
+
+        for clause in clauses {
+            let clause_type = type_of(clause.as_value_ref());
+            let is_valid = unsafe {
+                LLVMIsConstant(clause.as_value_ref()) == 1 && matches!(LLVMGetTypeKind(clause_type), LLVMTypeKind::LLVMPointerTypeKind | LLVMTypeKind::LLVMArrayTypeKind)
+            };
+
+            if !is_valid {
+                return Err(type_mismatch("a constant pointer or array clause", clause_type));
+            }
+        }
+
+        Ok(self.builder.build_landing_pad(exception_type, personality_function, clauses, is_cleanup, name))
+    }
+
+    pub fn build_resume(&self, value: &BasicValue<'ctx, 'm>) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        let basic_block = self.check_position(InsertKind::Terminator)?;
+
+        // Every landing pad in a function must have the type resume propagates
+        let landing_pad = basic_block.get_parent()
+                                     .unwrap()
+                                     .get_basic_blocks()
+                                     .iter()
+                                     .flat_map(|basic_block| basic_block.get_first_instruction())
+                                     .find(|instruction| instruction.get_opcode() == InstructionOpcode::LandingPad);
+
+        if let Some(landing_pad) = landing_pad {
+            check_type(type_of(landing_pad.as_value_ref()), type_of(value.as_value_ref()))?;
+        }
+
+        Ok(self.builder.build_resume(value))
+    }
+
+    pub fn build_gep(&self, ptr: &PointerValue<'ctx, 'm>, ordered_indexes: &[&IntValue<'ctx, 'm>], name: &str) -> Result<PointerValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_sized(element_type_of(ptr.as_value_ref()), "a pointer to a sized type")?;
+        check_gep_indices(ptr.as_value_ref(), ordered_indexes)?;
+
+        Ok(self.builder.build_gep(ptr, ordered_indexes, name))
+    }
+
+    pub fn build_in_bounds_gep(&self, ptr: &PointerValue<'ctx, 'm>, ordered_indexes: &[&IntValue<'ctx, 'm>], name: &str) -> Result<PointerValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_sized(element_type_of(ptr.as_value_ref()), "a pointer to a sized type")?;
+        check_gep_indices(ptr.as_value_ref(), ordered_indexes)?;
+
+        Ok(self.builder.build_in_bounds_gep(ptr, ordered_indexes, name))
+    }
+
+    pub fn build_struct_gep(&self, ptr: &PointerValue<'ctx, 'm>, index: u32, name: &str) -> Result<PointerValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        let pointee_type = element_type_of(ptr.as_value_ref());
+        let field_count = unsafe {
+            match LLVMGetTypeKind(pointee_type) {
+                LLVMTypeKind::LLVMStructTypeKind => LLVMCountStructElementTypes(pointee_type),
+                _ => return Err(type_mismatch("a pointer to a struct", type_of(ptr.as_value_ref()))),
+            }
+        };
+
+        check_index(index, field_count)?;
+
+        Ok(self.builder.build_struct_gep(ptr, index, name))
+    }
+
+    pub fn build_ptr_diff(&self, lhs_ptr: &PointerValue<'ctx, 'm>, rhs_ptr: &PointerValue<'ctx, 'm>, name: &str) -> Result<IntValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(lhs_ptr.as_value_ref()), type_of(rhs_ptr.as_value_ref()))?;
+
+        Ok(self.builder.build_ptr_diff(lhs_ptr, rhs_ptr, name))
+    }
+
+    pub fn build_phi<T: BasicType<'ctx>>(&self, type_: &T, name: &str) -> Result<PhiValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Phi)?;
+
+        Ok(self.builder.build_phi(type_, name))
+    }
+
+    pub fn build_store<V: BasicValue<'ctx, 'm>>(&self, ptr: &PointerValue<'ctx, 'm>, value: &V) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_pointee(ptr, value.as_value_ref())?;
+
+        Ok(self.builder.build_store(ptr, value))
+    }
+
+    pub fn build_load(&self, ptr: &PointerValue<'ctx, 'm>, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_sized(element_type_of(ptr.as_value_ref()), "a pointer to a sized type")?;
+
+        Ok(self.builder.build_load(ptr, name))
+    }
+
+    pub fn build_alloca<T: BasicType<'ctx>>(&self, type_: &T, name: &str) -> Result<PointerValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_sized(type_.as_type_ref(), "a sized type")?;
+
+        Ok(self.builder.build_alloca(type_, name))
+    }
+
+    pub fn build_array_alloca<T: BasicType<'ctx>>(&self, type_: &T, size: &IntValue<'ctx, 'm>, name: &str) -> Result<PointerValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_sized(type_.as_type_ref(), "a sized type")?;
+
+        Ok(self.builder.build_array_alloca(type_, size, name))
+    }
+
+    pub fn build_malloc<T: BasicType<'ctx>>(&self, type_: &T, name: &str) -> Result<PointerValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_sized(type_.as_type_ref(), "a sized type")?;
+
+        Ok(self.builder.build_malloc(type_, name))
+    }
+
+    pub fn build_array_malloc<T: BasicType<'ctx>>(&self, type_: &T, size: &IntValue<'ctx, 'm>, name: &str) -> Result<PointerValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_sized(type_.as_type_ref(), "a sized type")?;
+
+        Ok(self.builder.build_array_malloc(type_, size, name))
+    }
+
+    pub fn build_free(&self, ptr: &PointerValue<'ctx, 'm>) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_pointer(ptr.as_value_ref())?;
+
+        Ok(self.builder.build_free(ptr))
+    }
+
+    checked_binary_ops!(IntValue: build_int_add, build_int_nsw_add, build_int_nuw_add, build_int_sub, build_int_nsw_sub, build_int_nuw_sub,
+                        build_int_mul, build_int_nsw_mul, build_int_nuw_mul, build_int_unsigned_div, build_int_signed_div,
+                        build_int_exact_signed_div, build_int_unsigned_rem, build_int_signed_rem, build_and, build_or, build_xor,
+                        build_left_shift);
+    checked_binary_ops!(FloatValue: build_float_add, build_float_sub, build_float_mul, build_float_div, build_float_rem);
+    checked_unary_ops!(IntValue: build_int_neg, build_int_nsw_neg, build_int_nuw_neg, build_not);
+    checked_unary_ops!(FloatValue: build_float_neg);
+
+    pub fn build_right_shift(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, sign_extend: bool, name: &str) -> Result<IntValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(lhs.as_value_ref()), type_of(rhs.as_value_ref()))?;
+
+        Ok(self.builder.build_right_shift(lhs, rhs, sign_extend, name))
+    }
+
+    pub fn build_int_add_with_overflow(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, is_signed: bool, name: &str) -> Result<(IntValue<'ctx, 'm>, IntValue<'ctx, 'm>), BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(lhs.as_value_ref()), type_of(rhs.as_value_ref()))?;
+
+        Ok(self.builder.build_int_add_with_overflow(lhs, rhs, is_signed, name))
+    }
+
+    pub fn build_int_sub_with_overflow(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, is_signed: bool, name: &str) -> Result<(IntValue<'ctx, 'm>, IntValue<'ctx, 'm>), BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(lhs.as_value_ref()), type_of(rhs.as_value_ref()))?;
+
+        Ok(self.builder.build_int_sub_with_overflow(lhs, rhs, is_signed, name))
+    }
+
+    pub fn build_int_mul_with_overflow(&self, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, is_signed: bool, name: &str) -> Result<(IntValue<'ctx, 'm>, IntValue<'ctx, 'm>), BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(lhs.as_value_ref()), type_of(rhs.as_value_ref()))?;
+
+        Ok(self.builder.build_int_mul_with_overflow(lhs, rhs, is_signed, name))
+    }
+
+    checked_casts!(IntValue, IntType, int_width, IntValue:
+                   build_int_s_extend => |from, to| to > from,
+                   build_int_z_extend => |from, to| to > from,
+                   build_int_s_extend_or_bit_cast => |from, to| to >= from,
+                   build_int_z_extend_or_bit_cast => |from, to| to >= from,
+                   build_int_truncate => |from, to| to < from,
+                   build_int_truncate_or_bit_cast => |from, to| to <= from);
+    checked_casts!(FloatValue, FloatType, float_width, FloatValue:
+                   build_float_trunc => |from, to| to < from,
+                   build_float_ext => |from, to| to > from);
+    checked_conversions!(IntValue, IntType, IntValue: build_int_cast);
+    checked_conversions!(FloatValue, FloatType, FloatValue: build_float_cast);
+    checked_conversions!(FloatValue, IntType, IntValue: build_float_to_unsigned_int, build_float_to_signed_int);
+    checked_conversions!(IntValue, FloatType, FloatValue: build_unsigned_int_to_float, build_signed_int_to_float);
+    checked_conversions!(PointerValue, PointerType, PointerValue: build_pointer_cast);
+    checked_conversions!(IntValue, PointerType, PointerValue: build_int_to_ptr);
+    checked_conversions!(PointerValue, IntType, IntValue: build_ptr_to_int);
+
+    pub fn build_cast(&self, op: LLVMOpcode, from_value: &BasicValue<'ctx, 'm>, to_type: &BasicType<'ctx>, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        let (from, to) = (type_of(from_value.as_value_ref()), to_type.as_type_ref());
+
+        check_cast(from, to, is_valid_cast(&op, from, to))?;
+
+        Ok(self.builder.build_cast(op, from_value, to_type, name))
+    }
+
+    pub fn build_int_compare(&self, op: IntPredicate, lhs: &IntValue<'ctx, 'm>, rhs: &IntValue<'ctx, 'm>, name: &str) -> Result<IntValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(lhs.as_value_ref()), type_of(rhs.as_value_ref()))?;
+
+        Ok(self.builder.build_int_compare(op, lhs, rhs, name))
+    }
+
+    pub fn build_float_compare(&self, op: FloatPredicate, lhs: &FloatValue<'ctx, 'm>, rhs: &FloatValue<'ctx, 'm>, name: &str) -> Result<IntValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(lhs.as_value_ref()), type_of(rhs.as_value_ref()))?;
+
+        Ok(self.builder.build_float_compare(op, lhs, rhs, name))
+    }
+
+    pub fn build_unconditional_branch(&self, destination_block: &BasicBlock<'ctx, 'm>) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        let basic_block = self.check_position(InsertKind::Terminator)?;
+
+        CheckedBuilder::check_same_function(&basic_block, &[destination_block])?;
+
+        Ok(self.builder.build_unconditional_branch(destination_block))
+    }
+
+    pub fn build_conditional_branch(&self, comparison: &IntValue<'ctx, 'm>, then_block: &BasicBlock<'ctx, 'm>, else_block: &BasicBlock<'ctx, 'm>) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        let basic_block = self.check_position(InsertKind::Terminator)?;
+        let comparison_type = type_of(comparison.as_value_ref());
+
+        if !is_bool(comparison_type) {
+            return Err(type_mismatch("i1", comparison_type));
+        }
+
+        CheckedBuilder::check_same_function(&basic_block, &[then_block, else_block])?;
+
+        Ok(self.builder.build_conditional_branch(comparison, then_block, else_block))
+    }
+
+    pub fn build_switch(&self, value: &IntValue<'ctx, 'm>, else_block: &BasicBlock<'ctx, 'm>, cases: &[(&IntValue<'ctx, 'm>, &BasicBlock<'ctx, 'm>)]) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        let basic_block = self.check_position(InsertKind::Terminator)?;
+
+        CheckedBuilder::check_same_function(&basic_block, &[else_block])?;
+
+        for &(case_value, case_block) in cases {
+            check_type(type_of(value.as_value_ref()), type_of(case_value.as_value_ref()))?;
+            CheckedBuilder::check_same_function(&basic_block, &[case_block])?;
+        }
+
+        Ok(self.builder.build_switch(value, else_block, cases))
+    }
+
+    pub fn build_unreachable(&self) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Terminator)?;
+
+        Ok(self.builder.build_unreachable())
+    }
+
+    pub fn build_extract_value(&self, value: &AggregateValue<'ctx, 'm>, index: u32, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        let len = aggregate_element_types(type_of(value.as_value_ref())).map_or(0, |element_types| element_types.len() as u32);
+
+        check_index(index, len)?;
+
+        Ok(self.builder.build_extract_value(value, index, name))
+    }
+
+    pub fn build_insert_value(&self, value: &BasicValue<'ctx, 'm>, ptr: &PointerValue<'ctx, 'm>, index: u32, name: &str) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        let aggregate_type = type_of(value.as_value_ref());
+        let element_types = match aggregate_element_types(aggregate_type) {
+            Some(element_types) => element_types,
+            None => return Err(type_mismatch("an aggregate", aggregate_type)),
+        };
+
+        check_index(index, element_types.len() as u32)?;
+        check_type(element_types[index as usize], type_of(ptr.as_value_ref()))?;
+
+        Ok(self.builder.build_insert_value(value, ptr, index, name))
+    }
+
+    pub fn build_extract_element(&self, vector: &VectorValue<'ctx, 'm>, index: &IntValue<'ctx, 'm>, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_element_index(vector, index)?;
+
+        Ok(self.builder.build_extract_element(vector, index, name))
+    }
+
+    pub fn build_insert_element(&self, vector: &VectorValue<'ctx, 'm>, element: &BasicValue<'ctx, 'm>, index: &IntValue<'ctx, 'm>, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(element_type_of(vector.as_value_ref()), type_of(element.as_value_ref()))?;
+        check_element_index(vector, index)?;
+
+        Ok(self.builder.build_insert_element(vector, element, index, name))
+    }
+
+    pub fn build_shuffle_vector(&self, left: &VectorValue<'ctx, 'm>, right: &VectorValue<'ctx, 'm>, mask: &VectorValue<'ctx, 'm>, name: &str) -> Result<VectorValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_type(type_of(left.as_value_ref()), type_of(right.as_value_ref()))?;
+
+        let mask_element_type = element_type_of(mask.as_value_ref());
+        let is_i32 = unsafe {
+            match LLVMGetTypeKind(mask_element_type) {
+                LLVMTypeKind::LLVMIntegerTypeKind => int_width(mask_element_type) == 32,
+                _ => false,
+            }
+        };
+
+        if !is_i32 {
+            return Err(type_mismatch("a vector of i32", type_of(mask.as_value_ref())));
+        }
+
+        Ok(self.builder.build_shuffle_vector(left, right, mask, name))
+    }
+
+    pub fn build_select(&self, condition: &BasicValue<'ctx, 'm>, then_value: &BasicValue<'ctx, 'm>, else_value: &BasicValue<'ctx, 'm>, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        let (condition_type, value_type) = (type_of(condition.as_value_ref()), type_of(then_value.as_value_ref()));
+
+        check_type(value_type, type_of(else_value.as_value_ref()))?;
+
+        let is_valid = unsafe {
+            match (LLVMGetTypeKind(condition_type), LLVMGetTypeKind(value_type)) {
+                (LLVMTypeKind::LLVMVectorTypeKind, LLVMTypeKind::LLVMVectorTypeKind) => {
+                    is_bool(LLVMGetElementType(condition_type)) && LLVMGetVectorSize(condition_type) == LLVMGetVectorSize(value_type)
+                },
+                (LLVMTypeKind::LLVMVectorTypeKind, _) => false,
+                _ => is_bool(condition_type),
+            }
+        };
+
+        if !is_valid {
+            return Err(type_mismatch("i1 or a vector of i1 as long as the values", condition_type));
+        }
+
+        Ok(self.builder.build_select(condition, then_value, else_value, name))
+    }
+
+    pub fn build_va_arg(&self, list: &PointerValue<'ctx, 'm>, type_: &BasicType<'ctx>, name: &str) -> Result<BasicValueEnum<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        Ok(self.builder.build_va_arg(list, type_, name))
+    }
+
+    pub fn build_memcpy(&self, dest: &PointerValue<'ctx, 'm>, src: &PointerValue<'ctx, 'm>, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_mem_operands(&[dest, src], size)?;
+
+        Ok(self.builder.build_memcpy(dest, src, size, align, is_volatile))
+    }
+
+    pub fn build_memmove(&self, dest: &PointerValue<'ctx, 'm>, src: &PointerValue<'ctx, 'm>, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_mem_operands(&[dest, src], size)?;
+
+        Ok(self.builder.build_memmove(dest, src, size, align, is_volatile))
+    }
+
+    pub fn build_memset(&self, dest: &PointerValue<'ctx, 'm>, value: &IntValue<'ctx, 'm>, size: &IntValue<'ctx, 'm>, align: u32, is_volatile: bool) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_mem_operands(&[dest], size)?;
+
+        let value_type = type_of(value.as_value_ref());
+
+        if int_width(value_type) != 8 {
+            return Err(type_mismatch("i8", value_type));
+        }
+
+        Ok(self.builder.build_memset(dest, value, size, align, is_volatile))
+    }
+
+    pub fn build_fence(&self, fence_ordering: FenceOrdering, single_thread: bool, name: &str) -> Result<InstructionValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        Ok(self.builder.build_fence(fence_ordering, single_thread, name))
+    }
+
+    pub fn build_atomicrmw(&self, op: AtomicRMWBinOp, ptr: &PointerValue<'ctx, 'm>, value: &IntValue<'ctx, 'm>, ordering: AtomicOrdering, single_thread: bool, name: &str) -> Result<IntValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_pointee(ptr, value.as_value_ref())?;
+
+        Ok(self.builder.build_atomicrmw(op, ptr, value, ordering, single_thread, name))
+    }
+
+    /// See `Builder::build_cmpxchg`, which is only available with the `llvm3-9` and `llvm4-0` features.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn build_cmpxchg<V: BasicValue<'ctx, 'm>>(&self, ptr: &PointerValue<'ctx, 'm>, cmp: &V, new: &V, orderings: CmpXchgOrderings, single_thread: bool, name: &str) -> Result<StructValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        CheckedBuilder::check_pointee(ptr, cmp.as_value_ref())?;
+        CheckedBuilder::check_pointee(ptr, new.as_value_ref())?;
+
+        self.builder.build_cmpxchg(ptr, cmp, new, orderings, single_thread, name)
+    }
+
+    pub fn build_is_null(&self, ptr: &PointerValue<'ctx, 'm>, name: &str) -> Result<IntValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_pointer(ptr.as_value_ref())?;
+
+        Ok(self.builder.build_is_null(ptr, name))
+    }
+
+    pub fn build_is_not_null(&self, ptr: &PointerValue<'ctx, 'm>, name: &str) -> Result<IntValue<'ctx, 'm>, BuilderError> {
+        self.check_position(InsertKind::Instruction)?;
+
+        check_pointer(ptr.as_value_ref())?;
+
+        Ok(self.builder.build_is_not_null(ptr, name))
+    }
+
+    // Global strings are not instructions, but LLVM finds the module to add them to
+    // through the insertion block
+    pub fn build_global_string(&self, value: &str, name: &str) -> Result<GlobalValue<'ctx, 'm>, BuilderError> {
+        if self.builder.get_insert_block().and_then(|basic_block| basic_block.get_parent()).is_none() {
+            return Err(BuilderError::UnsetPosition);
+        }
+
+        Ok(self.builder.build_global_string(value, name))
+    }
+
+    pub fn build_global_string_ptr(&self, value: &str, name: &str) -> Result<GlobalValue<'ctx, 'm>, BuilderError> {
+        if self.builder.get_insert_block().and_then(|basic_block| basic_block.get_parent()).is_none() {
+            return Err(BuilderError::UnsetPosition);
+        }
+
+        Ok(self.builder.build_global_string_ptr(value, name))
+    }
+}
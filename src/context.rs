@@ -1,20 +1,124 @@
-use llvm_sys::core::{LLVMAppendBasicBlockInContext, LLVMContextCreate, LLVMContextDispose, LLVMCreateBuilderInContext, LLVMDoubleTypeInContext, LLVMFloatTypeInContext, LLVMFP128TypeInContext, LLVMInsertBasicBlockInContext, LLVMInt16TypeInContext, LLVMInt1TypeInContext, LLVMInt32TypeInContext, LLVMInt64TypeInContext, LLVMInt8TypeInContext, LLVMIntTypeInContext, LLVMModuleCreateWithNameInContext, LLVMStructCreateNamed, LLVMStructTypeInContext, LLVMVoidTypeInContext, LLVMHalfTypeInContext, LLVMGetGlobalContext, LLVMPPCFP128TypeInContext, LLVMConstStructInContext, LLVMDisposeMessage, LLVMMDNodeInContext, LLVMMDStringInContext, LLVMGetMDKindIDInContext};
-use llvm_sys::prelude::{LLVMContextRef, LLVMTypeRef, LLVMValueRef};
+use llvm_sys::core::{LLVMAppendBasicBlockInContext, LLVMContextCreate, LLVMContextDispose, LLVMCreateBuilderInContext, LLVMDoubleTypeInContext, LLVMFloatTypeInContext, LLVMFP128TypeInContext, LLVMInsertBasicBlockInContext, LLVMInt16TypeInContext, LLVMInt1TypeInContext, LLVMInt32TypeInContext, LLVMInt64TypeInContext, LLVMInt8TypeInContext, LLVMIntTypeInContext, LLVMModuleCreateWithNameInContext, LLVMStructCreateNamed, LLVMStructTypeInContext, LLVMVoidTypeInContext, LLVMHalfTypeInContext, LLVMGetGlobalContext, LLVMPPCFP128TypeInContext, LLVMConstStructInContext, LLVMMDNodeInContext, LLVMMDStringInContext, LLVMGetMDKindIDInContext, LLVMContextSetDiagnosticHandler, LLVMGetDiagInfoDescription, LLVMGetDiagInfoSeverity};
+use llvm_sys::prelude::{LLVMContextRef, LLVMDiagnosticInfoRef, LLVMTypeRef, LLVMValueRef};
+use llvm_sys::LLVMDiagnosticSeverity;
 use llvm_sys::ir_reader::LLVMParseIRInContext;
 
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use attributes::Attribute;
 use basic_block::BasicBlock;
-use builder::Builder;
+use builder::{Builder, CheckedBuilder};
 use memory_buffer::MemoryBuffer;
 use module::Module;
+use support::LLVMString;
 use types::{BasicType, FloatType, IntType, StructType, VoidType};
 use values::{AsValueRef, BasicValue, FunctionValue, StructValue, MetadataValue};
 
-use std::ffi::{CStr, CString};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::marker::PhantomData;
 use std::mem::forget;
 use std::ops::Deref;
+use std::os::raw::c_void;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
 use std::ptr;
 use std::rc::Rc;
 
+struct DiagnosticHandler {
+    handler: Box<FnMut(&Diagnostic)>,
+    // The first panic raised by the handler during an LLVM call, which can't unwind through LLVM
+    panic: Option<Box<Any + Send>>,
+}
+
+// Handlers are keyed by the raw context rather than stored on `Context` since
+// `ContextRef`s and the `Context`s held by modules do not share the original `Rc`.
+// Contexts are not thread safe, so diagnostics are always reported on the thread
+// which installed the handler.
+thread_local! {
+    static DIAGNOSTIC_HANDLERS: RefCell<HashMap<usize, Box<DiagnosticHandler>>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Remark,
+    Note,
+}
+
+impl DiagnosticSeverity {
+    fn new(severity: LLVMDiagnosticSeverity) -> Self {
+        match severity {
+            LLVMDiagnosticSeverity::LLVMDSError => DiagnosticSeverity::Error,
+            LLVMDiagnosticSeverity::LLVMDSWarning => DiagnosticSeverity::Warning,
+            LLVMDiagnosticSeverity::LLVMDSRemark => DiagnosticSeverity::Remark,
+            LLVMDiagnosticSeverity::LLVMDSNote => DiagnosticSeverity::Note,
+        }
+    }
+}
+
+/// A diagnostic reported by LLVM, such as an inline assembly error or an
+/// optimization remark. See `Context::set_diagnostic_handler`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    severity: DiagnosticSeverity,
+    message: String,
+}
+
+impl Diagnostic {
+    fn new(diagnostic_info: LLVMDiagnosticInfoRef) -> Self {
+        let (severity, message) = unsafe {
+            let severity = LLVMGetDiagInfoSeverity(diagnostic_info);
+            let message = LLVMString::new(LLVMGetDiagInfoDescription(diagnostic_info)).to_string();
+
+            (severity, message)
+        };
+
+        Diagnostic {
+            severity: DiagnosticSeverity::new(severity),
+            message,
+        }
+    }
+
+    pub fn get_severity(&self) -> DiagnosticSeverity {
+        self.severity
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+extern "C" fn diagnostic_handler_wrapper(diagnostic_info: LLVMDiagnosticInfoRef, handler: *mut c_void) {
+    let diagnostic = Diagnostic::new(diagnostic_info);
+    let handler = unsafe { &mut *(handler as *mut DiagnosticHandler) };
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        (handler.handler)(&diagnostic)
+    }));
+
+    if let Err(payload) = result {
+        if handler.panic.is_none() {
+            handler.panic = Some(payload);
+        }
+    }
+}
+
+// Resumes a panic raised by the diagnostic handler of `context` during the LLVM call which has
+// just returned. Called after the LLVM calls inkwell makes which report diagnostics
+pub(crate) fn resume_diagnostic_handler_panic(context: LLVMContextRef) {
+    let payload = DIAGNOSTIC_HANDLERS.with(|handlers| {
+        handlers.borrow_mut()
+                .get_mut(&(context as usize))
+                .and_then(|handler| handler.panic.take())
+    });
+
+    if let Some(payload) = payload {
+        resume_unwind(payload);
+    }
+}
+
 // From Docs: A single context is not thread safe.
 // However, different contexts can execute on different threads simultaneously.
 #[derive(Debug, PartialEq, Eq)]
@@ -33,6 +137,12 @@ impl Context {
 
     /// Creates a new `Context`.
     ///
+    /// Types, values and modules created through a `Context` are tied to a borrow of it, which
+    /// stops them from outliving it. It does not tell two `Context`s apart though: both can be
+    /// borrowed for the same lifetime, so nothing stops the values of one from being used with
+    /// the other, which LLVM doesn't allow. Only the contexts created by `Context::scope` make
+    /// mixing them up a compile error.
+    ///
     /// # Example
     ///
     /// ```
@@ -40,6 +150,21 @@ impl Context {
     ///
     /// let context = Context::create();
     /// ```
+    ///
+    /// Values from a `Context` can't be used with those of a context from `Context::scope`:
+    ///
+    /// ```compile_fail
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    ///
+    /// Context::scope(|scoped_context| {
+    ///     let module = scoped_context.create_module("my_module");
+    ///     let fn_type = context.void_type().fn_type(&[], false);
+    ///
+    ///     module.add_function("my_fn", &fn_type, None);
+    /// });
+    /// ```
     pub fn create() -> Self {
         let context = unsafe {
             LLVMContextCreate()
@@ -48,6 +173,76 @@ impl Context {
         Context::new(Rc::new(context))
     }
 
+    /// Creates a new `Context` which lives for the duration of `f`, and which `f` can only
+    /// access through a `ContextRef`.
+    ///
+    /// Unlike the borrows of contexts from `Context::create`, each `ContextRef` passed to `f` has
+    /// a lifetime of its own which can't be unified with any other, so values from different
+    /// contexts can't be used together and none of them can escape `f`. This is the only way to
+    /// have mixing up contexts caught at compile time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inkwell::context::Context;
+    ///
+    /// let num_params = Context::scope(|context| {
+    ///     let module = context.create_module("my_module");
+    ///     let i32_type = context.i32_type();
+    ///     let fn_type = i32_type.fn_type(&[&i32_type], false);
+    ///     let function = module.add_function("my_fn", &fn_type, None);
+    ///
+    ///     function.count_params()
+    /// });
+    ///
+    /// assert_eq!(num_params, 1);
+    /// ```
+    ///
+    /// Values from different contexts can't be used together:
+    ///
+    /// ```compile_fail
+    /// use inkwell::context::Context;
+    ///
+    /// Context::scope(|context| {
+    ///     Context::scope(|other_context| {
+    ///         let module = context.create_module("my_module");
+    ///         let fn_type = other_context.void_type().fn_type(&[], false);
+    ///
+    ///         module.add_function("my_fn", &fn_type, None);
+    ///     });
+    /// });
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use inkwell::context::Context;
+    ///
+    /// Context::scope(|context| {
+    ///     Context::scope(|other_context| {
+    ///         let builder = context.create_builder();
+    ///         let i8_type = other_context.i8_type();
+    ///
+    ///         builder.build_int_add(&i8_type.const_int(1, false), &i8_type.const_int(2, false), "sum");
+    ///     });
+    /// });
+    /// ```
+    ///
+    /// Nor can they outlive the context:
+    ///
+    /// ```compile_fail
+    /// use inkwell::context::Context;
+    ///
+    /// let i8_type = Context::scope(|context| context.i8_type());
+    /// ```
+    pub fn scope<F, R>(f: F) -> R
+    where
+        F: for<'ctx> FnOnce(ContextRef<'ctx>) -> R,
+    {
+        let context = Context::create();
+
+        // The ContextRef shares the Rc, so the context is only disposed of once `context` is dropped
+        f(ContextRef::shared(Context::new(Rc::clone(&context.context))))
+    }
+
     /// Creates a `ContextRef` which references the global context singleton.
     ///
     /// # Example
@@ -57,7 +252,7 @@ impl Context {
     ///
     /// let context = Context::get_global();
     /// ```
-    pub fn get_global() -> ContextRef {
+    pub fn get_global() -> ContextRef<'static> {
         let context = unsafe {
             LLVMGetGlobalContext()
         };
@@ -75,7 +270,7 @@ impl Context {
     /// let context = Context::create();
     /// let builder = context.create_builder();
     /// ```
-    pub fn create_builder(&self) -> Builder {
+    pub fn create_builder<'ctx, 'm>(&'ctx self) -> Builder<'ctx, 'm> {
         let builder = unsafe {
             LLVMCreateBuilderInContext(*self.context)
         };
@@ -83,6 +278,21 @@ impl Context {
         Builder::new(builder)
     }
 
+    /// Creates a new `CheckedBuilder` for a `Context`, which returns a `BuilderError` rather
+    /// than building invalid IR.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let builder = context.create_checked_builder();
+    /// ```
+    pub fn create_checked_builder<'ctx, 'm>(&'ctx self) -> CheckedBuilder<'ctx, 'm> {
+        self.create_builder().into_checked()
+    }
+
     /// Creates a new `Module` for a `Context`.
     ///
     /// # Example
@@ -93,7 +303,7 @@ impl Context {
     /// let context = Context::create();
     /// let module = context.create_module("my_module");
     /// ```
-    pub fn create_module(&self, name: &str) -> Module {
+    pub fn create_module<'ctx>(&'ctx self, name: &str) -> Module<'ctx> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let module = unsafe {
@@ -107,7 +317,7 @@ impl Context {
     // is that the method needs to take ownership of the MemoryBuffer... otherwise I see what looks like
     // a double free in valgrind when the MemoryBuffer drops so we are `forget`ting MemoryBuffer here
     // for now until we can confirm this is the correct thing to do
-    pub fn create_module_from_ir(&self, memory_buffer: MemoryBuffer) -> Result<Module, String> {
+    pub fn create_module_from_ir<'ctx>(&'ctx self, memory_buffer: MemoryBuffer) -> Result<Module<'ctx>, String> {
         let mut module = ptr::null_mut();
         let mut err_str = ptr::null_mut();
 
@@ -121,18 +331,12 @@ impl Context {
             return Ok(Module::new(module, Some(&self)));
         }
 
-        let rust_str = unsafe {
-            let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-            LLVMDisposeMessage(err_str);
-
-            rust_str
-        };
+        let rust_str = LLVMString::new(err_str).to_string();
 
         Err(rust_str)
     }
 
-    pub fn void_type(&self) -> VoidType {
+    pub fn void_type<'ctx>(&'ctx self) -> VoidType<'ctx> {
         let void_type = unsafe {
             LLVMVoidTypeInContext(*self.context)
         };
@@ -140,7 +344,7 @@ impl Context {
         VoidType::new(void_type)
     }
 
-    pub fn bool_type(&self) -> IntType {
+    pub fn bool_type<'ctx>(&'ctx self) -> IntType<'ctx> {
         let bool_type = unsafe {
             LLVMInt1TypeInContext(*self.context)
         };
@@ -148,7 +352,7 @@ impl Context {
         IntType::new(bool_type)
     }
 
-    pub fn i8_type(&self) -> IntType {
+    pub fn i8_type<'ctx>(&'ctx self) -> IntType<'ctx> {
         let i8_type = unsafe {
             LLVMInt8TypeInContext(*self.context)
         };
@@ -156,7 +360,7 @@ impl Context {
         IntType::new(i8_type)
     }
 
-    pub fn i16_type(&self) -> IntType {
+    pub fn i16_type<'ctx>(&'ctx self) -> IntType<'ctx> {
         let i16_type = unsafe {
             LLVMInt16TypeInContext(*self.context)
         };
@@ -164,7 +368,7 @@ impl Context {
         IntType::new(i16_type)
     }
 
-    pub fn i32_type(&self) -> IntType {
+    pub fn i32_type<'ctx>(&'ctx self) -> IntType<'ctx> {
         let i32_type = unsafe {
             LLVMInt32TypeInContext(*self.context)
         };
@@ -172,7 +376,7 @@ impl Context {
         IntType::new(i32_type)
     }
 
-    pub fn i64_type(&self) -> IntType {
+    pub fn i64_type<'ctx>(&'ctx self) -> IntType<'ctx> {
         let i64_type = unsafe {
             LLVMInt64TypeInContext(*self.context)
         };
@@ -180,14 +384,14 @@ impl Context {
         IntType::new(i64_type)
     }
 
-    pub fn i128_type(&self) -> IntType {
+    pub fn i128_type<'ctx>(&'ctx self) -> IntType<'ctx> {
         // REVIEW: The docs says there's a LLVMInt128TypeInContext, but
         // it might only be in a newer version
 
         self.custom_width_int_type(128)
     }
 
-    pub fn custom_width_int_type(&self, bits: u32) -> IntType {
+    pub fn custom_width_int_type<'ctx>(&'ctx self, bits: u32) -> IntType<'ctx> {
         let int_type = unsafe {
             LLVMIntTypeInContext(*self.context, bits)
         };
@@ -195,7 +399,7 @@ impl Context {
         IntType::new(int_type)
     }
 
-    pub fn f16_type(&self) -> FloatType {
+    pub fn f16_type<'ctx>(&'ctx self) -> FloatType<'ctx> {
         let f16_type = unsafe {
             LLVMHalfTypeInContext(*self.context)
         };
@@ -203,7 +407,7 @@ impl Context {
         FloatType::new(f16_type)
     }
 
-    pub fn f32_type(&self) -> FloatType {
+    pub fn f32_type<'ctx>(&'ctx self) -> FloatType<'ctx> {
         let f32_type = unsafe {
             LLVMFloatTypeInContext(*self.context)
         };
@@ -211,7 +415,7 @@ impl Context {
         FloatType::new(f32_type)
     }
 
-    pub fn f64_type(&self) -> FloatType {
+    pub fn f64_type<'ctx>(&'ctx self) -> FloatType<'ctx> {
         let f64_type = unsafe {
             LLVMDoubleTypeInContext(*self.context)
         };
@@ -219,7 +423,7 @@ impl Context {
         FloatType::new(f64_type)
     }
 
-    pub fn f128_type(&self) -> FloatType {
+    pub fn f128_type<'ctx>(&'ctx self) -> FloatType<'ctx> {
         let f128_type = unsafe {
             LLVMFP128TypeInContext(*self.context)
         };
@@ -227,7 +431,7 @@ impl Context {
         FloatType::new(f128_type)
     }
 
-    pub fn ppc_f128_type(&self) -> FloatType {
+    pub fn ppc_f128_type<'ctx>(&'ctx self) -> FloatType<'ctx> {
         let f128_type = unsafe {
             LLVMPPCFP128TypeInContext(*self.context)
         };
@@ -236,7 +440,7 @@ impl Context {
     }
 
     // REVIEW: AnyType but VoidType? FunctionType?
-    pub fn struct_type(&self, field_types: &[&BasicType], packed: bool) -> StructType {
+    pub fn struct_type<'ctx>(&'ctx self, field_types: &[&BasicType<'ctx>], packed: bool) -> StructType<'ctx> {
         let mut field_types: Vec<LLVMTypeRef> = field_types.iter()
                                                            .map(|val| val.as_type_ref())
                                                            .collect();
@@ -247,7 +451,7 @@ impl Context {
         StructType::new(struct_type)
     }
 
-    pub fn opaque_struct_type(&self, name: &str) -> StructType {
+    pub fn opaque_struct_type<'ctx>(&'ctx self, name: &str) -> StructType<'ctx> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let struct_type = unsafe {
@@ -257,7 +461,7 @@ impl Context {
         StructType::new(struct_type)
     }
 
-    pub fn append_basic_block(&self, function: &FunctionValue, name: &str) -> BasicBlock {
+    pub fn append_basic_block<'ctx, 'm>(&'ctx self, function: &FunctionValue<'ctx, 'm>, name: &str) -> BasicBlock<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let bb = unsafe {
@@ -270,7 +474,7 @@ impl Context {
     // REVIEW: What happens when using these methods and the BasicBlock doesn't have a parent?
     // Should they be callable at all? Needs testing to see what LLVM will do, I suppose. See below unwrap.
     // Maybe need SubTypes: BasicBlock<HasParent>, BasicBlock<Orphan>?
-    pub fn insert_basic_block_after(&self, basic_block: &BasicBlock, name: &str) -> BasicBlock {
+    pub fn insert_basic_block_after<'ctx, 'm>(&'ctx self, basic_block: &BasicBlock<'ctx, 'm>, name: &str) -> BasicBlock<'ctx, 'm> {
         match basic_block.get_next_basic_block() {
             Some(next_basic_block) => self.prepend_basic_block(&next_basic_block, name),
             None => {
@@ -281,7 +485,7 @@ impl Context {
         }
     }
 
-    pub fn prepend_basic_block(&self, basic_block: &BasicBlock, name: &str) -> BasicBlock {
+    pub fn prepend_basic_block<'ctx, 'm>(&'ctx self, basic_block: &BasicBlock<'ctx, 'm>, name: &str) -> BasicBlock<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let bb = unsafe {
@@ -291,7 +495,7 @@ impl Context {
         BasicBlock::new(bb).expect("Prepending basic block should never fail")
     }
 
-    pub fn const_struct(&self, values: &[&BasicValue], packed: bool) -> StructValue {
+    pub fn const_struct<'ctx, 'm>(&'ctx self, values: &[&BasicValue<'ctx, 'm>], packed: bool) -> StructValue<'ctx, 'm> {
         let mut args: Vec<LLVMValueRef> = values.iter()
                                                 .map(|val| val.as_value_ref())
                                                 .collect();
@@ -304,7 +508,7 @@ impl Context {
 
     // REVIEW: Maybe more helpful to beginners to call this metadata_tuple?
     // REVIEW: Seems to be unassgned to anything
-    pub fn metadata_node(&self, values: &[&BasicValue]) -> MetadataValue {
+    pub fn metadata_node<'ctx, 'm>(&'ctx self, values: &[&BasicValue<'ctx, 'm>]) -> MetadataValue<'ctx, 'm> {
         let mut tuple_values: Vec<LLVMValueRef> = values.iter()
                                                         .map(|val| val.as_value_ref())
                                                         .collect();
@@ -316,7 +520,7 @@ impl Context {
     }
 
     // REVIEW: Seems to be unassgned to anything
-    pub fn metadata_string(&self, string: &str) -> MetadataValue {
+    pub fn metadata_string<'ctx>(&'ctx self, string: &str) -> MetadataValue<'ctx, 'ctx> {
         let c_string = CString::new(string).expect("Conversion to CString failed unexpectedly");
 
         let metadata_value = unsafe {
@@ -332,16 +536,97 @@ impl Context {
         }
     }
 
-    // LLVM 3.9+
-    // pub fn get_diagnostic_handler(&self) -> DiagnosticHandler {
-    //     let handler = unsafe {
-    //         LLVMContextGetDiagnosticHandler(self.context)
-    //     };
+    /// Creates an enum attribute, such as `noinline` or `dereferenceable(8)`. `kind_id` can be
+    /// looked up with `AttributeKind::get_kind_id` or `Attribute::get_named_enum_kind_id`, and `val` is ignored by attributes
+    /// which don't take an integer argument.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn create_enum_attribute(&self, kind_id: u32, val: u64) -> Attribute {
+        use llvm_sys::core::LLVMCreateEnumAttribute;
+
+        let attribute = unsafe {
+            LLVMCreateEnumAttribute(*self.context, kind_id, val)
+        };
+
+        Attribute::new(attribute)
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn create_string_attribute(&self, key: &str, val: &str) -> Attribute {
+        use llvm_sys::core::LLVMCreateStringAttribute;
+
+        let attribute = unsafe {
+            LLVMCreateStringAttribute(*self.context, key.as_ptr() as *const _, key.len() as u32, val.as_ptr() as *const _, val.len() as u32)
+        };
+
+        Attribute::new(attribute)
+    }
+
+    /// Routes the diagnostics LLVM reports for this `Context` to `handler` rather
+    /// than printing them to stderr. Errors reported to a handler no longer abort
+    /// the process. The handler lives until it is replaced or removed, or until the
+    /// `Context` is disposed.
+    ///
+    /// The handler must not replace or remove itself while it is running.
+    ///
+    /// A panic in the handler doesn't unwind into LLVM. It is caught, and resumed once LLVM returns
+    /// to the inkwell method which made it report the diagnostic, such as `Module::link_in_module`,
+    /// `PassManager::run_on_module`, `TargetMachine::write_to_file` or
+    /// `ExecutionEngine::get_function_address`. Diagnostics reported in the meantime are still
+    /// passed to the handler. A panic raised during any other LLVM call is only resumed by the
+    /// next of those methods to be called on this `Context`, and is discarded along with the
+    /// handler when it is replaced or removed, or when the `Context` is disposed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inkwell::context::{Context, DiagnosticSeverity};
+    ///
+    /// let context = Context::create();
+    ///
+    /// context.set_diagnostic_handler(|diagnostic| {
+    ///     if diagnostic.get_severity() == DiagnosticSeverity::Error {
+    ///         eprintln!("LLVM error: {}", diagnostic.get_message());
+    ///     }
+    /// });
+    /// ```
+    pub fn set_diagnostic_handler<F: FnMut(&Diagnostic) + 'static>(&self, handler: F) {
+        let mut handler = Box::new(DiagnosticHandler {
+            handler: Box::new(handler),
+            panic: None,
+        });
+        let handler_ptr = &mut *handler as *mut DiagnosticHandler as *mut c_void;
+
+        unsafe {
+            LLVMContextSetDiagnosticHandler(*self.context, Some(diagnostic_handler_wrapper), handler_ptr);
+        }
+
+        // The previous handler is only dropped once LLVM no longer refers to it
+        let previous_handler = DIAGNOSTIC_HANDLERS.with(|handlers| {
+            handlers.borrow_mut().insert(*self.context as usize, handler)
+        });
+
+        drop(previous_handler);
+    }
+
+    /// Removes the handler installed by `Context::set_diagnostic_handler`, if any,
+    /// so that diagnostics are printed to stderr again.
+    pub fn remove_diagnostic_handler(&self) {
+        unsafe {
+            LLVMContextSetDiagnosticHandler(*self.context, None, ptr::null_mut());
+        }
+
+        self.drop_diagnostic_handler();
+    }
 
-    //     // REVIEW: Can this be null?
+    fn drop_diagnostic_handler(&self) {
+        // The handler is removed from the map before being dropped, in case
+        // dropping it drops another Context
+        let handler = DIAGNOSTIC_HANDLERS.with(|handlers| {
+            handlers.borrow_mut().remove(&(*self.context as usize))
+        });
 
-    //     DiagnosticHandler::new(handler)
-    // }
+        drop(handler);
+    }
 }
 
 impl Drop for Context {
@@ -350,6 +635,8 @@ impl Drop for Context {
             unsafe {
                 LLVMContextDispose(*self.context);
             }
+
+            self.drop_diagnostic_handler();
         }
     }
 }
@@ -361,20 +648,157 @@ impl Drop for Context {
 // have this ContextRef type, however Global Contexts throw a wrench in that
 // a bit as it is a special case. get_context() methods as well since they do
 // not have access to the original Rc.
-#[derive(Debug, PartialEq, Eq)]
-pub struct ContextRef {
+#[derive(Debug)]
+pub struct ContextRef<'ctx> {
     context: Option<Context>,
+    // Whether the Rc is shared with the Context which owns the underlying context, rather than
+    // wrapping a context which is owned elsewhere and must never be disposed of through it
+    shares_rc: bool,
+    _marker: PhantomData<fn(&'ctx ()) -> &'ctx ()>,
 }
 
-impl ContextRef {
+impl<'ctx> ContextRef<'ctx> {
     pub(crate) fn new(context: Context) -> Self {
         ContextRef {
             context: Some(context),
+            shares_rc: false,
+            _marker: PhantomData,
+        }
+    }
+
+    fn shared(context: Context) -> Self {
+        ContextRef {
+            context: Some(context),
+            shares_rc: true,
+            _marker: PhantomData,
         }
     }
 }
 
-impl Deref for ContextRef {
+// The methods which create types, values, modules and builders are repeated on ContextRef
+// so that what they create is tied to 'ctx rather than to a borrow of the ContextRef, which
+// would let values from different contexts be borrowed for the same lifetime
+impl<'ctx> ContextRef<'ctx> {
+    // The underlying LLVM context is alive for all of 'ctx, and none of the Context methods
+    // hold on to the reference beyond the call
+    fn branded_context(&self) -> &'ctx Context {
+        unsafe {
+            &*(&**self as *const Context)
+        }
+    }
+
+    pub fn create_builder<'m>(&self) -> Builder<'ctx, 'm> {
+        self.branded_context().create_builder()
+    }
+
+    pub fn create_checked_builder<'m>(&self) -> CheckedBuilder<'ctx, 'm> {
+        self.branded_context().create_checked_builder()
+    }
+
+    pub fn create_module(&self, name: &str) -> Module<'ctx> {
+        self.branded_context().create_module(name)
+    }
+
+    pub fn create_module_from_ir(&self, memory_buffer: MemoryBuffer) -> Result<Module<'ctx>, String> {
+        self.branded_context().create_module_from_ir(memory_buffer)
+    }
+
+    pub fn void_type(&self) -> VoidType<'ctx> {
+        self.branded_context().void_type()
+    }
+
+    pub fn bool_type(&self) -> IntType<'ctx> {
+        self.branded_context().bool_type()
+    }
+
+    pub fn i8_type(&self) -> IntType<'ctx> {
+        self.branded_context().i8_type()
+    }
+
+    pub fn i16_type(&self) -> IntType<'ctx> {
+        self.branded_context().i16_type()
+    }
+
+    pub fn i32_type(&self) -> IntType<'ctx> {
+        self.branded_context().i32_type()
+    }
+
+    pub fn i64_type(&self) -> IntType<'ctx> {
+        self.branded_context().i64_type()
+    }
+
+    pub fn i128_type(&self) -> IntType<'ctx> {
+        self.branded_context().i128_type()
+    }
+
+    pub fn custom_width_int_type(&self, bits: u32) -> IntType<'ctx> {
+        self.branded_context().custom_width_int_type(bits)
+    }
+
+    pub fn f16_type(&self) -> FloatType<'ctx> {
+        self.branded_context().f16_type()
+    }
+
+    pub fn f32_type(&self) -> FloatType<'ctx> {
+        self.branded_context().f32_type()
+    }
+
+    pub fn f64_type(&self) -> FloatType<'ctx> {
+        self.branded_context().f64_type()
+    }
+
+    pub fn f128_type(&self) -> FloatType<'ctx> {
+        self.branded_context().f128_type()
+    }
+
+    pub fn ppc_f128_type(&self) -> FloatType<'ctx> {
+        self.branded_context().ppc_f128_type()
+    }
+
+    pub fn struct_type(&self, field_types: &[&BasicType<'ctx>], packed: bool) -> StructType<'ctx> {
+        self.branded_context().struct_type(field_types, packed)
+    }
+
+    pub fn opaque_struct_type(&self, name: &str) -> StructType<'ctx> {
+        self.branded_context().opaque_struct_type(name)
+    }
+
+    pub fn append_basic_block<'m>(&self, function: &FunctionValue<'ctx, 'm>, name: &str) -> BasicBlock<'ctx, 'm> {
+        self.branded_context().append_basic_block(function, name)
+    }
+
+    pub fn insert_basic_block_after<'m>(&self, basic_block: &BasicBlock<'ctx, 'm>, name: &str) -> BasicBlock<'ctx, 'm> {
+        self.branded_context().insert_basic_block_after(basic_block, name)
+    }
+
+    pub fn prepend_basic_block<'m>(&self, basic_block: &BasicBlock<'ctx, 'm>, name: &str) -> BasicBlock<'ctx, 'm> {
+        self.branded_context().prepend_basic_block(basic_block, name)
+    }
+
+    pub fn const_struct<'m>(&self, values: &[&BasicValue<'ctx, 'm>], packed: bool) -> StructValue<'ctx, 'm> {
+        self.branded_context().const_struct(values, packed)
+    }
+
+    pub fn metadata_node<'m>(&self, values: &[&BasicValue<'ctx, 'm>]) -> MetadataValue<'ctx, 'm> {
+        self.branded_context().metadata_node(values)
+    }
+
+    pub fn metadata_string(&self, string: &str) -> MetadataValue<'ctx, 'ctx> {
+        self.branded_context().metadata_string(string)
+    }
+}
+
+// ContextRefs with different lifetimes may still refer to the same context, ie one from
+// Context::get_global and one from a type created in the global context
+impl<'ctx, 'other> PartialEq<ContextRef<'other>> for ContextRef<'ctx> {
+    fn eq(&self, other: &ContextRef<'other>) -> bool {
+        **self == **other
+    }
+}
+
+impl<'ctx> Eq for ContextRef<'ctx> {}
+
+impl<'ctx> Deref for ContextRef<'ctx> {
     type Target = Context;
 
     fn deref(&self) -> &Self::Target {
@@ -382,9 +806,13 @@ impl Deref for ContextRef {
     }
 }
 
-impl Drop for ContextRef {
+impl<'ctx> Drop for ContextRef<'ctx> {
     fn drop(&mut self) {
+        let context = self.context.take().expect("Context should always exist until Drop");
+
         // REVIEW: If you forget an Rc type like Context, does that mean it never gets decremented?
-        forget(self.context.take());
+        if !self.shares_rc {
+            forget(context);
+        }
     }
 }
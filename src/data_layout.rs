@@ -1,30 +1,22 @@
-use llvm_sys::core::LLVMDisposeMessage;
+use support::LLVMString;
 
-use std::cell::Cell;
 use std::ffi::CStr;
 use std::fmt;
 
-// REVIEW: We should consider making this more generic. DataLayout could just be
-// a newtype on a LLVMMessage struct
-
 #[derive(Eq)]
 pub struct DataLayout {
-    pub(crate) data_layout: Cell<*mut i8>,
+    pub(crate) data_layout: LLVMString,
 }
 
 impl DataLayout {
-    pub(crate) fn new(data_layout: *mut i8) -> DataLayout {
-        assert!(!data_layout.is_null());
-
+    pub(crate) fn new(data_layout: LLVMString) -> DataLayout {
         DataLayout {
-            data_layout: Cell::new(data_layout),
+            data_layout,
         }
     }
 
     pub fn as_str(&self) -> &CStr {
-        unsafe {
-            CStr::from_ptr(self.data_layout.get())
-        }
+        &self.data_layout
     }
 }
 
@@ -37,15 +29,7 @@ impl PartialEq for DataLayout {
 impl fmt::Debug for DataLayout {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DataLayout {{\n    ")?;
-        write!(f, "address: {:?}\n    ", self.data_layout.get())?;
+        write!(f, "address: {:?}\n    ", self.as_str().as_ptr())?;
         write!(f, "repr: {:?}\n}}", self.as_str())
     }
 }
-
-impl Drop for DataLayout {
-    fn drop(&mut self) {
-        unsafe {
-            LLVMDisposeMessage(self.data_layout.get())
-        }
-    }
-}
@@ -1,29 +1,53 @@
-use llvm_sys::core::LLVMDisposeMessage;
-use llvm_sys::execution_engine::{LLVMGetExecutionEngineTargetData, LLVMExecutionEngineRef, LLVMRunFunction, LLVMRunFunctionAsMain, LLVMDisposeExecutionEngine, LLVMGetFunctionAddress, LLVMAddModule, LLVMFindFunction, LLVMLinkInMCJIT, LLVMLinkInInterpreter, LLVMRemoveModule, LLVMGenericValueRef, LLVMFreeMachineCodeForFunction, LLVMAddGlobalMapping};
-
+use libc::c_char;
+use llvm_sys::core::{LLVMGetNamedFunction, LLVMIsDeclaration, LLVMGetFirstFunction, LLVMGetNextFunction, LLVMGetLinkage, LLVMGetValueName, LLVMGetModuleContext};
+use llvm_sys::execution_engine::{LLVMGetExecutionEngineTargetData, LLVMExecutionEngineRef, LLVMRunFunction, LLVMRunFunctionAsMain, LLVMDisposeExecutionEngine, LLVMGetFunctionAddress, LLVMAddModule, LLVMFindFunction, LLVMLinkInMCJIT, LLVMLinkInInterpreter, LLVMRemoveModule, LLVMGenericValueRef, LLVMFreeMachineCodeForFunction, LLVMAddGlobalMapping, LLVMRunStaticConstructors, LLVMRunStaticDestructors};
+use llvm_sys::prelude::LLVMModuleRef;
+use llvm_sys::LLVMLinkage;
+
+use context::resume_diagnostic_handler_panic;
+use memory_manager::McJitMemoryManager;
 use module::Module;
+use support::LLVMString;
 use targets::TargetData;
+use types::AnyType;
 use values::{AnyValue, AsValueRef, FunctionValue, GenericValue};
 
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
 use std::rc::Rc;
 use std::ffi::{CStr, CString};
-use std::mem::{forget, uninitialized, zeroed};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::mem::{forget, size_of, transmute_copy, uninitialized, zeroed};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::ptr;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum FunctionLookupError {
     JITNotEnabled,
     FunctionNotFound, // 404!
+    SignatureMismatch,
 }
 
 #[derive(PartialEq, Eq, Debug)]
-pub struct ExecutionEngine {
+pub struct ExecutionEngine<'ctx> {
     pub(crate) execution_engine: Rc<LLVMExecutionEngineRef>,
+    // The modules owned by this engine, shared by every handle to it
+    modules: Rc<RefCell<Vec<LLVMModuleRef>>>,
     target_data: Option<TargetData>,
     jit_mode: bool,
+    perf_map_listener: RefCell<Option<PerfMapListener>>,
+    _marker: PhantomData<fn(&'ctx ()) -> &'ctx ()>,
 }
 
-impl ExecutionEngine {
-    pub(crate) fn new(execution_engine: Rc<LLVMExecutionEngineRef>, jit_mode: bool) -> ExecutionEngine {
+impl<'ctx> ExecutionEngine<'ctx> {
+    pub(crate) fn new(execution_engine: LLVMExecutionEngineRef, module: LLVMModuleRef, jit_mode: bool) -> ExecutionEngine<'ctx> {
+        ExecutionEngine::from_shared(Rc::new(execution_engine), Rc::new(RefCell::new(vec![module])), jit_mode)
+    }
+
+    fn from_shared(execution_engine: Rc<LLVMExecutionEngineRef>, modules: Rc<RefCell<Vec<LLVMModuleRef>>>, jit_mode: bool) -> ExecutionEngine<'ctx> {
         assert!(!execution_engine.is_null());
 
         // REVIEW: Will we have to do this for LLVMGetExecutionEngineTargetMachine too?
@@ -33,11 +57,23 @@ impl ExecutionEngine {
 
         ExecutionEngine {
             execution_engine: execution_engine,
+            modules: modules,
             target_data: Some(TargetData::new(target_data)),
             jit_mode: jit_mode,
+            perf_map_listener: RefCell::new(None),
+            _marker: PhantomData,
         }
     }
 
+    // Creates another handle to the same engine, which is only disposed once every handle is dropped
+    pub(crate) fn share(&self) -> ExecutionEngine<'ctx> {
+        let execution_engine = ExecutionEngine::from_shared(self.execution_engine.clone(), self.modules.clone(), self.jit_mode);
+
+        *execution_engine.perf_map_listener.borrow_mut() = self.perf_map_listener.borrow().clone();
+
+        execution_engine
+    }
+
     pub fn link_in_mc_jit() {
         unsafe {
             LLVMLinkInMCJIT()
@@ -78,7 +114,7 @@ impl ExecutionEngine {
     /// let extf = module.add_function("sumf", &ft.fn_type(&[ &ft, &ft ], false), None);
     ///
     /// let argf = ft.const_float(64.);
-    /// let retv = builder.build_call(&extf, &[ &argf, &argf ], "retv", false).left().unwrap().into_float_value();
+    /// let retv = builder.build_call(&extf, &[ &argf, &argf ], "retv").try_as_basic_value().left().unwrap().into_float_value();
     ///
     /// builder.build_return(Some(&retv));
     ///
@@ -89,7 +125,7 @@ impl ExecutionEngine {
     ///
     /// assert_eq!(result, 128.);
     /// ```
-    pub fn add_global_mapping(&self, value: &AnyValue, addr: usize) {
+    pub fn add_global_mapping<'m>(&self, value: &AnyValue<'ctx, 'm>, addr: usize) {
         unsafe {
             LLVMAddGlobalMapping(*self.execution_engine, value.as_value_ref(), addr as *mut _)
         }
@@ -112,21 +148,23 @@ impl ExecutionEngine {
     ///
     /// assert!(ee.add_module(&module).is_err());
     /// ```
-    pub fn add_module(&self, module: &Module) -> Result<(), ()> {
+    pub fn add_module(&self, module: &Module<'ctx>) -> Result<(), ()> {
+        if module.owned_by_ee.borrow().is_some() {
+            return Err(());
+        }
+
         unsafe {
             LLVMAddModule(*self.execution_engine, module.module.get())
         }
 
-        if module.owned_by_ee.borrow().is_some() {
-            return Err(());
-        }
+        self.modules.borrow_mut().push(module.module.get());
 
-        *module.owned_by_ee.borrow_mut() = Some(ExecutionEngine::new(self.execution_engine.clone(), self.jit_mode));
+        *module.owned_by_ee.borrow_mut() = Some(self.share());
 
         Ok(())
     }
 
-    pub fn remove_module(&self, module: &Module) -> Result<(), String> {
+    pub fn remove_module(&mut self, module: &Module<'ctx>) -> Result<(), String> {
         match *module.owned_by_ee.borrow() {
             Some(ref ee) if *ee.execution_engine != *self.execution_engine => return Err("Module is not owned by this Execution Engine".into()),
             None => return Err("Module is not owned by an Execution Engine".into()),
@@ -141,23 +179,26 @@ impl ExecutionEngine {
         };
 
         if code == 1 {
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-                LLVMDisposeMessage(err_str);
-
-                rust_str
-            };
+            let rust_str = LLVMString::new(err_str).to_string();
 
             return Err(rust_str);
         }
 
+        self.modules.borrow_mut().retain(|&owned| owned != module.module.get());
+
         module.module.set(new_module);
         *module.owned_by_ee.borrow_mut() = None;
 
         Ok(())
     }
 
+    // Diagnostics are reported to the context of the modules being compiled or run
+    fn resume_diagnostic_handler_panic(&self) {
+        if let Some(&module) = self.modules.borrow().first() {
+            resume_diagnostic_handler_panic(unsafe { LLVMGetModuleContext(module) });
+        }
+    }
+
     /// WARNING: The returned address *will* be invalid if the EE drops first
     /// Do not attempt to transmute it to a function if the ExecutionEngine is gone
     // TODOC: Initializing a target MUST occur before creating the EE or else it will not count
@@ -173,6 +214,8 @@ impl ExecutionEngine {
             LLVMGetFunctionAddress(*self.execution_engine, c_string.as_ptr())
         };
 
+        self.resume_diagnostic_handler_panic();
+
         // REVIEW: Can also return 0 if no targets are initialized.
         // One option might be to set a global to true if any at all of the targets have been
         // initialized (maybe we could figure out which config in particular is the trigger)
@@ -181,9 +224,147 @@ impl ExecutionEngine {
             return Err(FunctionLookupError::FunctionNotFound);
         }
 
+        if let Some(ref perf_map_listener) = *self.perf_map_listener.borrow() {
+            self.record_perf_map_symbols(perf_map_listener, &c_string);
+        }
+
         Ok(address)
     }
 
+    pub(crate) fn set_perf_map_listener(&self, perf_map_listener: PerfMapListener) {
+        *self.perf_map_listener.borrow_mut() = Some(perf_map_listener);
+    }
+
+    // MCJIT compiles a whole module at a time, so once one of its functions has been looked up,
+    // every function the module defines can be looked up without compiling anything else
+    fn record_perf_map_symbols(&self, perf_map_listener: &PerfMapListener, fn_name: &CStr) {
+        let module = self.modules.borrow().iter().cloned().find(|&module| unsafe {
+            let function = LLVMGetNamedFunction(module, fn_name.as_ptr());
+
+            !function.is_null() && LLVMIsDeclaration(function) == 0
+        });
+
+        let module = match module {
+            Some(module) if !perf_map_listener.has_recorded_module(module) => module,
+            _ => return,
+        };
+
+        let mut symbols = Vec::new();
+
+        unsafe {
+            let mut function = LLVMGetFirstFunction(module);
+
+            while !function.is_null() {
+                // Private functions aren't given a symbol, so they can't be looked up
+                if LLVMIsDeclaration(function) == 0 && !matches!(LLVMGetLinkage(function), LLVMLinkage::LLVMPrivateLinkage) {
+                    let name = LLVMGetValueName(function);
+                    let address = LLVMGetFunctionAddress(*self.execution_engine, name);
+
+                    if address != 0 {
+                        symbols.push((address as usize, CStr::from_ptr(name).to_string_lossy().into_owned()));
+                    }
+                }
+
+                function = LLVMGetNextFunction(function);
+            }
+        }
+
+        perf_map_listener.record_module(module, symbols);
+    }
+
+    /// Looks up a JIT compiled function by name and returns a typed handle to it,
+    /// which keeps the `ExecutionEngine` (and so its machine code) alive for as
+    /// long as the handle exists.
+    ///
+    /// The number of parameters and the size of each parameter and the return value
+    /// of `F` are checked against the `FunctionType` of the function. Any mismatch
+    /// results in a `FunctionLookupError::SignatureMismatch`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use inkwell::OptimizationLevel;
+    /// use inkwell::context::Context;
+    /// use inkwell::targets::{InitializationConfig, Target};
+    ///
+    /// Target::initialize_native(&InitializationConfig::default()).unwrap();
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("test");
+    /// let builder = context.create_builder();
+    /// let f64_type = context.f64_type();
+    /// let fn_type = f64_type.fn_type(&[&f64_type], false);
+    ///
+    /// let function = module.add_function("double", &fn_type, None);
+    /// let basic_block = context.append_basic_block(&function, "entry");
+    ///
+    /// builder.position_at_end(&basic_block);
+    ///
+    /// let x = function.get_first_param().unwrap().into_float_value();
+    /// let doubled = builder.build_float_add(&x, &x, "doubled");
+    ///
+    /// builder.build_return(Some(&doubled));
+    ///
+    /// let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+    ///
+    /// let double = unsafe {
+    ///     execution_engine.get_function::<unsafe extern "C" fn(f64) -> f64>("double").unwrap()
+    /// };
+    ///
+    /// // The function remains callable even though the engine has been dropped
+    /// drop(execution_engine);
+    ///
+    /// assert_eq!(unsafe { double.call(21.) }, 42.);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The check cannot tell apart types of the same size, ie `i64` and `f64` or
+    /// different pointer types, so it is up to the caller to pick a matching `F`.
+    pub unsafe fn get_function<F: UnsafeFunctionPointer>(&self, fn_name: &str) -> Result<JitFunction<'ctx, F>, FunctionLookupError> {
+        let function = self.get_function_value(fn_name)?;
+
+        if !self.signature_matches::<F>(&function) {
+            return Err(FunctionLookupError::SignatureMismatch);
+        }
+
+        let address = self.get_function_address(fn_name)? as usize;
+
+        assert_eq!(size_of::<F>(), size_of::<usize>(), "The type `F` must have the same size as a function pointer");
+
+        Ok(JitFunction {
+            _execution_engine: self.share(),
+            inner: transmute_copy(&address),
+        })
+    }
+
+    fn signature_matches<'m, F: UnsafeFunctionPointer>(&self, function: &FunctionValue<'ctx, 'm>) -> bool {
+        let fn_type = function.get_type();
+        let target_data = self.get_target_data();
+        let (param_sizes, return_size) = F::signature();
+
+        if fn_type.is_var_arg() {
+            return false;
+        }
+
+        let param_types = fn_type.get_param_types();
+
+        if param_types.len() != param_sizes.len() {
+            return false;
+        }
+
+        let params_match = param_types.iter()
+                                      .zip(param_sizes)
+                                      .all(|(param_type, size)| target_data.get_store_size(param_type as &AnyType) == size as u64);
+
+        let expected_return_size = match fn_type.get_return_type() {
+            Some(return_type) => target_data.get_store_size(&return_type as &AnyType),
+            None => 0,
+        };
+
+        params_match && expected_return_size == return_size as u64
+    }
+
     // REVIEW: Not sure if an EE's target data can change.. if so we might want to update the value
     // when making this call
     pub fn get_target_data(&self) -> &TargetData {
@@ -193,7 +374,7 @@ impl ExecutionEngine {
     // REVIEW: Can also find nothing if no targeting is initialized. Maybe best to
     // do have a global flag for anything initialized. Catch is that it must be initialized
     // before EE is created
-    pub fn get_function_value(&self, fn_name: &str) -> Result<FunctionValue, FunctionLookupError> {
+    pub fn get_function_value<'m>(&'m self, fn_name: &str) -> Result<FunctionValue<'ctx, 'm>, FunctionLookupError> {
         if !self.jit_mode {
             return Err(FunctionLookupError::JITNotEnabled);
         }
@@ -214,35 +395,80 @@ impl ExecutionEngine {
 
     // TODOC: Marked as unsafe because input function could very well do something unsafe. It's up to the caller
     // to ensure that doesn't happen by defining their function correctly.
-    pub unsafe fn run_function(&self, function: &FunctionValue, args: &[&GenericValue]) -> GenericValue {
+    pub unsafe fn run_function<'m>(&self, function: &FunctionValue<'ctx, 'm>, args: &[&GenericValue<'ctx>]) -> GenericValue<'ctx> {
         let mut args: Vec<LLVMGenericValueRef> = args.iter()
                                                      .map(|val| val.generic_value)
                                                      .collect();
 
         let value = LLVMRunFunction(*self.execution_engine, function.as_value_ref(), args.len() as u32, args.as_mut_ptr()); // REVIEW: usize to u32 ok??
+        let value = GenericValue::new(value);
+
+        self.resume_diagnostic_handler_panic();
 
-        GenericValue::new(value)
+        value
     }
 
-    pub fn run_function_as_main(&self, function: &FunctionValue) {
-        let args = vec![]; // TODO: Support argc, argv
-        let env_p = vec![]; // REVIEW: No clue what this is
+    /// Runs a function like a C `main`, with `args` passed in as `argv` and `env`,
+    /// a list of `NAME=value` strings, passed in as `envp`. Returns the function's
+    /// return value as an exit code.
+    ///
+    /// # Safety
+    ///
+    /// The function may do anything, so it is up to the caller to ensure it is
+    /// safe to run, and that it takes the `argc`, `argv` and `envp` of a C `main`.
+    pub unsafe fn run_function_as_main<'m>(&self, function: &FunctionValue<'ctx, 'm>, args: &[&str], env: &[&str]) -> i32 {
+        let args: Vec<CString> = args.iter()
+                                     .map(|arg| CString::new(*arg).expect("Conversion to CString failed unexpectedly"))
+                                     .collect();
+        let env: Vec<CString> = env.iter()
+                                   .map(|var| CString::new(*var).expect("Conversion to CString failed unexpectedly"))
+                                   .collect();
 
-        unsafe {
-            LLVMRunFunctionAsMain(*self.execution_engine, function.as_value_ref(), args.len() as u32, args.as_ptr(), env_p.as_ptr()); // REVIEW: usize to u32 cast ok??
-        }
+        let raw_args: Vec<*const c_char> = args.iter().map(|arg| arg.as_ptr()).collect();
+
+        // Unlike argv, envp has no count and must be null terminated
+        let raw_env: Vec<*const c_char> = env.iter()
+                                             .map(|var| var.as_ptr())
+                                             .chain(Some(ptr::null()))
+                                             .collect();
+
+        let exit_code = LLVMRunFunctionAsMain(*self.execution_engine, function.as_value_ref(), raw_args.len() as u32, raw_args.as_ptr(), raw_env.as_ptr()); // REVIEW: usize to u32 cast ok??
+
+        self.resume_diagnostic_handler_panic();
+
+        exit_code
+    }
+
+    /// Runs the static constructors of all modules in this `ExecutionEngine`,
+    /// which are the functions listed in their `llvm.global_ctors`.
+    ///
+    /// # Safety
+    ///
+    /// The constructors may do anything, so it is up to the caller to ensure they're safe to run.
+    pub unsafe fn run_static_constructors(&self) {
+        LLVMRunStaticConstructors(*self.execution_engine)
+    }
+
+    /// Runs the static destructors of all modules in this `ExecutionEngine`,
+    /// which are the functions listed in their `llvm.global_dtors`.
+    ///
+    /// # Safety
+    ///
+    /// The destructors may do anything, so it is up to the caller to ensure they're safe to run.
+    pub unsafe fn run_static_destructors(&self) {
+        LLVMRunStaticDestructors(*self.execution_engine)
     }
 
-    pub fn free_fn_machine_code(&self, function: &FunctionValue) {
+    pub fn free_fn_machine_code<'m>(&self, function: &FunctionValue<'ctx, 'm>) {
         unsafe {
             LLVMFreeMachineCodeForFunction(*self.execution_engine, function.as_value_ref())
         }
     }
 }
 
-// Modules owned by the EE will be discarded by the EE so we don't
-// want owned modules to drop.
-impl Drop for ExecutionEngine {
+// Disposing of the EE also disposes of the modules it owns. It can only happen once every
+// handle to it is gone, including those held by its modules, so no values can refer to them.
+impl<'ctx> Drop for ExecutionEngine<'ctx> {
     fn drop(&mut self) {
         forget(self.target_data.take().expect("TargetData should always exist until Drop"));
 
@@ -253,3 +479,308 @@ impl Drop for ExecutionEngine {
         }
     }
 }
+
+/// Writes an entry for each JIT compiled function to `/tmp/perf-<pid>.map`,
+/// which `perf report` uses to symbolize frames of JIT compiled code.
+///
+/// A listener is attached to an `ExecutionEngine` by creating it with
+/// `Module::create_mcjit_execution_engine_with_perf_map`. MCJIT compiles a whole module
+/// at a time, the first time one of its functions is looked up, at which point an entry
+/// is written for every function the module defines. Nothing is compiled just for the perf map.
+///
+/// The C API doesn't expose the size of each function, so a function is assumed to extend
+/// up to the next function in the same code section, or otherwise up to the end of the
+/// section. Functions with private linkage aren't given a symbol by MCJIT, so they get no
+/// entry and are attributed to the function placed before them.
+///
+/// # GDB
+///
+/// Every MCJIT `ExecutionEngine` registers the objects it loads with GDB's JIT interface,
+/// whether or not it has a `PerfMapListener`, and an interpreter never does. GDB reads
+/// registered objects through the `__jit_debug_register_code` breakpoint, so symbols for JIT
+/// compiled frames show up in its backtraces without any setup. LLVM's C API has no way to
+/// turn the registration off or to register objects separately, so it can't be opted out of.
+///
+/// # Example
+///
+/// ```no_run
+/// use inkwell::OptimizationLevel;
+/// use inkwell::context::Context;
+/// use inkwell::execution_engine::PerfMapListener;
+/// use inkwell::memory_manager::PageMemoryManager;
+/// use inkwell::targets::{CodeModel, InitializationConfig, Target};
+///
+/// Target::initialize_native(&InitializationConfig::default()).unwrap();
+///
+/// let context = Context::create();
+/// let module = context.create_module("perf");
+/// let builder = context.create_builder();
+/// let void_type = context.void_type();
+/// let function = module.add_function("nothing", &void_type.fn_type(&[], false), None);
+/// let basic_block = context.append_basic_block(&function, "entry");
+///
+/// builder.position_at_end(&basic_block);
+/// builder.build_return(None);
+///
+/// let perf_map = PerfMapListener::create().unwrap();
+/// let execution_engine = module.create_mcjit_execution_engine_with_perf_map(&perf_map, PageMemoryManager::new(), OptimizationLevel::None, CodeModel::JITDefault, true, false).unwrap();
+///
+/// // Compiles the module, and writes an entry for each of its functions
+/// let nothing = unsafe { execution_engine.get_function::<unsafe extern "C" fn()>("nothing").unwrap() };
+/// ```
+#[derive(Debug, Clone)]
+pub struct PerfMapListener {
+    path: PathBuf,
+    state: Rc<RefCell<PerfMapState>>,
+}
+
+#[derive(Debug)]
+struct PerfMapState {
+    file: File,
+    // Code sections as (start, end, is_finalized)
+    code_sections: Vec<(usize, usize, bool)>,
+    // The modules whose functions have been recorded
+    modules: HashSet<usize>,
+    // The functions of those modules, by address
+    symbols: BTreeMap<usize, String>,
+    written_addresses: HashSet<usize>,
+    error: Option<io::Error>,
+}
+
+impl PerfMapListener {
+    /// Opens this process' perf map for appending, creating it if need be.
+    pub fn create() -> io::Result<Self> {
+        let path = PathBuf::from(format!("/tmp/perf-{}.map", process::id()));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let state = PerfMapState {
+            file,
+            code_sections: Vec::new(),
+            modules: HashSet::new(),
+            symbols: BTreeMap::new(),
+            written_addresses: HashSet::new(),
+            error: None,
+        };
+
+        Ok(PerfMapListener {
+            path,
+            state: Rc::new(RefCell::new(state)),
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    // Wraps a memory manager so that the code sections it allocates are known to this listener
+    pub(crate) fn wrap_memory_manager<MM: McJitMemoryManager>(&self, memory_manager: MM) -> PerfMapMemoryManager<MM> {
+        PerfMapMemoryManager {
+            inner: memory_manager,
+            listener: self.clone(),
+            code_sections: Vec::new(),
+        }
+    }
+
+    /// Takes the error from the last failed write to the perf map, if any. Writes happen
+    /// during lookups, which have no way to report them.
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.state.borrow_mut().error.take()
+    }
+
+    fn has_recorded_module(&self, module: LLVMModuleRef) -> bool {
+        self.state.borrow().modules.contains(&(module as usize))
+    }
+
+    fn record_module(&self, module: LLVMModuleRef, symbols: Vec<(usize, String)>) {
+        let mut state = self.state.borrow_mut();
+
+        state.modules.insert(module as usize);
+        state.symbols.extend(symbols);
+        state.write_entries();
+    }
+}
+
+impl PartialEq for PerfMapListener {
+    fn eq(&self, other: &PerfMapListener) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Eq for PerfMapListener {}
+
+impl PerfMapState {
+    // Writes an entry for every recorded function whose code section has been finalized
+    fn write_entries(&mut self) {
+        let mut entries = Vec::new();
+
+        for (&address, name) in &self.symbols {
+            if self.written_addresses.contains(&address) {
+                continue;
+            }
+
+            let section_end = match self.code_sections.iter().find(|&&(start, end, _)| start <= address && address < end) {
+                Some(&(_, end, true)) => end,
+                _ => continue,
+            };
+
+            let end = match self.symbols.range(address + 1..).next() {
+                Some((&next_address, _)) if next_address < section_end => next_address,
+                _ => section_end,
+            };
+
+            entries.push((address, end - address, name.clone()));
+        }
+
+        for (address, size, name) in entries {
+            let result = writeln!(self.file, "{:x} {:x} {}", address, size, name).and_then(|_| self.file.flush());
+
+            match result {
+                Ok(()) => {
+                    self.written_addresses.insert(address);
+                },
+                Err(err) => self.error = Some(err),
+            }
+        }
+    }
+}
+
+// A McJitMemoryManager which tells a PerfMapListener where code sections are allocated
+// and when they are finalized, and otherwise defers to the memory manager it wraps
+#[derive(Debug)]
+pub(crate) struct PerfMapMemoryManager<MM> {
+    inner: MM,
+    listener: PerfMapListener,
+    // The (start, end) of each code section allocated through this memory manager
+    code_sections: Vec<(usize, usize)>,
+}
+
+impl<MM: McJitMemoryManager> McJitMemoryManager for PerfMapMemoryManager<MM> {
+    fn allocate_code_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str) -> *mut u8 {
+        let ptr = self.inner.allocate_code_section(size, alignment, section_id, section_name);
+
+        if !ptr.is_null() && size > 0 {
+            let section = (ptr as usize, ptr as usize + size);
+
+            self.code_sections.push(section);
+            self.listener.state.borrow_mut().code_sections.push((section.0, section.1, false));
+        }
+
+        ptr
+    }
+
+    fn allocate_data_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str, is_read_only: bool) -> *mut u8 {
+        self.inner.allocate_data_section(size, alignment, section_id, section_name, is_read_only)
+    }
+
+    // Entries are written once the functions in these sections have been looked up
+    fn finalize_memory(&mut self) -> Result<(), String> {
+        self.inner.finalize_memory()?;
+
+        let mut state = self.listener.state.borrow_mut();
+
+        for section in state.code_sections.iter_mut() {
+            if self.code_sections.contains(&(section.0, section.1)) {
+                section.2 = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    // The memory may be reused once it is released, so the sections and the functions
+    // in them are forgotten
+    fn destroy(&mut self) {
+        self.inner.destroy();
+
+        let mut state = self.listener.state.borrow_mut();
+        let code_sections = &self.code_sections;
+        let in_sections = |address: usize| code_sections.iter().any(|&(start, end)| start <= address && address < end);
+
+        state.code_sections.retain(|&(start, end, _)| !code_sections.contains(&(start, end)));
+
+        let removed: Vec<usize> = state.symbols.keys().cloned().filter(|&address| in_sections(address)).collect();
+
+        for address in removed {
+            state.symbols.remove(&address);
+            state.written_addresses.remove(&address);
+        }
+
+        self.code_sections.clear();
+    }
+}
+
+/// A handle to a JIT compiled function, which keeps the `ExecutionEngine`
+/// it was compiled by alive.
+#[derive(Debug, PartialEq, Eq)]
+pub struct JitFunction<'ctx, F> {
+    _execution_engine: ExecutionEngine<'ctx>,
+    inner: F,
+}
+
+impl<'ctx, F: Copy> JitFunction<'ctx, F> {
+    /// Gets the raw function pointer.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must not be called after the `ExecutionEngine` it came from
+    /// has been dropped, which may happen as soon as this `JitFunction` is.
+    pub unsafe fn into_raw(self) -> F {
+        self.inner
+    }
+
+    /// Gets the raw function pointer, which must not outlive this `JitFunction`.
+    pub fn as_raw(&self) -> F {
+        self.inner
+    }
+}
+
+/// Marker trait for the `unsafe extern "C" fn` pointer types which may be
+/// retrieved from an `ExecutionEngine` with `get_function`.
+pub trait UnsafeFunctionPointer: private::SealedUnsafeFunctionPointer {}
+
+mod private {
+    /// Prevents `UnsafeFunctionPointer` from being implemented outside of this crate.
+    pub trait SealedUnsafeFunctionPointer: Copy {
+        /// Gets the sizes of the parameters and of the return value.
+        fn signature() -> (Vec<usize>, usize);
+    }
+}
+
+impl<F: private::SealedUnsafeFunctionPointer> UnsafeFunctionPointer for F {}
+
+macro_rules! impl_unsafe_fn {
+    ($($param:ident),*) => {
+        impl<Output, $($param),*> private::SealedUnsafeFunctionPointer for unsafe extern "C" fn($($param),*) -> Output {
+            fn signature() -> (Vec<usize>, usize) {
+                (vec![$(size_of::<$param>()),*], size_of::<Output>())
+            }
+        }
+
+        impl<'ctx, Output, $($param),*> JitFunction<'ctx, unsafe extern "C" fn($($param),*) -> Output> {
+            /// Calls the JIT compiled function.
+            ///
+            /// # Safety
+            ///
+            /// The function may do anything, so it is up to the caller to ensure
+            /// it is safe to call with these arguments.
+            #[allow(non_snake_case)]
+            #[allow(clippy::too_many_arguments)]
+            pub unsafe fn call(&self, $($param: $param),*) -> Output {
+                (self.inner)($($param),*)
+            }
+        }
+    };
+}
+
+impl_unsafe_fn!();
+impl_unsafe_fn!(A);
+impl_unsafe_fn!(A, B);
+impl_unsafe_fn!(A, B, C);
+impl_unsafe_fn!(A, B, C, D);
+impl_unsafe_fn!(A, B, C, D, E);
+impl_unsafe_fn!(A, B, C, D, E, F);
+impl_unsafe_fn!(A, B, C, D, E, F, G);
+impl_unsafe_fn!(A, B, C, D, E, F, G, H);
+impl_unsafe_fn!(A, B, C, D, E, F, G, H, I);
+impl_unsafe_fn!(A, B, C, D, E, F, G, H, I, J);
+impl_unsafe_fn!(A, B, C, D, E, F, G, H, I, J, K);
+impl_unsafe_fn!(A, B, C, D, E, F, G, H, I, J, K, L);
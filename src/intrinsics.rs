@@ -0,0 +1,258 @@
+use llvm_sys::core::{LLVMAddFunction, LLVMFunctionType, LLVMGetArrayLength, LLVMGetElementType, LLVMGetIntTypeWidth, LLVMGetModuleContext, LLVMGetNamedFunction, LLVMGetPointerAddressSpace, LLVMGetTypeKind, LLVMGetVectorSize, LLVMInt1TypeInContext, LLVMInt32TypeInContext, LLVMInt64TypeInContext, LLVMInt8TypeInContext, LLVMMDNodeInContext, LLVMPointerType, LLVMTypeOf, LLVMStructTypeInContext, LLVMVoidTypeInContext};
+use llvm_sys::prelude::{LLVMModuleRef, LLVMTypeRef};
+use llvm_sys::LLVMTypeKind;
+
+use std::ffi::CString;
+use std::ptr;
+
+use types::{AsTypeRef, BasicTypeEnum};
+use values::FunctionValue;
+
+/// A catalogue of the LLVM intrinsic functions inkwell knows how to declare.
+///
+/// Some intrinsics are overloaded on one or more of their argument or return types, which
+/// must be passed in order to `Module::get_intrinsic_function` (see `overloaded_type_count`).
+/// For example, `Intrinsic::Sqrt` with an `f64` type declares `llvm.sqrt.f64`.
+///
+/// Signatures follow the LLVM 3.7 - 4.0 definitions, e.g. `llvm.memcpy` takes an explicit
+/// alignment argument.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Intrinsic {
+    // Memory: overloaded on the pointer and length types
+    Memcpy,
+    Memmove,
+    Memset,
+    LifetimeStart,
+    LifetimeEnd,
+    StackSave,
+    StackRestore,
+    // Variadic arguments
+    VaStart,
+    VaEnd,
+    VaCopy,
+    // Floating point math: overloaded on the float (or float vector) type
+    Sqrt,
+    Powi,
+    Sin,
+    Cos,
+    Pow,
+    Exp,
+    Exp2,
+    Log,
+    Log2,
+    Log10,
+    Fma,
+    FMulAdd,
+    Fabs,
+    MinNum,
+    MaxNum,
+    CopySign,
+    Floor,
+    Ceil,
+    Trunc,
+    Rint,
+    NearbyInt,
+    Round,
+    // Bit manipulation: overloaded on the int (or int vector) type
+    Bswap,
+    Ctpop,
+    Ctlz,
+    Cttz,
+    // Overflow checked arithmetic: overloaded on the int type, returns { iN, i1 }
+    SAddWithOverflow,
+    UAddWithOverflow,
+    SSubWithOverflow,
+    USubWithOverflow,
+    SMulWithOverflow,
+    UMulWithOverflow,
+    // Misc
+    Expect,
+    Trap,
+    DebugTrap,
+    // Debug info: take metadata operands, see Builder::build_dbg_declare and build_dbg_value
+    DbgDeclare,
+    DbgValue,
+}
+
+impl Intrinsic {
+    fn get_base_name(&self) -> &'static str {
+        match *self {
+            Intrinsic::Memcpy => "llvm.memcpy",
+            Intrinsic::Memmove => "llvm.memmove",
+            Intrinsic::Memset => "llvm.memset",
+            Intrinsic::LifetimeStart => "llvm.lifetime.start",
+            Intrinsic::LifetimeEnd => "llvm.lifetime.end",
+            Intrinsic::StackSave => "llvm.stacksave",
+            Intrinsic::StackRestore => "llvm.stackrestore",
+            Intrinsic::VaStart => "llvm.va_start",
+            Intrinsic::VaEnd => "llvm.va_end",
+            Intrinsic::VaCopy => "llvm.va_copy",
+            Intrinsic::Sqrt => "llvm.sqrt",
+            Intrinsic::Powi => "llvm.powi",
+            Intrinsic::Sin => "llvm.sin",
+            Intrinsic::Cos => "llvm.cos",
+            Intrinsic::Pow => "llvm.pow",
+            Intrinsic::Exp => "llvm.exp",
+            Intrinsic::Exp2 => "llvm.exp2",
+            Intrinsic::Log => "llvm.log",
+            Intrinsic::Log2 => "llvm.log2",
+            Intrinsic::Log10 => "llvm.log10",
+            Intrinsic::Fma => "llvm.fma",
+            Intrinsic::FMulAdd => "llvm.fmuladd",
+            Intrinsic::Fabs => "llvm.fabs",
+            Intrinsic::MinNum => "llvm.minnum",
+            Intrinsic::MaxNum => "llvm.maxnum",
+            Intrinsic::CopySign => "llvm.copysign",
+            Intrinsic::Floor => "llvm.floor",
+            Intrinsic::Ceil => "llvm.ceil",
+            Intrinsic::Trunc => "llvm.trunc",
+            Intrinsic::Rint => "llvm.rint",
+            Intrinsic::NearbyInt => "llvm.nearbyint",
+            Intrinsic::Round => "llvm.round",
+            Intrinsic::Bswap => "llvm.bswap",
+            Intrinsic::Ctpop => "llvm.ctpop",
+            Intrinsic::Ctlz => "llvm.ctlz",
+            Intrinsic::Cttz => "llvm.cttz",
+            Intrinsic::SAddWithOverflow => "llvm.sadd.with.overflow",
+            Intrinsic::UAddWithOverflow => "llvm.uadd.with.overflow",
+            Intrinsic::SSubWithOverflow => "llvm.ssub.with.overflow",
+            Intrinsic::USubWithOverflow => "llvm.usub.with.overflow",
+            Intrinsic::SMulWithOverflow => "llvm.smul.with.overflow",
+            Intrinsic::UMulWithOverflow => "llvm.umul.with.overflow",
+            Intrinsic::Expect => "llvm.expect",
+            Intrinsic::Trap => "llvm.trap",
+            Intrinsic::DebugTrap => "llvm.debugtrap",
+            Intrinsic::DbgDeclare => "llvm.dbg.declare",
+            Intrinsic::DbgValue => "llvm.dbg.value",
+        }
+    }
+
+    /// The number of types this intrinsic is overloaded on, which is the number of types
+    /// that must be provided to declare it:
+    ///
+    /// * `Memcpy` and `Memmove`: the destination pointer, source pointer and length types
+    /// * `Memset`: the destination pointer and length types
+    /// * Math, bit manipulation, overflow and `Expect` intrinsics: the operand type
+    /// * Everything else: none
+    pub fn overloaded_type_count(&self) -> usize {
+        match *self {
+            Intrinsic::Memcpy | Intrinsic::Memmove => 3,
+            Intrinsic::Memset => 2,
+            Intrinsic::LifetimeStart | Intrinsic::LifetimeEnd |
+            Intrinsic::StackSave | Intrinsic::StackRestore |
+            Intrinsic::VaStart | Intrinsic::VaEnd | Intrinsic::VaCopy |
+            Intrinsic::Trap | Intrinsic::DebugTrap |
+            Intrinsic::DbgDeclare | Intrinsic::DbgValue => 0,
+            _ => 1,
+        }
+    }
+
+    /// Gets the mangled name of this intrinsic for the given overloaded types,
+    /// ie `llvm.memcpy.p0i8.p0i8.i64` or `llvm.sqrt.f64`.
+    pub fn get_name(&self, overloaded_types: &[BasicTypeEnum]) -> String {
+        assert_eq!(overloaded_types.len(), self.overloaded_type_count(), "Incorrect number of overloaded types for {:?}", self);
+
+        let mut name = self.get_base_name().to_string();
+
+        for type_ in overloaded_types {
+            name.push('.');
+            name.push_str(&mangle_type(type_.as_type_ref()));
+        }
+
+        name
+    }
+
+    // Finds an existing declaration for this intrinsic in the module or declares it.
+    // LLVM attaches the intrinsic's attributes (nounwind, readnone, ...) itself when
+    // a function with a known intrinsic name is created.
+    pub(crate) fn get_declaration<'ctx, 'm>(&self, module: LLVMModuleRef, overloaded_types: &[BasicTypeEnum<'ctx>]) -> FunctionValue<'ctx, 'm> {
+        let c_string = CString::new(self.get_name(overloaded_types)).expect("Conversion to CString failed unexpectedly");
+
+        let existing = unsafe {
+            LLVMGetNamedFunction(module, c_string.as_ptr())
+        };
+
+        if let Some(function) = FunctionValue::new(existing) {
+            return function;
+        }
+
+        let fn_type = self.get_fn_type(module, overloaded_types);
+        let value = unsafe {
+            LLVMAddFunction(module, c_string.as_ptr(), fn_type)
+        };
+
+        FunctionValue::new(value).expect("Intrinsic declaration should always succeed")
+    }
+
+    fn get_fn_type(&self, module: LLVMModuleRef, overloaded_types: &[BasicTypeEnum]) -> LLVMTypeRef {
+        let overloaded: Vec<LLVMTypeRef> = overloaded_types.iter()
+                                                           .map(|type_| type_.as_type_ref())
+                                                           .collect();
+
+        unsafe {
+            let context = LLVMGetModuleContext(module);
+            let void_type = LLVMVoidTypeInContext(context);
+            let i1_type = LLVMInt1TypeInContext(context);
+            let i8_type = LLVMInt8TypeInContext(context);
+            let i32_type = LLVMInt32TypeInContext(context);
+            let i64_type = LLVMInt64TypeInContext(context);
+            let i8_ptr_type = LLVMPointerType(i8_type, 0);
+            // There's no way to get the metadata type directly, but metadata values have it
+            let metadata_type = LLVMTypeOf(LLVMMDNodeInContext(context, ptr::null_mut(), 0));
+
+            let (return_type, mut param_types) = match *self {
+                Intrinsic::Memcpy | Intrinsic::Memmove => (void_type, vec![overloaded[0], overloaded[1], overloaded[2], i32_type, i1_type]),
+                Intrinsic::Memset => (void_type, vec![overloaded[0], i8_type, overloaded[1], i32_type, i1_type]),
+                Intrinsic::LifetimeStart | Intrinsic::LifetimeEnd => (void_type, vec![i64_type, i8_ptr_type]),
+                Intrinsic::StackSave => (i8_ptr_type, vec![]),
+                Intrinsic::StackRestore | Intrinsic::VaStart | Intrinsic::VaEnd => (void_type, vec![i8_ptr_type]),
+                Intrinsic::VaCopy => (void_type, vec![i8_ptr_type, i8_ptr_type]),
+                Intrinsic::Sqrt | Intrinsic::Sin | Intrinsic::Cos | Intrinsic::Exp | Intrinsic::Exp2 |
+                Intrinsic::Log | Intrinsic::Log2 | Intrinsic::Log10 | Intrinsic::Fabs | Intrinsic::Floor |
+                Intrinsic::Ceil | Intrinsic::Trunc | Intrinsic::Rint | Intrinsic::NearbyInt | Intrinsic::Round |
+                Intrinsic::Bswap | Intrinsic::Ctpop => (overloaded[0], vec![overloaded[0]]),
+                Intrinsic::Powi => (overloaded[0], vec![overloaded[0], i32_type]),
+                Intrinsic::Pow | Intrinsic::MinNum | Intrinsic::MaxNum | Intrinsic::CopySign |
+                Intrinsic::Expect => (overloaded[0], vec![overloaded[0], overloaded[0]]),
+                Intrinsic::Fma | Intrinsic::FMulAdd => (overloaded[0], vec![overloaded[0], overloaded[0], overloaded[0]]),
+                // The i1 flag is is_zero_undef
+                Intrinsic::Ctlz | Intrinsic::Cttz => (overloaded[0], vec![overloaded[0], i1_type]),
+                Intrinsic::SAddWithOverflow | Intrinsic::UAddWithOverflow |
+                Intrinsic::SSubWithOverflow | Intrinsic::USubWithOverflow |
+                Intrinsic::SMulWithOverflow | Intrinsic::UMulWithOverflow => {
+                    let mut field_types = vec![overloaded[0], i1_type];
+                    let return_type = LLVMStructTypeInContext(context, field_types.as_mut_ptr(), field_types.len() as u32, false as i32);
+
+                    (return_type, vec![overloaded[0], overloaded[0]])
+                },
+                Intrinsic::Trap | Intrinsic::DebugTrap => (void_type, vec![]),
+                Intrinsic::DbgDeclare => (void_type, vec![metadata_type, metadata_type, metadata_type]),
+                // The i64 is the offset into the variable, which LLVM 6 removed
+                Intrinsic::DbgValue => (void_type, vec![metadata_type, i64_type, metadata_type, metadata_type]),
+            };
+
+            LLVMFunctionType(return_type, param_types.as_mut_ptr(), param_types.len() as u32, false as i32)
+        }
+    }
+}
+
+// Mangles a type the same way LLVM's Intrinsic::getName does for the types
+// intrinsics are overloaded on
+fn mangle_type(type_: LLVMTypeRef) -> String {
+    unsafe {
+        match LLVMGetTypeKind(type_) {
+            LLVMTypeKind::LLVMIntegerTypeKind => format!("i{}", LLVMGetIntTypeWidth(type_)),
+            LLVMTypeKind::LLVMHalfTypeKind => "f16".into(),
+            LLVMTypeKind::LLVMFloatTypeKind => "f32".into(),
+            LLVMTypeKind::LLVMDoubleTypeKind => "f64".into(),
+            LLVMTypeKind::LLVMX86_FP80TypeKind => "f80".into(),
+            LLVMTypeKind::LLVMFP128TypeKind => "f128".into(),
+            LLVMTypeKind::LLVMPPC_FP128TypeKind => "ppcf128".into(),
+            LLVMTypeKind::LLVMX86_MMXTypeKind => "x86mmx".into(),
+            LLVMTypeKind::LLVMPointerTypeKind => format!("p{}{}", LLVMGetPointerAddressSpace(type_), mangle_type(LLVMGetElementType(type_))),
+            LLVMTypeKind::LLVMVectorTypeKind => format!("v{}{}", LLVMGetVectorSize(type_), mangle_type(LLVMGetElementType(type_))),
+            LLVMTypeKind::LLVMArrayTypeKind => format!("a{}{}", LLVMGetArrayLength(type_), mangle_type(LLVMGetElementType(type_))),
+            _ => panic!("Intrinsics cannot be overloaded on this type"),
+        }
+    }
+}
@@ -1,32 +1,64 @@
 extern crate either;
-#[macro_use]
-extern crate enum_methods;
 extern crate libc;
-extern crate llvm_sys;
-
+#[cfg(feature = "llvm3-7")]
+extern crate llvm_sys_37 as llvm_sys;
+#[cfg(feature = "llvm3-8")]
+extern crate llvm_sys_38 as llvm_sys;
+#[cfg(feature = "llvm3-9")]
+extern crate llvm_sys_39 as llvm_sys;
+#[cfg(feature = "llvm4-0")]
+extern crate llvm_sys_40 as llvm_sys;
+
+// Exactly one LLVM version may be selected. Since `llvm3-7` is a default feature, selecting
+// another version requires `default-features = false` in the dependent's Cargo.toml
+#[cfg(not(any(feature = "llvm3-7", feature = "llvm3-8", feature = "llvm3-9", feature = "llvm4-0")))]
+compile_error!("One of the llvm3-7, llvm3-8, llvm3-9 or llvm4-0 features must be enabled");
+#[cfg(any(
+    all(feature = "llvm3-7", any(feature = "llvm3-8", feature = "llvm3-9", feature = "llvm4-0")),
+    all(feature = "llvm3-8", any(feature = "llvm3-9", feature = "llvm4-0")),
+    all(feature = "llvm3-9", feature = "llvm4-0"),
+))]
+compile_error!("Only one of the llvm3-7, llvm3-8, llvm3-9 or llvm4-0 features may be enabled at a time");
+
+pub mod analysis;
+pub mod attributes;
 pub mod basic_block;
 pub mod builder;
 pub mod context;
 pub mod data_layout;
 pub mod execution_engine;
+pub mod intrinsics;
 pub mod memory_buffer;
+pub mod memory_manager;
 pub mod module;
 pub mod object_file;
 pub mod passes;
+pub mod support;
 pub mod targets;
 pub mod types;
 pub mod values;
 
-use llvm_sys::{LLVMIntPredicate, LLVMRealPredicate, LLVMVisibility, LLVMThreadLocalMode, LLVMDLLStorageClass};
+use llvm_sys::{LLVMCallConv, LLVMIntPredicate, LLVMRealPredicate, LLVMVisibility, LLVMThreadLocalMode, LLVMDLLStorageClass, LLVMAtomicOrdering, LLVMAtomicRMWBinOp};
+#[cfg(feature = "llvm3-7")]
 use llvm_sys::core::LLVMResetFatalErrorHandler;
+#[cfg(not(feature = "llvm3-7"))]
+use llvm_sys::error_handling::LLVMResetFatalErrorHandler;
 use llvm_sys::support::LLVMLoadLibraryPermanently;
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, RwLock};
+
+// Brands a value with its (invariant) context, and borrows the module it lives in
+pub(crate) type ModuleMarker<'ctx, 'm> = PhantomData<(fn(&'ctx ()) -> &'ctx (), &'m ())>;
 
 // TODO: Probably move into error handling module
 pub fn enable_llvm_pretty_stack_trace() {
-    // use llvm_sys::error_handling::LLVMEnablePrettyStackTrace; // v3.8
+    #[cfg(feature = "llvm3-7")]
     use llvm_sys::core::LLVMEnablePrettyStackTrace;
+    #[cfg(not(feature = "llvm3-7"))]
+    use llvm_sys::error_handling::LLVMEnablePrettyStackTrace;
 
     unsafe {
         LLVMEnablePrettyStackTrace()
@@ -62,30 +94,62 @@ pub fn shutdown_llvm() {
     }
 }
 
-// Installs an error handler to be called before LLVM exits
-// pub fn install_fatal_error_hanlder<H: Fn(&CStr)>(handler: H) {
-//     extern "C" fn handler_wrapper(message: *const i8) {
-//         let c_string = CStr::from_ptr(message);
+type FatalErrorHandler = Arc<Fn(&str) + Send + Sync>;
+
+// LLVM only accepts a plain function pointer, so the Rust handler is kept in
+// a global slot for the wrapper to call. Fatal errors may occur on any thread.
+static FATAL_ERROR_HANDLER: RwLock<Option<FatalErrorHandler>> = RwLock::new(None);
 
-//         handler(&c_string);
-//     }
+extern "C" fn fatal_error_handler_wrapper(reason: *const libc::c_char) {
+    let reason = unsafe {
+        CStr::from_ptr(reason).to_string_lossy()
+    };
 
-//     let handler_wrapper = |message: *const i8| {
-//         let c_string = CStr::from_ptr(message);
+    // The lock is released before calling the handler, so that it may install or reset a handler itself
+    let handler = FATAL_ERROR_HANDLER.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
 
-//         handler(&c_string);
-//     };
+    // LLVM exits the process once the handler returns, so a panic can't be resumed
+    // afterwards, and it mustn't unwind into LLVM
+    if let Some(handler) = handler {
+        let _ = catch_unwind(AssertUnwindSafe(|| handler(&reason)));
+    }
+}
 
-//     unsafe {
-//         LLVMInstallFatalErrorHandler(handler_wrapper as LLVMFatalErrorHandler)
-//     }
-// }
+/// Installs a handler to be called with the reason for a fatal LLVM error, before
+/// LLVM exits the process. Any previously installed handler is replaced.
+///
+/// LLVM exits the process as soon as the handler returns, even if it panics, in which
+/// case the panic message is still printed but the panic goes no further.
+///
+/// # Example
+///
+/// ```no_run
+/// use inkwell::install_fatal_error_handler;
+///
+/// install_fatal_error_handler(|reason| {
+///     eprintln!("LLVM is about to exit: {}", reason);
+/// });
+/// ```
+pub fn install_fatal_error_handler<H: Fn(&str) + Send + Sync + 'static>(handler: H) {
+    #[cfg(feature = "llvm3-7")]
+    use llvm_sys::core::LLVMInstallFatalErrorHandler;
+    #[cfg(not(feature = "llvm3-7"))]
+    use llvm_sys::error_handling::LLVMInstallFatalErrorHandler;
+
+    *FATAL_ERROR_HANDLER.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(handler));
+
+    unsafe {
+        LLVMInstallFatalErrorHandler(Some(fatal_error_handler_wrapper))
+    }
+}
 
 /// Resets LLVM's fatal error handler back to the default
 pub fn reset_fatal_error_handler() {
     unsafe {
         LLVMResetFatalErrorHandler()
     }
+
+    *FATAL_ERROR_HANDLER.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
 }
 
 /// Defines the address space in which a global will be inserted.
@@ -324,6 +388,178 @@ impl DLLStorageClass {
     }
 }
 
+/// The orderings an `atomicrmw`, or the successful exchange of a `cmpxchg`, may have.
+/// Read-modify-write operations can't be `unordered` or `not_atomic`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AtomicOrdering {
+    Monotonic,
+    Acquire,
+    Release,
+    AcquireRelease,
+    SequentiallyConsistent,
+}
+
+impl AtomicOrdering {
+    pub(crate) fn as_llvm_ordering(&self) -> LLVMAtomicOrdering {
+        match *self {
+            AtomicOrdering::Monotonic => LLVMAtomicOrdering::LLVMAtomicOrderingMonotonic,
+            AtomicOrdering::Acquire => LLVMAtomicOrdering::LLVMAtomicOrderingAcquire,
+            AtomicOrdering::Release => LLVMAtomicOrdering::LLVMAtomicOrderingRelease,
+            AtomicOrdering::AcquireRelease => LLVMAtomicOrdering::LLVMAtomicOrderingAcquireRelease,
+            AtomicOrdering::SequentiallyConsistent => LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+        }
+    }
+}
+
+/// The orderings a `cmpxchg` may have when the comparison fails, in which case nothing
+/// is stored, so they can't be `Release` or `AcquireRelease`. Like `Builder::build_cmpxchg`,
+/// this is only available with the `llvm3-9` and `llvm4-0` features.
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CmpXchgFailureOrdering {
+    Monotonic,
+    Acquire,
+    SequentiallyConsistent,
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+impl CmpXchgFailureOrdering {
+    pub(crate) fn as_llvm_ordering(&self) -> LLVMAtomicOrdering {
+        match *self {
+            CmpXchgFailureOrdering::Monotonic => LLVMAtomicOrdering::LLVMAtomicOrderingMonotonic,
+            CmpXchgFailureOrdering::Acquire => LLVMAtomicOrdering::LLVMAtomicOrderingAcquire,
+            CmpXchgFailureOrdering::SequentiallyConsistent => LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+        }
+    }
+
+    /// Whether this ordering is stronger than `success_ordering`, which LLVM doesn't allow.
+    /// `Acquire` and `Release` are not comparable, so neither is stronger than the other.
+    pub(crate) fn is_stronger_than(&self, success_ordering: AtomicOrdering) -> bool {
+        match (*self, success_ordering) {
+            (CmpXchgFailureOrdering::Monotonic, _) => false,
+            (CmpXchgFailureOrdering::Acquire, AtomicOrdering::Monotonic) => true,
+            (CmpXchgFailureOrdering::Acquire, _) => false,
+            (CmpXchgFailureOrdering::SequentiallyConsistent, AtomicOrdering::SequentiallyConsistent) => false,
+            (CmpXchgFailureOrdering::SequentiallyConsistent, _) => true,
+        }
+    }
+}
+
+/// The orderings of a `cmpxchg`. `success` applies when the exchange takes place and `failure`
+/// when it doesn't, which must not be stronger than `success`. Like `Builder::build_cmpxchg`,
+/// this is only available with the `llvm3-9` and `llvm4-0` features.
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CmpXchgOrderings {
+    pub success: AtomicOrdering,
+    pub failure: CmpXchgFailureOrdering,
+}
+
+/// The orderings a `fence` may have. Fences can't be `NotAtomic`, `Unordered`
+/// or `Monotonic`, which `AtomicOrdering` would otherwise allow.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FenceOrdering {
+    Acquire,
+    Release,
+    AcquireRelease,
+    SequentiallyConsistent,
+}
+
+impl FenceOrdering {
+    pub(crate) fn as_llvm_ordering(&self) -> LLVMAtomicOrdering {
+        match *self {
+            FenceOrdering::Acquire => LLVMAtomicOrdering::LLVMAtomicOrderingAcquire,
+            FenceOrdering::Release => LLVMAtomicOrdering::LLVMAtomicOrderingRelease,
+            FenceOrdering::AcquireRelease => LLVMAtomicOrdering::LLVMAtomicOrderingAcquireRelease,
+            FenceOrdering::SequentiallyConsistent => LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+        }
+    }
+}
+
+/// The operation performed by an `atomicrmw` instruction. `Max` and `Min` compare
+/// as signed integers, `UMax` and `UMin` as unsigned integers.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AtomicRMWBinOp {
+    Xchg,
+    Add,
+    Sub,
+    And,
+    Nand,
+    Or,
+    Xor,
+    Max,
+    Min,
+    UMax,
+    UMin,
+}
+
+impl AtomicRMWBinOp {
+    pub(crate) fn as_llvm_bin_op(&self) -> LLVMAtomicRMWBinOp {
+        match *self {
+            AtomicRMWBinOp::Xchg => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXchg,
+            AtomicRMWBinOp::Add => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAdd,
+            AtomicRMWBinOp::Sub => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpSub,
+            AtomicRMWBinOp::And => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAnd,
+            AtomicRMWBinOp::Nand => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpNand,
+            AtomicRMWBinOp::Or => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpOr,
+            AtomicRMWBinOp::Xor => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXor,
+            AtomicRMWBinOp::Max => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpMax,
+            AtomicRMWBinOp::Min => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpMin,
+            AtomicRMWBinOp::UMax => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpUMax,
+            AtomicRMWBinOp::UMin => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpUMin,
+        }
+    }
+}
+
+/// The calling convention of a function or call site. Target specific conventions
+/// without a variant of their own are represented by `Other` with their LLVM id.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CallingConvention {
+    C,
+    Fast,
+    Cold,
+    WebKitJS,
+    AnyReg,
+    X86Stdcall,
+    X86Fastcall,
+    Other(u32),
+}
+
+impl Default for CallingConvention {
+    /// Returns the default value for `CallingConvention`, namely `CallingConvention::C`.
+    fn default() -> Self {
+        CallingConvention::C
+    }
+}
+
+impl CallingConvention {
+    pub(crate) fn new(calling_convention: u32) -> Self {
+        match calling_convention {
+            0 => CallingConvention::C,
+            8 => CallingConvention::Fast,
+            9 => CallingConvention::Cold,
+            12 => CallingConvention::WebKitJS,
+            13 => CallingConvention::AnyReg,
+            64 => CallingConvention::X86Stdcall,
+            65 => CallingConvention::X86Fastcall,
+            other => CallingConvention::Other(other),
+        }
+    }
+
+    pub(crate) fn as_llvm_call_conv(&self) -> u32 {
+        match *self {
+            CallingConvention::C => LLVMCallConv::LLVMCCallConv as u32,
+            CallingConvention::Fast => LLVMCallConv::LLVMFastCallConv as u32,
+            CallingConvention::Cold => LLVMCallConv::LLVMColdCallConv as u32,
+            CallingConvention::WebKitJS => LLVMCallConv::LLVMWebKitJSCallConv as u32,
+            CallingConvention::AnyReg => LLVMCallConv::LLVMAnyRegCallConv as u32,
+            CallingConvention::X86Stdcall => LLVMCallConv::LLVMX86StdcallCallConv as u32,
+            CallingConvention::X86Fastcall => LLVMCallConv::LLVMX86FastcallCallConv as u32,
+            CallingConvention::Other(other) => other,
+        }
+    }
+}
+
 // Misc Notes
 // Always pass a c_string.as_ptr() call into the function call directly and never
 // before hand. Seems to make a huge difference (stuff stops working) otherwise
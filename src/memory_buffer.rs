@@ -1,8 +1,9 @@
-use llvm_sys::core::{LLVMDisposeMessage, LLVMCreateMemoryBufferWithContentsOfFile, LLVMCreateMemoryBufferWithSTDIN, LLVMCreateMemoryBufferWithMemoryRange, LLVMCreateMemoryBufferWithMemoryRangeCopy, LLVMGetBufferStart, LLVMGetBufferSize, LLVMDisposeMemoryBuffer};
+use llvm_sys::core::{LLVMCreateMemoryBufferWithContentsOfFile, LLVMCreateMemoryBufferWithSTDIN, LLVMCreateMemoryBufferWithMemoryRange, LLVMCreateMemoryBufferWithMemoryRangeCopy, LLVMGetBufferStart, LLVMGetBufferSize, LLVMDisposeMemoryBuffer};
 use llvm_sys::prelude::LLVMMemoryBufferRef;
 use llvm_sys::object::LLVMCreateObjectFile;
 
 use object_file::ObjectFile;
+use support::LLVMString;
 
 use std::ffi::{CString, CStr};
 use std::mem::zeroed;
@@ -24,23 +25,17 @@ impl MemoryBuffer {
 
     pub fn create_from_file(path: &Path) -> Result<Self, String> {
         let path = path.to_str().expect("Did not find a valid Unicode path string");
+        let c_string = CString::new(path).expect("Conversion to CString failed unexpectedly");
         let mut memory_buffer = ptr::null_mut();
         let mut err_str = unsafe { zeroed() };
 
         let return_code = unsafe {
-            // REVIEW: Unclear why this expects *const i8 instead of *const u8
-            LLVMCreateMemoryBufferWithContentsOfFile(path.as_ptr() as *const i8, &mut memory_buffer, &mut err_str)
+            LLVMCreateMemoryBufferWithContentsOfFile(c_string.as_ptr(), &mut memory_buffer, &mut err_str)
         };
 
         // TODO: Verify 1 is error code (LLVM can be inconsistent)
         if return_code == 1 {
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-                LLVMDisposeMessage(err_str);
-
-                rust_str
-            };
+            let rust_str = LLVMString::new(err_str).to_string();
 
             return Err(rust_str);
         }
@@ -58,13 +53,7 @@ impl MemoryBuffer {
 
         // TODO: Verify 1 is error code (LLVM can be inconsistent)
         if return_code == 1 {
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-                LLVMDisposeMessage(err_str);
-
-                rust_str
-            };
+            let rust_str = LLVMString::new(err_str).to_string();
 
             return Err(rust_str);
         }
@@ -0,0 +1,328 @@
+//! Memory managers which allocate the sections of MCJIT compiled code and data.
+
+use libc::{c_char, c_uint, c_void, uintptr_t};
+use llvm_sys::execution_engine::{LLVMCreateSimpleMCJITMemoryManager, LLVMMCJITMemoryManagerRef};
+use llvm_sys::prelude::LLVMBool;
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+/// Allocates and finalizes the memory MCJIT emits code and data sections into.
+///
+/// A memory manager is owned by the `ExecutionEngine` it is given to, which
+/// calls `destroy` before dropping it. Panics cannot unwind into LLVM, so a
+/// panicking allocation is reported as a failed (null) allocation and a
+/// panicking `finalize_memory` as an error.
+pub trait McJitMemoryManager {
+    /// Allocates `size` bytes of memory for executable code, aligned to `alignment`.
+    /// Returns a null pointer on failure.
+    fn allocate_code_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str) -> *mut u8;
+
+    /// Allocates `size` bytes of memory for data, aligned to `alignment`.
+    /// Returns a null pointer on failure.
+    fn allocate_data_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str, is_read_only: bool) -> *mut u8;
+
+    /// Called once the sections have been written, to apply page permissions
+    /// and flush the instruction cache.
+    fn finalize_memory(&mut self) -> Result<(), String>;
+
+    /// Called when the owning `ExecutionEngine` is disposed of.
+    fn destroy(&mut self);
+}
+
+// The boxed memory manager is double boxed so that it can be passed to LLVM through a thin pointer
+pub(crate) fn create_llvm_memory_manager<MM: McJitMemoryManager + 'static>(memory_manager: MM) -> LLVMMCJITMemoryManagerRef {
+    let boxed: Box<Box<McJitMemoryManager>> = Box::new(Box::new(memory_manager));
+    let opaque = Box::into_raw(boxed) as *mut c_void;
+
+    let memory_manager = unsafe {
+        LLVMCreateSimpleMCJITMemoryManager(opaque, allocate_code_section_callback, allocate_data_section_callback, finalize_memory_callback, Some(destroy_callback))
+    };
+
+    assert!(!memory_manager.is_null());
+
+    memory_manager
+}
+
+unsafe fn memory_manager_from_opaque<'a>(opaque: *mut c_void) -> &'a mut Box<McJitMemoryManager> {
+    &mut *(opaque as *mut Box<McJitMemoryManager>)
+}
+
+fn panic_message(payload: &(Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message;
+    }
+
+    payload.downcast_ref::<String>().map_or("Box<Any>", |message| message)
+}
+
+extern "C" fn allocate_code_section_callback(opaque: *mut c_void, size: uintptr_t, alignment: c_uint, section_id: c_uint, section_name: *const c_char) -> *mut u8 {
+    let memory_manager = unsafe { memory_manager_from_opaque(opaque) };
+    let section_name = unsafe { CStr::from_ptr(section_name) }.to_string_lossy();
+
+    catch_unwind(AssertUnwindSafe(|| memory_manager.allocate_code_section(size, alignment, section_id, &section_name)))
+        .unwrap_or(::std::ptr::null_mut())
+}
+
+extern "C" fn allocate_data_section_callback(opaque: *mut c_void, size: uintptr_t, alignment: c_uint, section_id: c_uint, section_name: *const c_char, is_read_only: LLVMBool) -> *mut u8 {
+    let memory_manager = unsafe { memory_manager_from_opaque(opaque) };
+    let section_name = unsafe { CStr::from_ptr(section_name) }.to_string_lossy();
+
+    catch_unwind(AssertUnwindSafe(|| memory_manager.allocate_data_section(size, alignment, section_id, &section_name, is_read_only != 0)))
+        .unwrap_or(::std::ptr::null_mut())
+}
+
+extern "C" fn finalize_memory_callback(opaque: *mut c_void, err_msg: *mut *mut c_char) -> LLVMBool {
+    let memory_manager = unsafe { memory_manager_from_opaque(opaque) };
+    let result = catch_unwind(AssertUnwindSafe(|| memory_manager.finalize_memory())).unwrap_or_else(|payload| {
+        Err(format!("The memory manager panicked while finalizing memory: {}", panic_message(&*payload)))
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            // LLVM frees the message with free(), so it must be allocated by malloc
+            let c_string = CString::new(err).unwrap_or_else(|_| CString::new("Failed to finalize memory").unwrap());
+
+            unsafe {
+                *err_msg = ::libc::strdup(c_string.as_ptr());
+            }
+
+            1
+        },
+    }
+}
+
+// There is no way to report a failure to LLVM here, so a panic while destroying
+// or dropping the memory manager is swallowed rather than unwinding into LLVM
+extern "C" fn destroy_callback(opaque: *mut c_void) {
+    let mut memory_manager = unsafe { Box::from_raw(opaque as *mut Box<McJitMemoryManager>) };
+
+    let _ = catch_unwind(AssertUnwindSafe(move || {
+        memory_manager.destroy();
+    }));
+}
+
+/// The kind of memory a section was allocated for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SectionKind {
+    Code,
+    ReadOnlyData,
+    ReadWriteData,
+}
+
+/// The memory used by a single section allocated by a `PageMemoryManager`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SectionUsage {
+    pub section_id: u32,
+    pub section_name: String,
+    pub kind: SectionKind,
+    /// The address the section was allocated at.
+    pub address: usize,
+    /// The number of bytes requested for the section.
+    pub size: usize,
+    /// The number of bytes mapped for the section, which is rounded up to whole pages.
+    pub mapped_size: usize,
+}
+
+/// A handle to the memory usage of a `PageMemoryManager`, which remains
+/// readable after the memory manager has been handed to an `ExecutionEngine`.
+#[derive(Debug, Clone)]
+pub struct MemoryUsageTracker {
+    sections: Rc<RefCell<Vec<SectionUsage>>>,
+}
+
+impl MemoryUsageTracker {
+    /// Gets the usage of every section which is currently allocated.
+    pub fn get_sections(&self) -> Vec<SectionUsage> {
+        self.sections.borrow().clone()
+    }
+
+    /// Gets the number of bytes mapped for sections of the given kind.
+    pub fn get_mapped_bytes(&self, kind: SectionKind) -> usize {
+        self.sections.borrow()
+                     .iter()
+                     .filter(|section| section.kind == kind)
+                     .map(|section| section.mapped_size)
+                     .sum()
+    }
+
+    /// Gets the number of bytes mapped for all sections.
+    pub fn get_total_mapped_bytes(&self) -> usize {
+        self.sections.borrow().iter().map(|section| section.mapped_size).sum()
+    }
+}
+
+#[cfg(unix)]
+#[derive(Debug)]
+struct Mapping {
+    ptr: *mut c_void,
+    len: usize,
+    kind: SectionKind,
+    finalized: bool,
+}
+
+/// A `McJitMemoryManager` which maps every section into its own pages.
+///
+/// Pages are mapped as readable and writable, and are only made executable
+/// (and no longer writable) for code sections, or read-only for read-only data
+/// sections, once the memory is finalized. The memory used by each section can
+/// be observed through a `MemoryUsageTracker`.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct PageMemoryManager {
+    mappings: Vec<Mapping>,
+    sections: Rc<RefCell<Vec<SectionUsage>>>,
+    page_size: usize,
+}
+
+#[cfg(unix)]
+impl PageMemoryManager {
+    pub fn new() -> Self {
+        let page_size = unsafe {
+            ::libc::sysconf(::libc::_SC_PAGESIZE) as usize
+        };
+
+        PageMemoryManager {
+            mappings: Vec::new(),
+            sections: Rc::new(RefCell::new(Vec::new())),
+            page_size,
+        }
+    }
+
+    pub fn get_usage_tracker(&self) -> MemoryUsageTracker {
+        MemoryUsageTracker {
+            sections: self.sections.clone(),
+        }
+    }
+
+    fn allocate(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str, kind: SectionKind) -> *mut u8 {
+        let alignment = alignment.max(1) as usize;
+
+        // Mappings are page aligned, so larger alignments are satisfied by mapping extra
+        // pages and skipping ahead within them
+        let padding = if alignment > self.page_size { alignment } else { 0 };
+        let len = round_up(size.max(1) + padding, self.page_size);
+
+        let ptr = unsafe {
+            ::libc::mmap(::std::ptr::null_mut(), len, ::libc::PROT_READ | ::libc::PROT_WRITE, ::libc::MAP_PRIVATE | ::libc::MAP_ANON, -1, 0)
+        };
+
+        if ptr == ::libc::MAP_FAILED {
+            return ::std::ptr::null_mut();
+        }
+
+        let address = round_up(ptr as usize, alignment);
+
+        self.mappings.push(Mapping {
+            ptr,
+            len,
+            kind,
+            finalized: false,
+        });
+        self.sections.borrow_mut().push(SectionUsage {
+            section_id,
+            section_name: section_name.to_string(),
+            kind,
+            address,
+            size,
+            mapped_size: len,
+        });
+
+        address as *mut u8
+    }
+}
+
+#[cfg(unix)]
+impl Default for PageMemoryManager {
+    fn default() -> Self {
+        PageMemoryManager::new()
+    }
+}
+
+#[cfg(unix)]
+impl McJitMemoryManager for PageMemoryManager {
+    fn allocate_code_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str) -> *mut u8 {
+        self.allocate(size, alignment, section_id, section_name, SectionKind::Code)
+    }
+
+    fn allocate_data_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str, is_read_only: bool) -> *mut u8 {
+        let kind = if is_read_only { SectionKind::ReadOnlyData } else { SectionKind::ReadWriteData };
+
+        self.allocate(size, alignment, section_id, section_name, kind)
+    }
+
+    fn finalize_memory(&mut self) -> Result<(), String> {
+        for mapping in self.mappings.iter_mut().filter(|mapping| !mapping.finalized) {
+            // Architectures without a coherent instruction cache (ie ARM) would otherwise
+            // be able to execute stale instructions from the freshly written code
+            if mapping.kind == SectionKind::Code {
+                unsafe {
+                    flush_instruction_cache(mapping.ptr, mapping.len);
+                }
+            }
+
+            let protection = match mapping.kind {
+                SectionKind::Code => ::libc::PROT_READ | ::libc::PROT_EXEC,
+                SectionKind::ReadOnlyData => ::libc::PROT_READ,
+                SectionKind::ReadWriteData => ::libc::PROT_READ | ::libc::PROT_WRITE,
+            };
+
+            let code = unsafe {
+                ::libc::mprotect(mapping.ptr, mapping.len, protection)
+            };
+
+            if code != 0 {
+                return Err(format!("Failed to set the protection of {:?} section memory: {}", mapping.kind, ::std::io::Error::last_os_error()));
+            }
+
+            mapping.finalized = true;
+        }
+
+        Ok(())
+    }
+
+    fn destroy(&mut self) {
+        for mapping in self.mappings.drain(..) {
+            unsafe {
+                ::libc::munmap(mapping.ptr, mapping.len);
+            }
+        }
+
+        self.sections.borrow_mut().clear();
+    }
+}
+
+#[cfg(unix)]
+impl Drop for PageMemoryManager {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios"))))]
+unsafe fn flush_instruction_cache(ptr: *mut c_void, len: usize) {
+    extern "C" {
+        fn __clear_cache(start: *mut c_char, end: *mut c_char);
+    }
+
+    __clear_cache(ptr as *mut c_char, (ptr as *mut c_char).add(len))
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+unsafe fn flush_instruction_cache(ptr: *mut c_void, len: usize) {
+    extern "C" {
+        fn sys_icache_invalidate(start: *mut c_void, len: usize);
+    }
+
+    sys_icache_invalidate(ptr, len)
+}
+
+// Both page sizes and section alignments are powers of two
+#[cfg(unix)]
+fn round_up(value: usize, multiple: usize) -> usize {
+    (value + multiple - 1) & !(multiple - 1)
+}
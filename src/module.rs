@@ -1,23 +1,32 @@
+use libc::c_char;
 use llvm_sys::analysis::{LLVMVerifyModule, LLVMVerifierFailureAction};
 use llvm_sys::bit_writer::{LLVMWriteBitcodeToFile, LLVMWriteBitcodeToMemoryBuffer};
-use llvm_sys::core::{LLVMAddFunction, LLVMAddGlobal, LLVMDisposeMessage, LLVMDumpModule, LLVMGetNamedFunction, LLVMGetTypeByName, LLVMSetDataLayout, LLVMSetTarget, LLVMCloneModule, LLVMDisposeModule, LLVMGetTarget, LLVMGetDataLayout, LLVMModuleCreateWithName, LLVMGetModuleContext, LLVMGetFirstFunction, LLVMGetLastFunction, LLVMSetLinkage, LLVMAddGlobalInAddressSpace, LLVMPrintModuleToString, LLVMGetNamedMetadataNumOperands, LLVMAddNamedMetadataOperand, LLVMGetNamedMetadataOperands, LLVMGetFirstGlobal, LLVMGetLastGlobal, LLVMGetNamedGlobal, LLVMPrintModuleToFile, LLVMSetModuleInlineAsm};
-use llvm_sys::execution_engine::LLVMCreateJITCompilerForModule;
-use llvm_sys::prelude::{LLVMValueRef, LLVMModuleRef};
+use llvm_sys::core::{LLVMAddFunction, LLVMAddGlobal, LLVMDumpModule, LLVMGetNamedFunction, LLVMGetTypeByName, LLVMSetDataLayout, LLVMSetTarget, LLVMCloneModule, LLVMDisposeModule, LLVMGetTarget, LLVMModuleCreateWithName, LLVMGetModuleContext, LLVMGetFirstFunction, LLVMGetLastFunction, LLVMSetLinkage, LLVMAddGlobalInAddressSpace, LLVMPrintModuleToString, LLVMGetNamedMetadataNumOperands, LLVMAddNamedMetadataOperand, LLVMGetNamedMetadataOperands, LLVMGetFirstGlobal, LLVMGetLastGlobal, LLVMGetNamedGlobal, LLVMPrintModuleToFile, LLVMSetModuleInlineAsm, LLVMGetNextFunction, LLVMGetNextGlobal, LLVMGetPreviousFunction, LLVMGetPreviousGlobal, LLVMGetValueName, LLVMGetLinkage, LLVMIsDeclaration, LLVMModuleCreateWithNameInContext};
+use llvm_sys::execution_engine::{LLVMCreateJITCompilerForModule, LLVMCreateInterpreterForModule, LLVMCreateMCJITCompilerForModule, LLVMInitializeMCJITCompilerOptions, LLVMMCJITCompilerOptions, LLVMMCJITMemoryManagerRef, LLVMExecutionEngineRef, LLVMAddModule, LLVMRemoveModule};
+use llvm_sys::prelude::{LLVMBool, LLVMValueRef, LLVMModuleRef};
 use llvm_sys::LLVMLinkage;
 
 use std::cell::{Cell, RefCell};
+use std::error::Error;
 use std::ffi::{CString, CStr};
+use std::fmt;
 use std::fs::File;
-use std::mem::{forget, uninitialized, zeroed};
+use std::marker::PhantomData;
+use std::mem::{forget, size_of, zeroed};
 use std::path::Path;
+use std::ptr;
 use std::rc::Rc;
 use std::slice::from_raw_parts;
 
 use {AddressSpace, OptimizationLevel};
-use context::{Context, ContextRef};
+use context::{Context, ContextRef, resume_diagnostic_handler_panic};
 use data_layout::DataLayout;
-use execution_engine::ExecutionEngine;
+use execution_engine::{ExecutionEngine, PerfMapListener};
+use intrinsics::Intrinsic;
 use memory_buffer::MemoryBuffer;
+use memory_manager::{McJitMemoryManager, create_llvm_memory_manager};
+use support::LLVMString;
+use targets::CodeModel;
 use types::{AsTypeRef, BasicType, FunctionType, BasicTypeEnum};
 use values::{AsValueRef, FunctionValue, GlobalValue, MetadataValue};
 
@@ -89,53 +98,117 @@ impl Linkage {
     }
 }
 
+/// An error encountered while linking one `Module` into another.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LinkingError {
+    /// The modules were created in different `Context`s.
+    ContextMismatch,
+    /// The source module has been handed to an `ExecutionEngine`, which owns it.
+    OwnedByExecutionEngine,
+    /// Both modules contain a strong definition of the named symbol.
+    SymbolConflict(String),
+    /// LLVM failed to link the modules. Newer versions of LLVM only report the
+    /// reason through the context's diagnostic handler.
+    LinkFailed(String),
+}
+
+/// The reasons LLVM's verifier rejected a `Module` or `FunctionValue`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VerificationError {
+    message: String,
+}
+
+impl VerificationError {
+    pub(crate) fn new(message: String) -> Self {
+        VerificationError { message }
+    }
+
+    /// Gets the verifier's output, which lists each problem it found.
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for VerificationError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+}
+
+pub(crate) fn verify_module(module: LLVMModuleRef) -> Result<(), VerificationError> {
+    let mut err_str = ptr::null_mut();
+
+    let code = unsafe {
+        LLVMVerifyModule(module, LLVMVerifierFailureAction::LLVMReturnStatusAction, &mut err_str)
+    };
+
+    // LLVM allocates a message even when verification succeeds
+    let message = if err_str.is_null() {
+        String::new()
+    } else {
+        LLVMString::new(err_str).to_string()
+    };
+
+    if code == 1 {
+        return Err(VerificationError::new(message));
+    }
+
+    Ok(())
+}
+
 /// Represents a reference to an LLVM `Module`.
 /// The underlying module will be disposed when dropping this object.
+///
+/// The functions, globals and instructions taken from a `Module` borrow it, so they
+/// can't outlive it:
+///
+/// ```compile_fail
+/// use inkwell::context::Context;
+///
+/// let context = Context::create();
+/// let fn_type = context.void_type().fn_type(&[], false);
+/// let function = {
+///     let module = context.create_module("my_module");
+///
+///     module.add_function("my_fn", &fn_type, None)
+/// };
+/// ```
+///
+/// Nor can they outlive the `Context`:
+///
+/// ```compile_fail
+/// use inkwell::context::Context;
+///
+/// let function = {
+///     let context = Context::create();
+///     let module = context.create_module("my_module");
+///     let fn_type = context.void_type().fn_type(&[], false);
+///
+///     module.add_function("my_fn", &fn_type, None)
+/// };
+/// ```
 #[derive(Debug, PartialEq, Eq)]
-pub struct Module {
+pub struct Module<'ctx> {
     pub(crate) non_global_context: Option<Context>,
-    data_layout: Option<DataLayout>,
     pub(crate) module: Cell<LLVMModuleRef>,
-    pub(crate) owned_by_ee: RefCell<Option<ExecutionEngine>>,
+    pub(crate) owned_by_ee: RefCell<Option<ExecutionEngine<'ctx>>>,
 }
 
-impl Module {
+impl<'ctx> Module<'ctx> {
     pub(crate) fn new(module: LLVMModuleRef, context: Option<&Context>) -> Self {
         assert!(!module.is_null());
 
-        let mut module = Module {
+        Module {
             module: Cell::new(module),
             non_global_context: context.map(|ctx| Context::new(ctx.context.clone())),
             owned_by_ee: RefCell::new(None),
-            data_layout: None,
-        };
-
-        module.data_layout = Some(DataLayout::new(module.get_raw_data_layout()));
-        module
-    }
-
-    /// Creates a named `Module`. Will be automatically assigned the global context.
-    ///
-    /// To use your own `Context`, see [inkwell::context::create_module()](../context/struct.Context.html#method.create_module)
-    ///
-    /// # Example
-    /// ```
-    /// use inkwell::context::Context;
-    /// use inkwell::module::Module;
-    ///
-    /// let context = Context::get_global();
-    /// let module = Module::create("my_module");
-    ///
-    /// assert_eq!(module.get_context(), context);
-    /// ```
-    pub fn create(name: &str) -> Self {
-        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
-
-        let module = unsafe {
-            LLVMModuleCreateWithName(c_string.as_ptr())
-        };
-
-        Module::new(module, None)
+        }
     }
 
     /// Creates a function given its `name` and `ty`, adds it to the `Module`
@@ -158,7 +231,7 @@ impl Module {
     /// assert_eq!(fn_val.get_name().to_str(), Ok("my_function"));
     /// assert_eq!(fn_val.get_linkage(), Linkage::ExternalLinkage);
     /// ```
-    pub fn add_function(&self, name: &str, ty: &FunctionType, linkage: Option<&Linkage>) -> FunctionValue {
+    pub fn add_function<'m>(&'m self, name: &str, ty: &FunctionType<'ctx>, linkage: Option<&Linkage>) -> FunctionValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -192,7 +265,7 @@ impl Module {
     /// assert_eq!(*local_module.get_context(), local_context);
     /// assert_ne!(local_context, *global_context);
     /// ```
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         let context = unsafe {
             LLVMGetModuleContext(self.module.get())
         };
@@ -219,7 +292,7 @@ impl Module {
     ///
     /// assert_eq!(fn_value, module.get_first_function().unwrap());
     /// ```
-    pub fn get_first_function(&self) -> Option<FunctionValue> {
+    pub fn get_first_function<'m>(&'m self) -> Option<FunctionValue<'ctx, 'm>> {
         let function = unsafe {
             LLVMGetFirstFunction(self.module.get())
         };
@@ -245,7 +318,7 @@ impl Module {
     ///
     /// assert_eq!(fn_value, module.get_last_function().unwrap());
     /// ```
-    pub fn get_last_function(&self) -> Option<FunctionValue> {
+    pub fn get_last_function<'m>(&'m self) -> Option<FunctionValue<'ctx, 'm>> {
         let function = unsafe {
             LLVMGetLastFunction(self.module.get())
         };
@@ -253,6 +326,48 @@ impl Module {
         FunctionValue::new(function)
     }
 
+    /// Gets an iterator over all `FunctionValue`s defined in this `Module`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_mod");
+    /// let void_type = context.void_type();
+    /// let fn_type = void_type.fn_type(&[], false);
+    /// let fn_value = module.add_function("my_fn", &fn_type, None);
+    /// let fn_value2 = module.add_function("my_fn2", &fn_type, None);
+    ///
+    /// assert_eq!(module.functions().collect::<Vec<_>>(), vec![fn_value, fn_value2]);
+    /// assert_eq!(module.functions().rev().next(), Some(fn_value2));
+    /// ```
+    ///
+    /// The iterator borrows the `Module`, so it can't be used once the `Module` is dropped:
+    ///
+    /// ```compile_fail
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_mod");
+    /// let mut functions = module.functions();
+    ///
+    /// drop(module);
+    ///
+    /// functions.next();
+    /// ```
+    pub fn functions<'m>(&'m self) -> FunctionIter<'m, 'ctx> {
+        let (first, last) = unsafe {
+            (LLVMGetFirstFunction(self.module.get()), LLVMGetLastFunction(self.module.get()))
+        };
+
+        FunctionIter {
+            front: first,
+            back: last,
+            _marker: PhantomData,
+        }
+    }
+
     /// Gets a `FunctionValue` defined in this `Module` by its name.
     ///
     /// # Example
@@ -271,7 +386,7 @@ impl Module {
     ///
     /// assert_eq!(fn_value, module.get_function("my_fn").unwrap());
     /// ```
-    pub fn get_function(&self, name: &str) -> Option<FunctionValue> {
+    pub fn get_function<'m>(&'m self, name: &str) -> Option<FunctionValue<'ctx, 'm>> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -281,7 +396,29 @@ impl Module {
         FunctionValue::new(value)
     }
 
-    pub fn get_type(&self, name: &str) -> Option<BasicTypeEnum> {
+    /// Gets the declaration of an intrinsic function, declaring it in this `Module` if it hasn't
+    /// been already. `overloaded_types` are used to mangle the name and build the signature of
+    /// overloaded intrinsics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inkwell::context::Context;
+    /// use inkwell::intrinsics::Intrinsic;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_module");
+    /// let f64_type = context.f64_type();
+    /// let sqrt = module.get_intrinsic_function(Intrinsic::Sqrt, &[f64_type.into()]);
+    ///
+    /// assert_eq!(sqrt.get_name().to_str(), Ok("llvm.sqrt.f64"));
+    /// assert_eq!(sqrt, module.get_intrinsic_function(Intrinsic::Sqrt, &[f64_type.into()]));
+    /// ```
+    pub fn get_intrinsic_function<'m>(&'m self, intrinsic: Intrinsic, overloaded_types: &[BasicTypeEnum<'ctx>]) -> FunctionValue<'ctx, 'm> {
+        intrinsic.get_declaration(self.module.get(), overloaded_types)
+    }
+
+    pub fn get_type(&self, name: &str) -> Option<BasicTypeEnum<'ctx>> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let type_ = unsafe {
@@ -328,34 +465,200 @@ impl Module {
     ///
     /// assert_eq!(module.get_context(), context);
     /// ```
-    pub fn create_jit_execution_engine(&self, opt_level: OptimizationLevel) -> Result<ExecutionEngine, String> {
-        let mut execution_engine = unsafe { uninitialized() };
-        let mut err_str = unsafe { zeroed() };
+    pub fn create_jit_execution_engine(&self, opt_level: OptimizationLevel) -> Result<ExecutionEngine<'ctx>, String> {
+        self.check_not_owned_by_ee()?;
 
-        let code = unsafe {
-            LLVMCreateJITCompilerForModule(&mut execution_engine, self.module.get(), opt_level as u32, &mut err_str) // Should take ownership of module
+        self.create_execution_engine(true, |execution_engine, module, err_str| unsafe {
+            LLVMCreateJITCompilerForModule(execution_engine, module, opt_level as u32, err_str)
+        })
+    }
+
+    /// Consumes this `Module`, and creates an interpreter `ExecutionEngine` from it.
+    /// The interpreter doesn't require a JIT backend for the target, but functions
+    /// can only be run through `ExecutionEngine::run_function`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use inkwell::context::Context;
+    /// use inkwell::execution_engine::FunctionLookupError;
+    /// use inkwell::targets::{InitializationConfig, Target};
+    ///
+    /// Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_module");
+    /// let builder = context.create_builder();
+    /// let i32_type = context.i32_type();
+    /// let fn_type = i32_type.fn_type(&[], false);
+    ///
+    /// let function = module.add_function("forty_two", &fn_type, None);
+    /// let basic_block = context.append_basic_block(&function, "entry");
+    ///
+    /// builder.position_at_end(&basic_block);
+    /// builder.build_return(Some(&i32_type.const_int(42, false)));
+    ///
+    /// let execution_engine = module.create_interpreter_execution_engine().unwrap();
+    /// let result = unsafe { execution_engine.run_function(&function, &[]) };
+    ///
+    /// assert_eq!(result.as_int(false), 42);
+    /// assert_eq!(execution_engine.get_function_address("forty_two"), Err(FunctionLookupError::JITNotEnabled));
+    /// ```
+    pub fn create_interpreter_execution_engine(&self) -> Result<ExecutionEngine<'ctx>, String> {
+        self.check_not_owned_by_ee()?;
+
+        ExecutionEngine::link_in_interpreter();
+
+        self.create_execution_engine(false, |execution_engine, module, err_str| unsafe {
+            LLVMCreateInterpreterForModule(execution_engine, module, err_str)
+        })
+    }
+
+    /// Consumes this `Module`, and creates an MCJIT `ExecutionEngine` from it
+    /// with the given code generation options.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use inkwell::OptimizationLevel;
+    /// use inkwell::context::Context;
+    /// use inkwell::targets::{CodeModel, InitializationConfig, Target};
+    ///
+    /// Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_module");
+    /// let execution_engine = module.create_mcjit_execution_engine_with_options(OptimizationLevel::None, CodeModel::JITDefault, true, false).unwrap();
+    /// ```
+    pub fn create_mcjit_execution_engine_with_options(&self, opt_level: OptimizationLevel, code_model: CodeModel, no_frame_pointer_elim: bool, enable_fast_isel: bool) -> Result<ExecutionEngine<'ctx>, String> {
+        self.create_mcjit_execution_engine(ptr::null_mut(), opt_level, code_model, no_frame_pointer_elim, enable_fast_isel)
+    }
+
+    /// Consumes this `Module`, and creates an MCJIT `ExecutionEngine` from it which
+    /// allocates code and data sections through the given `McJitMemoryManager`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use inkwell::OptimizationLevel;
+    /// use inkwell::context::Context;
+    /// use inkwell::memory_manager::{PageMemoryManager, SectionKind};
+    /// use inkwell::targets::{CodeModel, InitializationConfig, Target};
+    ///
+    /// Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_module");
+    /// let builder = context.create_builder();
+    /// let void_type = context.void_type();
+    /// let function = module.add_function("nothing", &void_type.fn_type(&[], false), None);
+    /// let basic_block = context.append_basic_block(&function, "entry");
+    ///
+    /// builder.position_at_end(&basic_block);
+    /// builder.build_return(None);
+    ///
+    /// let memory_manager = PageMemoryManager::new();
+    /// let usage_tracker = memory_manager.get_usage_tracker();
+    /// let execution_engine = module.create_mcjit_execution_engine_with_memory_manager(memory_manager, OptimizationLevel::None, CodeModel::JITDefault, false, false).unwrap();
+    ///
+    /// execution_engine.get_function_address("nothing").unwrap();
+    ///
+    /// assert!(usage_tracker.get_mapped_bytes(SectionKind::Code) > 0);
+    /// ```
+    pub fn create_mcjit_execution_engine_with_memory_manager<MM: McJitMemoryManager + 'static>(&self, memory_manager: MM, opt_level: OptimizationLevel, code_model: CodeModel, no_frame_pointer_elim: bool, enable_fast_isel: bool) -> Result<ExecutionEngine<'ctx>, String> {
+        // Checked before the memory manager is handed to LLVM so that it is simply dropped
+        self.check_not_owned_by_ee()?;
+
+        // The ExecutionEngine takes ownership of the memory manager even if it fails to be created
+        let memory_manager = create_llvm_memory_manager(memory_manager);
+
+        self.create_mcjit_execution_engine(memory_manager, opt_level, code_model, no_frame_pointer_elim, enable_fast_isel)
+    }
+
+    /// Consumes this `Module`, and creates an MCJIT `ExecutionEngine` from it which allocates
+    /// code and data sections through the given `McJitMemoryManager`, and writes an entry to
+    /// the given `PerfMapListener`'s perf map for each function it compiles.
+    pub fn create_mcjit_execution_engine_with_perf_map<MM: McJitMemoryManager + 'static>(&self, perf_map_listener: &PerfMapListener, memory_manager: MM, opt_level: OptimizationLevel, code_model: CodeModel, no_frame_pointer_elim: bool, enable_fast_isel: bool) -> Result<ExecutionEngine<'ctx>, String> {
+        let memory_manager = perf_map_listener.wrap_memory_manager(memory_manager);
+        let execution_engine = self.create_mcjit_execution_engine_with_memory_manager(memory_manager, opt_level, code_model, no_frame_pointer_elim, enable_fast_isel)?;
+
+        execution_engine.set_perf_map_listener(perf_map_listener.clone());
+
+        Ok(execution_engine)
+    }
+
+    fn create_mcjit_execution_engine(&self, memory_manager: LLVMMCJITMemoryManagerRef, opt_level: OptimizationLevel, code_model: CodeModel, no_frame_pointer_elim: bool, enable_fast_isel: bool) -> Result<ExecutionEngine<'ctx>, String> {
+        self.check_not_owned_by_ee()?;
+
+        let mut options: LLVMMCJITCompilerOptions = unsafe { zeroed() };
+
+        unsafe {
+            LLVMInitializeMCJITCompilerOptions(&mut options, size_of::<LLVMMCJITCompilerOptions>());
+        }
+
+        options.OptLevel = opt_level as u32;
+        options.CodeModel = code_model.as_llvm_code_model();
+        options.NoFramePointerElim = no_frame_pointer_elim as i32;
+        options.EnableFastISel = enable_fast_isel as i32;
+        options.MCJMM = memory_manager;
+
+        self.create_execution_engine(true, |execution_engine, module, err_str| unsafe {
+            LLVMCreateMCJITCompilerForModule(execution_engine, module, &mut options, size_of::<LLVMMCJITCompilerOptions>(), err_str)
+        })
+    }
+
+    // A module can only belong to one ExecutionEngine at a time
+    fn check_not_owned_by_ee(&self) -> Result<(), String> {
+        if self.owned_by_ee.borrow().is_some() {
+            return Err("This module is already owned by an ExecutionEngine".into());
+        }
+
+        Ok(())
+    }
+
+    // LLVM's EngineBuilder owns the module as soon as it is handed over, and disposes of it
+    // if the engine can't be created. So the engine is created for an empty stand-in module
+    // with the same target and data layout, which is only swapped for this one on success
+    fn create_execution_engine<F>(&self, jit_mode: bool, create: F) -> Result<ExecutionEngine<'ctx>, String>
+    where
+        F: FnOnce(*mut LLVMExecutionEngineRef, LLVMModuleRef, *mut *mut c_char) -> LLVMBool,
+    {
+        let c_string = CString::new("stand_in").expect("Conversion to CString failed unexpectedly");
+        let stand_in = unsafe {
+            let stand_in = LLVMModuleCreateWithNameInContext(c_string.as_ptr(), LLVMGetModuleContext(self.module.get()));
+
+            LLVMSetTarget(stand_in, LLVMGetTarget(self.module.get()));
+            LLVMSetDataLayout(stand_in, self.get_raw_data_layout());
+
+            stand_in
         };
 
-        if code == 1 {
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
+        let mut execution_engine = ptr::null_mut();
+        let mut err_str = ptr::null_mut();
 
-                LLVMDisposeMessage(err_str);
+        if create(&mut execution_engine, stand_in, &mut err_str) == 1 {
+            return Err(LLVMString::new(err_str).to_string());
+        }
 
-                rust_str
-            };
+        let mut removed_module = ptr::null_mut();
+        let mut err_str = ptr::null_mut();
 
-            return Err(rust_str);
+        unsafe {
+            LLVMAddModule(execution_engine, self.module.get());
+
+            let code = LLVMRemoveModule(execution_engine, stand_in, &mut removed_module, &mut err_str);
+
+            debug_assert_eq!(code, 0, "Failed to remove the stand-in module: {}", LLVMString::new(err_str));
+
+            LLVMDisposeModule(removed_module);
         }
 
-        let execution_engine = ExecutionEngine::new(Rc::new(execution_engine), true);
+        // Hands ownership of this module over to the newly created ExecutionEngine
+        let execution_engine = ExecutionEngine::new(execution_engine, self.module.get(), jit_mode);
 
-        *self.owned_by_ee.borrow_mut() = Some(ExecutionEngine::new(execution_engine.execution_engine.clone(), true));
+        *self.owned_by_ee.borrow_mut() = Some(execution_engine.share());
 
         Ok(execution_engine)
     }
 
-    pub fn add_global(&self, type_: &BasicType, address_space: Option<AddressSpace>, name: &str) -> GlobalValue {
+    pub fn add_global<'m>(&'m self, type_: &BasicType<'ctx>, address_space: Option<AddressSpace>, name: &str) -> GlobalValue<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let value = unsafe {
@@ -404,66 +707,319 @@ impl Module {
         MemoryBuffer::new(memory_buffer)
     }
 
-    /// Ensures that the current `Module` is valid, and returns a `bool`
-    /// that describes whether or not it is.
+    /// Parses bitcode from a `MemoryBuffer` into a new `Module` in the given `Context`.
+    ///
+    /// # Example
+    /// ```
+    /// use inkwell::context::Context;
+    /// use inkwell::module::Module;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_module");
+    /// let fn_type = context.void_type().fn_type(&[], false);
     ///
-    /// * `print` - Whether or not a message describing the error should be printed
-    ///   to stderr, in case of failure.
+    /// module.add_function("my_fn", &fn_type, None);
+    ///
+    /// let buffer = module.write_bitcode_to_memory();
+    /// let module2 = Module::parse_bitcode_from_buffer(&buffer, &context).unwrap();
+    ///
+    /// assert!(module2.get_function("my_fn").is_some());
+    /// ```
+    // REVIEW: The *2 variants of the bitcode readers report errors through the
+    // context's diagnostic handler (which exits by default) rather than a message,
+    // so we stick with the original functions for now
+    #[allow(deprecated)]
+    pub fn parse_bitcode_from_buffer(buffer: &MemoryBuffer, context: &'ctx Context) -> Result<Self, String> {
+        use llvm_sys::bit_reader::LLVMParseBitcodeInContext;
+
+        let mut module = ptr::null_mut();
+        let mut err_str = ptr::null_mut();
+
+        let code = unsafe {
+            LLVMParseBitcodeInContext(*context.context, buffer.memory_buffer, &mut module, &mut err_str)
+        };
+
+        if code == 1 {
+            return Err(Module::take_error_message(err_str));
+        }
+
+        Ok(Module::new(module, Some(context)))
+    }
+
+    /// Reads the bitcode file at `path` into a new `Module` in the given `Context`.
+    pub fn parse_bitcode_from_path(path: &Path, context: &'ctx Context) -> Result<Self, String> {
+        let buffer = MemoryBuffer::create_from_file(path)?;
+
+        Module::parse_bitcode_from_buffer(&buffer, context)
+    }
+
+    /// Lazily parses bitcode from a `MemoryBuffer`, which the resulting `Module` takes
+    /// ownership of. Only the module's globals and function signatures are read up front;
+    /// function bodies are read from the buffer when the module is materialized, ie when
+    /// it is compiled by an `ExecutionEngine`. Until then, lazily loaded functions have
+    /// no basic blocks.
+    #[allow(deprecated)]
+    pub fn parse_bitcode_lazily_from_buffer(buffer: MemoryBuffer, context: &'ctx Context) -> Result<Self, String> {
+        use llvm_sys::bit_reader::LLVMGetBitcodeModuleInContext;
+
+        let mut module = ptr::null_mut();
+        let mut err_str = ptr::null_mut();
+
+        let code = unsafe {
+            LLVMGetBitcodeModuleInContext(*context.context, buffer.memory_buffer, &mut module, &mut err_str)
+        };
+
+        // The buffer is only owned by the module if it was successfully created
+        if code == 1 {
+            return Err(Module::take_error_message(err_str));
+        }
+
+        forget(buffer);
+
+        Ok(Module::new(module, Some(context)))
+    }
+
+    /// Lazily reads the bitcode file at `path`. See `Module::parse_bitcode_lazily_from_buffer`.
+    pub fn parse_bitcode_lazily_from_path(path: &Path, context: &'ctx Context) -> Result<Self, String> {
+        let buffer = MemoryBuffer::create_from_file(path)?;
+
+        Module::parse_bitcode_lazily_from_buffer(buffer, context)
+    }
+
+    fn take_error_message(err_str: *mut i8) -> String {
+        if err_str.is_null() {
+            return String::from("Failed to parse bitcode");
+        }
+
+        LLVMString::new(err_str).to_string()
+    }
+
+    /// Ensures that the current `Module` is valid, returning a `VerificationError`
+    /// describing every problem the verifier found if it is not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("my_module");
+    /// let fn_type = context.void_type().fn_type(&[], false);
+    /// let function = module.add_function("my_fn", &fn_type, None);
+    ///
+    /// assert!(module.verify().is_ok());
+    ///
+    /// // A basic block without a terminator is invalid
+    /// function.append_basic_block("entry");
+    ///
+    /// assert!(module.verify().unwrap_err().get_message().contains("does not have terminator"));
+    /// ```
     ///
     /// # Remarks
     /// See also: http://llvm.org/doxygen/Analysis_2Analysis_8cpp_source.html
-    pub fn verify(&self, print: bool) -> bool {
-        // REVIEW <6A>: Maybe, instead of printing the module to stderr with `print`,
-        // we should return Result<(), String> that contains an error string?
-        let mut err_str = unsafe { zeroed() };
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        verify_module(self.module.get())
+    }
+
+    /// Links `other` into this `Module`, consuming it. Strong definitions of
+    /// the same symbol in both modules are reported as a `SymbolConflict`
+    /// before anything is linked; declarations are resolved against the
+    /// definitions in the other module.
+    ///
+    /// # Example
+    /// ```
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("main");
+    /// let other = context.create_module("other");
+    /// let fn_type = context.void_type().fn_type(&[], false);
+    /// let function = other.add_function("my_fn", &fn_type, None);
+    ///
+    /// context.append_basic_block(&function, "entry");
+    ///
+    /// assert!(module.link_in_module(other).is_ok());
+    /// assert!(module.get_function("my_fn").is_some());
+    /// ```
+    ///
+    /// Values taken from `other` can't be used once it has been linked in; fetch them
+    /// from this `Module` instead:
+    ///
+    /// ```compile_fail
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("main");
+    /// let other = context.create_module("other");
+    /// let fn_type = context.void_type().fn_type(&[], false);
+    /// let function = other.add_function("my_fn", &fn_type, None);
+    ///
+    /// module.link_in_module(other).unwrap();
+    ///
+    /// function.get_name();
+    /// ```
+    pub fn link_in_module(&self, other: Module<'ctx>) -> Result<(), LinkingError> {
+        if other.owned_by_ee.borrow().is_some() {
+            return Err(LinkingError::OwnedByExecutionEngine);
+        }
+
+        let (context, other_context) = unsafe {
+            (LLVMGetModuleContext(self.module.get()), LLVMGetModuleContext(other.module.get()))
+        };
+
+        if context != other_context {
+            return Err(LinkingError::ContextMismatch);
+        }
+
+        if let Some(name) = self.find_conflicting_symbol(&other) {
+            return Err(LinkingError::SymbolConflict(name));
+        }
+
+        // The linker takes over `other` whether or not linking succeeds, so it must not
+        // dispose of it when dropped. Its values can't outlive it as it has been moved
+        let other_module = other.module.replace(ptr::null_mut());
+
+        self.link_modules(other_module)
+    }
+
+    #[cfg(feature = "llvm3-7")]
+    fn link_modules(&self, other: LLVMModuleRef) -> Result<(), LinkingError> {
+        use llvm_sys::linker::{LLVMLinkModules, LLVMLinkerMode};
+
+        let mut err_str = ptr::null_mut();
+        let code = unsafe {
+            LLVMLinkModules(self.module.get(), other, LLVMLinkerMode::LLVMLinkerDestroySource, &mut err_str)
+        };
+
+        let result = if code == 1 {
+            let rust_str = if err_str.is_null() {
+                String::from("Failed to link modules")
+            } else {
+                LLVMString::new(err_str).to_string()
+            };
 
-        let action = if print {
-            LLVMVerifierFailureAction::LLVMPrintMessageAction
+            Err(LinkingError::LinkFailed(rust_str))
         } else {
-            LLVMVerifierFailureAction::LLVMReturnStatusAction
+            Ok(())
         };
 
+        // Unlike LLVMLinkModules2, this leaves the (now gutted) source module to be disposed of
+        unsafe {
+            LLVMDisposeModule(other);
+        }
+
+        resume_diagnostic_handler_panic(unsafe { LLVMGetModuleContext(self.module.get()) });
+
+        result
+    }
+
+    #[cfg(any(feature = "llvm3-8", feature = "llvm3-9", feature = "llvm4-0"))]
+    fn link_modules(&self, other: LLVMModuleRef) -> Result<(), LinkingError> {
+        use llvm_sys::linker::LLVMLinkModules2;
+
         let code = unsafe {
-            LLVMVerifyModule(self.module.get(), action, &mut err_str)
+            LLVMLinkModules2(self.module.get(), other)
         };
 
-        if code == 1 && !err_str.is_null() {
-            unsafe {
-                if print {
-                    let rust_str = CStr::from_ptr(err_str).to_str().unwrap();
+        resume_diagnostic_handler_panic(unsafe { LLVMGetModuleContext(self.module.get()) });
+
+        if code == 1 {
+            return Err(LinkingError::LinkFailed(String::from("Failed to link modules")));
+        }
+
+        Ok(())
+    }
+
+    // REVIEW: The linker itself reports these through the context's diagnostic
+    // handler, which by default aborts the process, hence checking up front
+    fn find_conflicting_symbol(&self, other: &Module<'ctx>) -> Option<String> {
+        fn is_strong_definition(value: LLVMValueRef) -> bool {
+            let linkage = unsafe {
+                if LLVMIsDeclaration(value) == 1 {
+                    return false;
+                }
+
+                Linkage::new(LLVMGetLinkage(value))
+            };
+
+            linkage == Linkage::ExternalLinkage || linkage == Linkage::DLLExportLinkage
+        }
+
+        let mut symbols = Vec::new();
+
+        unsafe {
+            let mut function = LLVMGetFirstFunction(other.module.get());
+
+            while !function.is_null() {
+                symbols.push(function);
+                function = LLVMGetNextFunction(function);
+            }
+
+            let mut global = LLVMGetFirstGlobal(other.module.get());
+
+            while !global.is_null() {
+                symbols.push(global);
+                global = LLVMGetNextGlobal(global);
+            }
+        }
+
+        for symbol in symbols {
+            if !is_strong_definition(symbol) {
+                continue;
+            }
+
+            let name = unsafe {
+                CStr::from_ptr(LLVMGetValueName(symbol))
+            };
 
-                    eprintln!("{}", rust_str);
+            let existing = unsafe {
+                let function = LLVMGetNamedFunction(self.module.get(), name.as_ptr());
+
+                if function.is_null() {
+                    LLVMGetNamedGlobal(self.module.get(), name.as_ptr())
+                } else {
+                    function
                 }
+            };
 
-                LLVMDisposeMessage(err_str);
+            if !existing.is_null() && is_strong_definition(existing) {
+                return Some(name.to_string_lossy().into_owned());
             }
         }
 
-        code == 0
+        None
+    }
+
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    fn get_raw_data_layout(&self) -> *const c_char {
+        use llvm_sys::core::LLVMGetDataLayout;
+
+        unsafe {
+            LLVMGetDataLayout(self.module.get())
+        }
     }
 
-    // REVIEW: LLVMGetDataLayoutStr was added in 3.9+ and might be more correct according to llvm-sys
-    fn get_raw_data_layout(&self) -> *mut i8 {
+    // LLVMGetDataLayout was deprecated in 3.9 in favor of the more aptly named LLVMGetDataLayoutStr
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    fn get_raw_data_layout(&self) -> *const c_char {
+        use llvm_sys::core::LLVMGetDataLayoutStr;
+
         unsafe {
-            LLVMGetDataLayout(self.module.get()) as *mut _
+            LLVMGetDataLayoutStr(self.module.get())
         }
     }
 
-    pub fn get_data_layout(&self) -> &DataLayout {
-        self.data_layout.as_ref().expect("DataLayout should always exist until Drop")
+    /// Gets a copy of this `Module`'s data layout, since the `Module` frees its own
+    /// string whenever the data layout is replaced.
+    pub fn get_data_layout(&self) -> DataLayout {
+        DataLayout::new(LLVMString::create(self.get_raw_data_layout()))
     }
 
-    // REVIEW: Ensure the replaced string ptr still gets cleaned up by the module (I think it does)
-    // valgrind might come in handy once non jemalloc allocators stabilize
+    // LLVM copies the string, so data_layout may be dropped afterwards
     pub fn set_data_layout(&self, data_layout: &DataLayout) {
         unsafe {
-            LLVMSetDataLayout(self.module.get(), data_layout.data_layout.get());
+            LLVMSetDataLayout(self.module.get(), data_layout.as_str().as_ptr());
         }
-
-        self.data_layout.as_ref()
-                        .expect("DataLayout should always exist until Drop")
-                        .data_layout
-                        .set(self.get_raw_data_layout());
     }
 
     /// Prints the content of the `Module` to stderr.
@@ -474,10 +1030,12 @@ impl Module {
     }
 
     /// Prints the content of the `Module` to a string.
-    pub fn print_to_string(&self) -> &CStr {
-        unsafe {
-            CStr::from_ptr(LLVMPrintModuleToString(self.module.get()))
-        }
+    pub fn print_to_string(&self) -> LLVMString {
+        let module_string = unsafe {
+            LLVMPrintModuleToString(self.module.get())
+        };
+
+        LLVMString::new(module_string)
     }
 
     /// Prints the content of the `Module` to a file.
@@ -490,13 +1048,7 @@ impl Module {
 
         // TODO: Verify 1 is error code (LLVM can be inconsistent)
         if return_code == 1 {
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-                LLVMDisposeMessage(err_str);
-
-                rust_str
-            };
+            let rust_str = LLVMString::new(err_str).to_string();
 
             return Err(rust_str);
         }
@@ -516,7 +1068,7 @@ impl Module {
     // REVIEW: Should we return a MetadataValue for the global since it's its own value?
     // it would be the last item in get_global_metadata I believe
     // TODOC: Appends your metadata to a global MetadataValue<Node> indexed by key
-    pub fn add_global_metadata(&self, key: &str, metadata: &MetadataValue) {
+    pub fn add_global_metadata<'m>(&self, key: &str, metadata: &MetadataValue<'ctx, 'm>) {
         let c_string = CString::new(key).expect("Conversion to CString failed unexpectedly");
 
         unsafe {
@@ -535,7 +1087,7 @@ impl Module {
 
     // TODOC: Always returns a metadata node indexed by key, which may contain 1 string or multiple values as its get_node_values()
     // SubTypes: -> Vec<MetadataValue<Node>>
-    pub fn get_global_metadata(&self, key: &str) -> Vec<MetadataValue> {
+    pub fn get_global_metadata<'m>(&'m self, key: &str) -> Vec<MetadataValue<'ctx, 'm>> {
         let c_string = CString::new(key).expect("Conversion to CString failed unexpectedly");
         let count = self.get_global_metadata_size(key);
 
@@ -553,7 +1105,7 @@ impl Module {
         slice.iter().map(|val| MetadataValue::new(*val)).collect()
     }
 
-    pub fn get_first_global(&self) -> Option<GlobalValue> {
+    pub fn get_first_global<'m>(&'m self) -> Option<GlobalValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetFirstGlobal(self.module.get())
         };
@@ -565,7 +1117,7 @@ impl Module {
         Some(GlobalValue::new(value))
     }
 
-    pub fn get_last_global(&self) -> Option<GlobalValue> {
+    pub fn get_last_global<'m>(&'m self) -> Option<GlobalValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetLastGlobal(self.module.get())
         };
@@ -577,7 +1129,19 @@ impl Module {
         Some(GlobalValue::new(value))
     }
 
-    pub fn get_global(&self, name: &str) -> Option<GlobalValue> {
+    pub fn globals<'m>(&'m self) -> GlobalIter<'m, 'ctx> {
+        let (first, last) = unsafe {
+            (LLVMGetFirstGlobal(self.module.get()), LLVMGetLastGlobal(self.module.get()))
+        };
+
+        GlobalIter {
+            front: first,
+            back: last,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn get_global<'m>(&'m self, name: &str) -> Option<GlobalValue<'ctx, 'm>> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
         let value = unsafe {
             LLVMGetNamedGlobal(self.module.get(), c_string.as_ptr())
@@ -591,7 +1155,33 @@ impl Module {
     }
 }
 
-impl Clone for Module {
+impl Module<'static> {
+    /// Creates a named `Module`. Will be automatically assigned the global context.
+    ///
+    /// To use your own `Context`, see [inkwell::context::create_module()](../context/struct.Context.html#method.create_module)
+    ///
+    /// # Example
+    /// ```
+    /// use inkwell::context::Context;
+    /// use inkwell::module::Module;
+    ///
+    /// let context = Context::get_global();
+    /// let module = Module::create("my_module");
+    ///
+    /// assert_eq!(module.get_context(), context);
+    /// ```
+    pub fn create(name: &str) -> Self {
+        let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
+
+        let module = unsafe {
+            LLVMModuleCreateWithName(c_string.as_ptr())
+        };
+
+        Module::new(module, None)
+    }
+}
+
+impl<'ctx> Clone for Module<'ctx> {
     fn clone(&self) -> Self {
         let module = unsafe {
             LLVMCloneModule(self.module.get())
@@ -601,18 +1191,118 @@ impl Clone for Module {
     }
 }
 
-// Module owns the data layout string, so LLVMDisposeModule will deallocate it for us,
-// so we must call forget to avoid dropping it ourselves
-impl Drop for Module {
+impl<'ctx> Drop for Module<'ctx> {
     fn drop(&mut self) {
-        forget(self.data_layout.take().expect("DataLayout should always exist until Drop"));
-
-        if self.owned_by_ee.borrow_mut().take().is_none() {
+        // A module owned by an EE is disposed along with it, once every module it owns has been dropped.
+        // A null module has been handed over to the linker, which disposes of it
+        if self.owned_by_ee.borrow_mut().take().is_none() && !self.module.get().is_null() {
             unsafe {
                 LLVMDisposeModule(self.module.get());
             }
         }
 
-        // Context & EE will drop naturally if they are unique references at this point
+        // Context will drop naturally if it is a unique reference at this point
+    }
+}
+
+// The next position is looked up before an item is yielded, so it is fine
+// to delete the current function or global while iterating.
+pub struct FunctionIter<'m, 'ctx: 'm> {
+    front: LLVMValueRef,
+    back: LLVMValueRef,
+    _marker: PhantomData<&'m Module<'ctx>>,
+}
+
+impl<'m, 'ctx> Iterator for FunctionIter<'m, 'ctx> {
+    type Item = FunctionValue<'ctx, 'm>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front.is_null() {
+            return None;
+        }
+
+        let function = self.front;
+
+        if function == self.back {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.front = unsafe {
+                LLVMGetNextFunction(function)
+            };
+        }
+
+        FunctionValue::new(function)
+    }
+}
+
+impl<'m, 'ctx> DoubleEndedIterator for FunctionIter<'m, 'ctx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.back.is_null() {
+            return None;
+        }
+
+        let function = self.back;
+
+        if function == self.front {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.back = unsafe {
+                LLVMGetPreviousFunction(function)
+            };
+        }
+
+        FunctionValue::new(function)
+    }
+}
+
+pub struct GlobalIter<'m, 'ctx: 'm> {
+    front: LLVMValueRef,
+    back: LLVMValueRef,
+    _marker: PhantomData<&'m Module<'ctx>>,
+}
+
+impl<'m, 'ctx> Iterator for GlobalIter<'m, 'ctx> {
+    type Item = GlobalValue<'ctx, 'm>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front.is_null() {
+            return None;
+        }
+
+        let global = self.front;
+
+        if global == self.back {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.front = unsafe {
+                LLVMGetNextGlobal(global)
+            };
+        }
+
+        Some(GlobalValue::new(global))
+    }
+}
+
+impl<'m, 'ctx> DoubleEndedIterator for GlobalIter<'m, 'ctx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.back.is_null() {
+            return None;
+        }
+
+        let global = self.back;
+
+        if global == self.front {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.back = unsafe {
+                LLVMGetPreviousGlobal(global)
+            };
+        }
+
+        Some(GlobalValue::new(global))
     }
 }
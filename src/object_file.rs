@@ -1,5 +1,7 @@
 use llvm_sys::object::{LLVMDisposeObjectFile, LLVMObjectFileRef, LLVMSectionIteratorRef, LLVMGetSections, LLVMDisposeSectionIterator, LLVMSymbolIteratorRef, LLVMIsSectionIteratorAtEnd, LLVMGetSectionName, LLVMDisposeRelocationIterator, LLVMRelocationIteratorRef, LLVMDisposeSymbolIterator, LLVMGetSectionContents, LLVMGetSectionSize, LLVMMoveToNextSection, LLVMGetSectionAddress, LLVMGetSymbolName, LLVMGetSymbolSize, LLVMGetRelocations, LLVMGetSymbolAddress, LLVMGetRelocationOffset, LLVMGetRelocationSymbol, LLVMGetRelocationType, LLVMGetRelocationTypeName, LLVMGetRelocationValueString, LLVMMoveToNextSymbol, LLVMMoveToNextRelocation, LLVMIsSymbolIteratorAtEnd, LLVMIsRelocationIteratorAtEnd, LLVMGetSymbols};
 
+use support::LLVMString;
+
 use std::ffi::CStr;
 
 // REVIEW: Make sure SectionIterator's object_file ptr doesn't outlive ObjectFile
@@ -218,20 +220,22 @@ impl Relocation {
         SymbolIterator::new(symbol_iterator, self.object_file)
     }
 
-    pub fn get_type(&self) -> (u64, &CStr) {
+    // LLVM allocates a new string for each call to the name and value getters,
+    // which is ours to free
+    pub fn get_type(&self) -> (u64, LLVMString) {
         let type_int = unsafe {
             LLVMGetRelocationType(self.relocation)
         };
         let type_name = unsafe {
-            CStr::from_ptr(LLVMGetRelocationTypeName(self.relocation))
+            LLVMString::new(LLVMGetRelocationTypeName(self.relocation) as *mut _)
         };
 
         (type_int, type_name)
     }
 
-    pub fn get_value(&self) -> &CStr {
+    pub fn get_value(&self) -> LLVMString {
         unsafe {
-            CStr::from_ptr(LLVMGetRelocationValueString(self.relocation))
+            LLVMString::new(LLVMGetRelocationValueString(self.relocation) as *mut _)
         }
     }
 }
@@ -1,6 +1,7 @@
-use llvm_sys::core::{LLVMDisposePassManager, LLVMInitializeFunctionPassManager, LLVMFinalizeFunctionPassManager, LLVMRunFunctionPassManager, LLVMRunPassManager, LLVMCreatePassManager, LLVMCreateFunctionPassManagerForModule, LLVMGetGlobalPassRegistry};
+use llvm_sys::core::{LLVMDisposePassManager, LLVMInitializeFunctionPassManager, LLVMFinalizeFunctionPassManager, LLVMRunFunctionPassManager, LLVMRunPassManager, LLVMCreatePassManager, LLVMCreateFunctionPassManagerForModule, LLVMGetGlobalPassRegistry, LLVMGetModuleContext, LLVMGetTypeContext, LLVMTypeOf};
 use llvm_sys::initialization::{LLVMInitializeCore, LLVMInitializeTransformUtils, LLVMInitializeScalarOpts, LLVMInitializeObjCARCOpts, LLVMInitializeVectorization, LLVMInitializeInstCombine, LLVMInitializeIPO, LLVMInitializeInstrumentation, LLVMInitializeAnalysis, LLVMInitializeIPA, LLVMInitializeCodeGen, LLVMInitializeTarget};
-use llvm_sys::prelude::{LLVMPassManagerRef, LLVMPassRegistryRef};
+use llvm_sys::prelude::{LLVMContextRef, LLVMPassManagerRef, LLVMPassRegistryRef};
+#[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
 use llvm_sys::target::LLVMAddTargetData;
 use llvm_sys::transforms::ipo::{LLVMAddArgumentPromotionPass, LLVMAddConstantMergePass, LLVMAddDeadArgEliminationPass, LLVMAddFunctionAttrsPass, LLVMAddFunctionInliningPass, LLVMAddAlwaysInlinerPass, LLVMAddGlobalDCEPass, LLVMAddGlobalOptimizerPass, LLVMAddIPConstantPropagationPass, LLVMAddIPSCCPPass, LLVMAddInternalizePass, LLVMAddStripDeadPrototypesPass, LLVMAddPruneEHPass, LLVMAddStripSymbolsPass};
 use llvm_sys::transforms::pass_manager_builder::{LLVMPassManagerBuilderRef, LLVMPassManagerBuilderCreate, LLVMPassManagerBuilderDispose, LLVMPassManagerBuilderSetOptLevel, LLVMPassManagerBuilderSetSizeLevel, LLVMPassManagerBuilderSetDisableUnitAtATime, LLVMPassManagerBuilderSetDisableUnrollLoops, LLVMPassManagerBuilderSetDisableSimplifyLibCalls, LLVMPassManagerBuilderUseInlinerWithThreshold, LLVMPassManagerBuilderPopulateFunctionPassManager, LLVMPassManagerBuilderPopulateModulePassManager, LLVMPassManagerBuilderPopulateLTOPassManager};
@@ -8,7 +9,9 @@ use llvm_sys::transforms::scalar::{LLVMAddAggressiveDCEPass, LLVMAddMemCpyOptPas
 use llvm_sys::transforms::vectorize::{LLVMAddBBVectorizePass, LLVMAddLoopVectorizePass, LLVMAddSLPVectorizePass};
 
 use OptimizationLevel;
+use context::resume_diagnostic_handler_panic;
 use module::Module;
+#[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
 use targets::TargetData;
 use values::{AsValueRef, FunctionValue};
 
@@ -105,14 +108,17 @@ impl Drop for PassManagerBuilder {
 // SubTypes: PassManager<Module>, PassManager<FunctionValue>
 pub struct PassManager {
     pub(crate) pass_manager: LLVMPassManagerRef,
+    // The context of the module a function pass manager was created for
+    context: Option<LLVMContextRef>,
 }
 
 impl PassManager {
-    pub(crate) fn new(pass_manager: LLVMPassManagerRef) -> PassManager {
+    pub(crate) fn new(pass_manager: LLVMPassManagerRef, context: Option<LLVMContextRef>) -> PassManager {
         assert!(!pass_manager.is_null());
 
         PassManager {
-            pass_manager: pass_manager
+            pass_manager: pass_manager,
+            context,
         }
     }
 
@@ -122,43 +128,65 @@ impl PassManager {
             LLVMCreatePassManager()
         };
 
-        PassManager::new(pass_manager)
+        PassManager::new(pass_manager, None)
     }
 
     // SubTypes: PassManager<FunctionValue>::create()
     pub fn create_for_function(module: &Module) -> Self {
-        let pass_manager = unsafe {
-            LLVMCreateFunctionPassManagerForModule(module.module.get())
+        let (pass_manager, context) = unsafe {
+            (LLVMCreateFunctionPassManagerForModule(module.module.get()), LLVMGetModuleContext(module.module.get()))
         };
 
-        PassManager::new(pass_manager)
+        PassManager::new(pass_manager, Some(context))
     }
 
     // return true means some pass modified the module, not an error occurred
     pub fn initialize(&self) -> bool {
-        unsafe {
+        let modified = unsafe {
             LLVMInitializeFunctionPassManager(self.pass_manager) == 1
+        };
+
+        if let Some(context) = self.context {
+            resume_diagnostic_handler_panic(context);
         }
+
+        modified
     }
 
     pub fn finalize(&self) -> bool {
-        unsafe {
+        let modified = unsafe {
             LLVMFinalizeFunctionPassManager(self.pass_manager) == 1
+        };
+
+        if let Some(context) = self.context {
+            resume_diagnostic_handler_panic(context);
         }
+
+        modified
     }
 
     pub fn run_on_function(&self, fn_value: &FunctionValue) -> bool {
-        unsafe {
+        let modified = unsafe {
             LLVMRunFunctionPassManager(self.pass_manager, fn_value.as_value_ref()) == 1
-        }
+        };
+
+        resume_diagnostic_handler_panic(unsafe { LLVMGetTypeContext(LLVMTypeOf(fn_value.as_value_ref())) });
+
+        modified
     }
 
     pub fn run_on_module(&self, module: &Module) -> bool {
-        unsafe {
+        let modified = unsafe {
             LLVMRunPassManager(self.pass_manager, module.module.get()) == 1
-        }
+        };
+
+        resume_diagnostic_handler_panic(unsafe { LLVMGetModuleContext(module.module.get()) });
+
+        modified
     }
 
+    // Removed in LLVM 3.9 in favor of module level data layouts
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
     pub fn add_target_data(&self, target_data: &TargetData) {
         unsafe {
             LLVMAddTargetData(target_data.target_data, self.pass_manager)
@@ -317,12 +345,14 @@ impl PassManager {
         }
     }
 
-    // TODO: LLVM 4.0+
-    // pub fn add_new_gvn_pass(&self) {
-    //     unsafe {
-    //         LLVMAddNewGVNPass(self.pass_manager)
-    //     }
-    // }
+    #[cfg(feature = "llvm4-0")]
+    pub fn add_new_gvn_pass(&self) {
+        use llvm_sys::transforms::scalar::LLVMAddNewGVNPass;
+
+        unsafe {
+            LLVMAddNewGVNPass(self.pass_manager)
+        }
+    }
 
     pub fn add_ind_var_simplify_pass(&self) {
         unsafe {
@@ -480,12 +510,14 @@ impl PassManager {
         }
     }
 
-    // TODO: LLVM 4.0+
-    // pub fn add_early_cse_mem_ssa_pass(&self) {
-    //     unsafe {
-    //         LLVMAddEarlyCSEMemSSAPass(self.pass_manager)
-    //     }
-    // }
+    #[cfg(feature = "llvm4-0")]
+    pub fn add_early_cse_mem_ssa_pass(&self) {
+        use llvm_sys::transforms::scalar::LLVMAddEarlyCSEMemSSAPass;
+
+        unsafe {
+            LLVMAddEarlyCSEMemSSAPass(self.pass_manager)
+        }
+    }
 
     pub fn add_lower_expect_intrinsic_pass(&self) {
         unsafe {
@@ -0,0 +1,68 @@
+use llvm_sys::core::{LLVMCreateMessage, LLVMDisposeMessage};
+
+use std::ffi::CStr;
+use std::fmt;
+use std::ops::Deref;
+use std::os::raw::c_char;
+
+/// An owned string allocated by LLVM, such as printed IR or a target triple,
+/// which is freed with `LLVMDisposeMessage` when dropped.
+#[derive(Eq)]
+pub struct LLVMString {
+    ptr: *mut c_char,
+}
+
+impl LLVMString {
+    pub(crate) fn new(ptr: *mut c_char) -> Self {
+        assert!(!ptr.is_null());
+
+        LLVMString { ptr }
+    }
+
+    // Copies a string LLVM still owns, so that it may outlive its owner
+    pub(crate) fn create(ptr: *const c_char) -> Self {
+        assert!(!ptr.is_null());
+
+        let ptr = unsafe {
+            LLVMCreateMessage(ptr)
+        };
+
+        LLVMString::new(ptr)
+    }
+}
+
+impl Deref for LLVMString {
+    type Target = CStr;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            CStr::from_ptr(self.ptr)
+        }
+    }
+}
+
+impl PartialEq for LLVMString {
+    fn eq(&self, other: &LLVMString) -> bool {
+        **self == **other
+    }
+}
+
+impl fmt::Debug for LLVMString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.deref())
+    }
+}
+
+impl fmt::Display for LLVMString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_lossy())
+    }
+}
+
+impl Drop for LLVMString {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMDisposeMessage(self.ptr);
+        }
+    }
+}
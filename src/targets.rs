@@ -1,13 +1,14 @@
-use llvm_sys::core::LLVMDisposeMessage;
-use llvm_sys::target::{LLVMTargetDataRef, LLVMCopyStringRepOfTargetData, LLVMSizeOfTypeInBits, LLVMCreateTargetData, LLVMAddTargetData, LLVMByteOrder, LLVMPointerSize, LLVMByteOrdering, LLVMStoreSizeOfType, LLVMABISizeOfType, LLVMABIAlignmentOfType, LLVMCallFrameAlignmentOfType, LLVMPreferredAlignmentOfType, LLVMPreferredAlignmentOfGlobal, LLVMElementAtOffset, LLVMOffsetOfElement, LLVMDisposeTargetData, LLVMPointerSizeForAS, LLVMIntPtrType, LLVMIntPtrTypeForAS, LLVMIntPtrTypeInContext, LLVMIntPtrTypeForASInContext};
+use llvm_sys::core::LLVMGetModuleContext;
+use llvm_sys::target::{LLVMTargetDataRef, LLVMCopyStringRepOfTargetData, LLVMSizeOfTypeInBits, LLVMCreateTargetData, LLVMByteOrder, LLVMPointerSize, LLVMByteOrdering, LLVMStoreSizeOfType, LLVMABISizeOfType, LLVMABIAlignmentOfType, LLVMCallFrameAlignmentOfType, LLVMPreferredAlignmentOfType, LLVMPreferredAlignmentOfGlobal, LLVMElementAtOffset, LLVMOffsetOfElement, LLVMDisposeTargetData, LLVMPointerSizeForAS, LLVMIntPtrType, LLVMIntPtrTypeForAS, LLVMIntPtrTypeInContext, LLVMIntPtrTypeForASInContext};
 use llvm_sys::target_machine::{LLVMGetFirstTarget, LLVMTargetRef, LLVMGetNextTarget, LLVMGetTargetFromName, LLVMGetTargetFromTriple, LLVMGetTargetName, LLVMGetTargetDescription, LLVMTargetHasJIT, LLVMTargetHasTargetMachine, LLVMTargetHasAsmBackend, LLVMTargetMachineRef, LLVMDisposeTargetMachine, LLVMGetTargetMachineTarget, LLVMGetTargetMachineTriple, LLVMSetTargetMachineAsmVerbosity, LLVMCreateTargetMachine, LLVMGetTargetMachineCPU, LLVMGetTargetMachineFeatureString, LLVMGetDefaultTargetTriple, LLVMAddAnalysisPasses, LLVMCodeGenOptLevel, LLVMCodeModel, LLVMRelocMode, LLVMCodeGenFileType, LLVMTargetMachineEmitToMemoryBuffer, LLVMTargetMachineEmitToFile};
 
 use OptimizationLevel;
-use context::Context;
+use context::{Context, resume_diagnostic_handler_panic};
 use data_layout::DataLayout;
 use memory_buffer::MemoryBuffer;
 use module::Module;
 use passes::PassManager;
+use support::LLVMString;
 use types::{AnyType, AsTypeRef, StructType, PointerType};
 use values::{AsValueRef, GlobalValue};
 
@@ -27,6 +28,19 @@ pub enum CodeModel {
     Large,
 }
 
+impl CodeModel {
+    pub(crate) fn as_llvm_code_model(&self) -> LLVMCodeModel {
+        match *self {
+            CodeModel::Default => LLVMCodeModel::LLVMCodeModelDefault,
+            CodeModel::JITDefault => LLVMCodeModel::LLVMCodeModelJITDefault,
+            CodeModel::Small => LLVMCodeModel::LLVMCodeModelSmall,
+            CodeModel::Kernel => LLVMCodeModel::LLVMCodeModelKernel,
+            CodeModel::Medium => LLVMCodeModel::LLVMCodeModelMedium,
+            CodeModel::Large => LLVMCodeModel::LLVMCodeModelLarge,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum RelocMode {
     Default,
@@ -314,6 +328,8 @@ impl Target {
         }
     }
 
+    // The C++ backend was removed in LLVM 3.9
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
     pub fn initialize_cpp_backend(config: &InitializationConfig) {
         use llvm_sys::target::{LLVMInitializeCppBackendTarget, LLVMInitializeCppBackendTargetInfo, LLVMInitializeCppBackendTargetMC};
 
@@ -457,10 +473,14 @@ impl Target {
                 LLVMInitializeBPFAsmPrinter()
             }
 
-            // TODO: Added in 4.0
-            // if config.disassembler {
-            //     LLVMInitializeBPFDisassembler()
-            // }
+            #[cfg(feature = "llvm4-0")]
+            {
+                use llvm_sys::target::LLVMInitializeBPFDisassembler;
+
+                if config.disassembler {
+                    LLVMInitializeBPFDisassembler()
+                }
+            }
 
             if config.machine_code {
                 LLVMInitializeBPFTargetMC()
@@ -554,14 +574,7 @@ impl Target {
             OptimizationLevel::Default => LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault,
             OptimizationLevel::Aggressive => LLVMCodeGenOptLevel::LLVMCodeGenLevelAggressive,
         };
-        let code_model = match code_model {
-            CodeModel::Default => LLVMCodeModel::LLVMCodeModelDefault,
-            CodeModel::JITDefault => LLVMCodeModel::LLVMCodeModelJITDefault,
-            CodeModel::Small => LLVMCodeModel::LLVMCodeModelSmall,
-            CodeModel::Kernel => LLVMCodeModel::LLVMCodeModelKernel,
-            CodeModel::Medium => LLVMCodeModel::LLVMCodeModelMedium,
-            CodeModel::Large => LLVMCodeModel::LLVMCodeModelLarge,
-        };
+        let code_model = code_model.as_llvm_code_model();
         let reloc_mode = match reloc_mode {
             RelocMode::Default => LLVMRelocMode::LLVMRelocDefault,
             RelocMode::Static => LLVMRelocMode::LLVMRelocStatic,
@@ -635,13 +648,7 @@ impl Target {
         };
 
         if code == 1 { // REVIEW: 1 is error value
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-                LLVMDisposeMessage(err_str);
-
-                rust_str
-            };
+            let rust_str = LLVMString::new(err_str).to_string();
 
             return Err(rust_str);
         }
@@ -690,28 +697,36 @@ impl TargetMachine {
         Target::new(target)
     }
 
-    pub fn get_triple(&self) -> &CStr {
-        unsafe {
-            CStr::from_ptr(LLVMGetTargetMachineTriple(self.target_machine))
-        }
+    pub fn get_triple(&self) -> LLVMString {
+        let llvm_string = unsafe {
+            LLVMGetTargetMachineTriple(self.target_machine)
+        };
+
+        LLVMString::new(llvm_string)
     }
 
-    pub fn get_default_triple() -> &'static CStr { // FIXME: Probably not static?
-        unsafe {
-            CStr::from_ptr(LLVMGetDefaultTargetTriple())
-        }
+    pub fn get_default_triple() -> LLVMString {
+        let llvm_string = unsafe {
+            LLVMGetDefaultTargetTriple()
+        };
+
+        LLVMString::new(llvm_string)
     }
 
-    pub fn get_cpu(&self) -> &CStr {
-        unsafe {
-            CStr::from_ptr(LLVMGetTargetMachineCPU(self.target_machine))
-        }
+    pub fn get_cpu(&self) -> LLVMString {
+        let llvm_string = unsafe {
+            LLVMGetTargetMachineCPU(self.target_machine)
+        };
+
+        LLVMString::new(llvm_string)
     }
 
-    pub fn get_feature_string(&self) -> &CStr {
-        unsafe {
-            CStr::from_ptr(LLVMGetTargetMachineFeatureString(self.target_machine))
-        }
+    pub fn get_feature_string(&self) -> LLVMString {
+        let llvm_string = unsafe {
+            LLVMGetTargetMachineFeatureString(self.target_machine)
+        };
+
+        LLVMString::new(llvm_string)
     }
 
     pub fn set_asm_verbosity(&self, verbosity: bool) {
@@ -735,19 +750,15 @@ impl TargetMachine {
         };
 
         // TODO: Verify 1 is error code (LLVM can be inconsistent)
-        if return_code == 1 {
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-                LLVMDisposeMessage(err_str);
+        let result = if return_code == 1 {
+            Err(LLVMString::new(err_str).to_string())
+        } else {
+            Ok(MemoryBuffer::new(memory_buffer))
+        };
 
-                rust_str
-            };
+        resume_diagnostic_handler_panic(unsafe { LLVMGetModuleContext(module.module.get()) });
 
-            return Err(rust_str);
-        }
-
-        Ok(MemoryBuffer::new(memory_buffer))
+        result
     }
 
     pub fn write_to_file(&self, module: &Module, file_type: FileType, path: &Path) -> Result<(), String> {
@@ -759,19 +770,15 @@ impl TargetMachine {
         };
 
         // TODO: Verify 1 is error code (LLVM can be inconsistent)
-        if return_code == 1 {
-            let rust_str = unsafe {
-                let rust_str = CStr::from_ptr(err_str).to_string_lossy().into_owned();
-
-                LLVMDisposeMessage(err_str);
-
-                rust_str
-            };
+        let result = if return_code == 1 {
+            Err(LLVMString::new(err_str).to_string())
+        } else {
+            Ok(())
+        };
 
-            return Err(rust_str);
-        }
+        resume_diagnostic_handler_panic(unsafe { LLVMGetModuleContext(module.module.get()) });
 
-        Ok(())
+        result
     }
 }
 
@@ -803,7 +810,7 @@ impl TargetData {
         }
     }
 
-    pub fn int_ptr_type(&self) -> PointerType {
+    pub fn int_ptr_type(&self) -> PointerType<'static> {
         let ptr_type = unsafe {
             LLVMIntPtrType(self.target_data)
         };
@@ -811,7 +818,7 @@ impl TargetData {
         PointerType::new(ptr_type)
     }
 
-    pub fn int_ptr_type_for_as(&self, as_: u32) -> PointerType {
+    pub fn int_ptr_type_for_as(&self, as_: u32) -> PointerType<'static> {
         let ptr_type = unsafe {
             LLVMIntPtrTypeForAS(self.target_data, as_)
         };
@@ -819,7 +826,7 @@ impl TargetData {
         PointerType::new(ptr_type)
     }
 
-    pub fn int_ptr_type_in_context(&self, context: &Context) -> PointerType {
+    pub fn int_ptr_type_in_context<'ctx>(&self, context: &'ctx Context) -> PointerType<'ctx> {
         let ptr_type = unsafe {
             LLVMIntPtrTypeInContext(*context.context, self.target_data)
         };
@@ -827,7 +834,7 @@ impl TargetData {
         PointerType::new(ptr_type)
     }
 
-    pub fn int_ptr_type_for_as_in_context(&self, context: &Context, as_: u32) -> PointerType {
+    pub fn int_ptr_type_for_as_in_context<'ctx>(&self, context: &'ctx Context, as_: u32) -> PointerType<'ctx> {
         let ptr_type = unsafe {
             LLVMIntPtrTypeForASInContext(*context.context, self.target_data, as_)
         };
@@ -840,7 +847,7 @@ impl TargetData {
             LLVMCopyStringRepOfTargetData(self.target_data)
         };
 
-        DataLayout::new(data_layout)
+        DataLayout::new(LLVMString::new(data_layout))
     }
 
     // REVIEW: Does this only work if Sized?
@@ -861,7 +868,10 @@ impl TargetData {
     }
 
     // REVIEW: Maybe this should be pass_manager.add_target_data()?
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
     pub fn add_target_data(&self, pass_manager: &PassManager) {
+        use llvm_sys::target::LLVMAddTargetData;
+
         unsafe {
             LLVMAddTargetData(self.target_data, pass_manager.pass_manager)
         }
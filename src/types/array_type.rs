@@ -1,20 +1,19 @@
 use llvm_sys::core::{LLVMConstArray, LLVMConstNull, LLVMGetArrayLength};
 use llvm_sys::prelude::{LLVMTypeRef, LLVMValueRef};
 
-use std::ffi::CStr;
-
 use AddressSpace;
 use context::ContextRef;
+use support::LLVMString;
 use types::traits::AsTypeRef;
 use types::{Type, BasicType, PointerType, FunctionType};
 use values::{BasicValue, ArrayValue, PointerValue, IntValue};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct ArrayType {
-    array_type: Type,
+pub struct ArrayType<'ctx> {
+    array_type: Type<'ctx>,
 }
 
-impl ArrayType {
+impl<'ctx> ArrayType<'ctx> {
     pub(crate) fn new(array_type: LLVMTypeRef) -> Self {
         assert!(!array_type.is_null());
 
@@ -29,7 +28,7 @@ impl ArrayType {
     }
 
     // TODO: impl only for ArrayType<!StructType<Opaque>>
-    pub fn size_of(&self) -> Option<IntValue> {
+    pub fn size_of(&self) -> Option<IntValue<'ctx, 'ctx>> {
         if self.is_sized() {
             return Some(self.array_type.size_of())
         }
@@ -37,23 +36,23 @@ impl ArrayType {
         None
     }
 
-    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType {
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
         self.array_type.ptr_type(address_space)
     }
 
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         self.array_type.get_context()
     }
 
-    pub fn fn_type(&self, param_types: &[&BasicType], is_var_args: bool) -> FunctionType {
+    pub fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
         self.array_type.fn_type(param_types, is_var_args)
     }
 
-    pub fn array_type(&self, size: u32) -> ArrayType {
+    pub fn array_type(&self, size: u32) -> ArrayType<'ctx> {
         self.array_type.array_type(size)
     }
 
-    pub fn const_array<V: BasicValue>(&self, values: &[&V]) -> ArrayValue {
+    pub fn const_array<'m, V: BasicValue<'ctx, 'm>>(&self, values: &[&V]) -> ArrayValue<'ctx, 'm> {
         let mut values: Vec<LLVMValueRef> = values.iter()
                                                   .map(|val| val.as_value_ref())
                                                   .collect();
@@ -64,11 +63,11 @@ impl ArrayType {
         ArrayValue::new(value)
     }
 
-    pub fn const_null_ptr(&self) -> PointerValue {
+    pub fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
         self.array_type.const_null_ptr()
     }
 
-    pub fn const_null(&self) -> ArrayValue {
+    pub fn const_null(&self) -> ArrayValue<'ctx, 'ctx> {
         let null = unsafe {
             LLVMConstNull(self.as_type_ref())
         };
@@ -82,7 +81,7 @@ impl ArrayType {
         }
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.array_type.print_to_string()
     }
 
@@ -90,12 +89,12 @@ impl ArrayType {
         self.array_type.print_to_stderr()
     }
 
-    pub fn get_undef(&self) -> ArrayValue {
+    pub fn get_undef(&self) -> ArrayValue<'ctx, 'ctx> {
         ArrayValue::new(self.array_type.get_undef())
     }
 }
 
-impl AsTypeRef for ArrayType {
+impl<'ctx> AsTypeRef for ArrayType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.array_type.type_
     }
@@ -7,14 +7,14 @@ use types::traits::AsTypeRef;
 
 macro_rules! enum_type_set {
     ($enum_name:ident: $($args:ident),*) => (
-        #[derive(Debug, EnumAsGetters, EnumIntoGetters, EnumIsA, PartialEq, Eq, Clone, Copy)]
-        pub enum $enum_name {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub enum $enum_name<'ctx> {
             $(
-                $args($args),
+                $args($args<'ctx>),
             )*
         }
 
-        impl AsTypeRef for $enum_name {
+        impl<'ctx> AsTypeRef for $enum_name<'ctx> {
             fn as_type_ref(&self) -> LLVMTypeRef {
                 match *self {
                     $(
@@ -25,8 +25,8 @@ macro_rules! enum_type_set {
         }
 
         $(
-            impl From<$args> for $enum_name {
-                fn from(value: $args) -> $enum_name {
+            impl<'ctx> From<$args<'ctx>> for $enum_name<'ctx> {
+                fn from(value: $args<'ctx>) -> $enum_name<'ctx> {
                     $enum_name::$args(value)
                 }
             }
@@ -34,12 +34,65 @@ macro_rules! enum_type_set {
     );
 }
 
+// The is_*, as_* and into_* getters are implemented by hand as derives for
+// them do not support generic (ie lifetime parameterized) enums
+macro_rules! enum_type_getters {
+    ($enum_name:ident: $($args:ident: $is_a:ident, $as_a:ident, $into_a:ident;)*) => (
+        impl<'ctx> $enum_name<'ctx> {
+            $(
+                pub fn $is_a(&self) -> bool {
+                    if let $enum_name::$args(_) = *self {
+                        true
+                    } else {
+                        false
+                    }
+                }
+
+                pub fn $as_a(&self) -> &$args<'ctx> {
+                    if let $enum_name::$args(ref t) = *self {
+                        t
+                    } else {
+                        panic!(concat!("called ", stringify!($as_a), "() on {:?}"), self);
+                    }
+                }
+
+                pub fn $into_a(self) -> $args<'ctx> {
+                    if let $enum_name::$args(t) = self {
+                        t
+                    } else {
+                        panic!(concat!("called ", stringify!($into_a), "() on {:?}"), self);
+                    }
+                }
+            )*
+        }
+    );
+}
+
 enum_type_set! {AnyTypeEnum: IntType, FunctionType, FloatType, PointerType, StructType, ArrayType, VoidType, VectorType}
 enum_type_set! {BasicTypeEnum: IntType, FloatType, PointerType, StructType, ArrayType, VectorType}
 
+enum_type_getters! {AnyTypeEnum:
+    IntType: is_int_type, as_int_type, into_int_type;
+    FunctionType: is_function_type, as_function_type, into_function_type;
+    FloatType: is_float_type, as_float_type, into_float_type;
+    PointerType: is_pointer_type, as_pointer_type, into_pointer_type;
+    StructType: is_struct_type, as_struct_type, into_struct_type;
+    ArrayType: is_array_type, as_array_type, into_array_type;
+    VoidType: is_void_type, as_void_type, into_void_type;
+    VectorType: is_vector_type, as_vector_type, into_vector_type;
+}
+enum_type_getters! {BasicTypeEnum:
+    IntType: is_int_type, as_int_type, into_int_type;
+    FloatType: is_float_type, as_float_type, into_float_type;
+    PointerType: is_pointer_type, as_pointer_type, into_pointer_type;
+    StructType: is_struct_type, as_struct_type, into_struct_type;
+    ArrayType: is_array_type, as_array_type, into_array_type;
+    VectorType: is_vector_type, as_vector_type, into_vector_type;
+}
+
 
-impl AnyTypeEnum {
-    pub(crate) fn new(type_: LLVMTypeRef) -> AnyTypeEnum {
+impl<'ctx> AnyTypeEnum<'ctx> {
+    pub(crate) fn new(type_: LLVMTypeRef) -> AnyTypeEnum<'ctx> {
         let type_kind = unsafe {
             LLVMGetTypeKind(type_)
         };
@@ -61,13 +114,14 @@ impl AnyTypeEnum {
             LLVMTypeKind::LLVMVectorTypeKind => AnyTypeEnum::VectorType(VectorType::new(type_)),
             LLVMTypeKind::LLVMMetadataTypeKind => panic!("FIXME: Unsupported type: Metadata"),
             LLVMTypeKind::LLVMX86_MMXTypeKind => panic!("FIXME: Unsupported type: MMX"),
-            // LLVMTypeKind::LLVMTokenTypeKind => panic!("FIXME: Unsupported type: Token"), // Different version?
+            #[cfg(not(feature = "llvm3-7"))]
+            LLVMTypeKind::LLVMTokenTypeKind => panic!("FIXME: Unsupported type: Token"),
         }
     }
 }
 
-impl BasicTypeEnum {
-    pub(crate) fn new(type_: LLVMTypeRef) -> BasicTypeEnum {
+impl<'ctx> BasicTypeEnum<'ctx> {
+    pub(crate) fn new(type_: LLVMTypeRef) -> BasicTypeEnum<'ctx> {
         let type_kind = unsafe {
             LLVMGetTypeKind(type_)
         };
@@ -89,6 +143,8 @@ impl BasicTypeEnum {
             LLVMTypeKind::LLVMLabelTypeKind => unreachable!("Unsupported type: Label"),
             LLVMTypeKind::LLVMVoidTypeKind => unreachable!("Unsupported type: VoidType"),
             LLVMTypeKind::LLVMFunctionTypeKind => unreachable!("Unsupported type: FunctionType"),
+            #[cfg(not(feature = "llvm3-7"))]
+            LLVMTypeKind::LLVMTokenTypeKind => unreachable!("Unsupported type: Token"),
         }
     }
 }
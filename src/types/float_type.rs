@@ -2,20 +2,19 @@ use llvm_sys::core::{LLVMConstReal, LLVMConstNull, LLVMHalfType, LLVMFloatType,
 use llvm_sys::execution_engine::LLVMCreateGenericValueOfFloat;
 use llvm_sys::prelude::LLVMTypeRef;
 
-use std::ffi::CStr;
-
 use AddressSpace;
 use context::ContextRef;
+use support::LLVMString;
 use types::traits::AsTypeRef;
 use types::{Type, PointerType, FunctionType, BasicType, ArrayType, VectorType};
 use values::{FloatValue, GenericValue, PointerValue, IntValue};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct FloatType {
-    float_type: Type,
+pub struct FloatType<'ctx> {
+    float_type: Type<'ctx>,
 }
 
-impl FloatType {
+impl<'ctx> FloatType<'ctx> {
     pub(crate) fn new(float_type: LLVMTypeRef) -> Self {
         assert!(!float_type.is_null());
 
@@ -24,19 +23,19 @@ impl FloatType {
         }
     }
 
-    pub fn fn_type(&self, param_types: &[&BasicType], is_var_args: bool) -> FunctionType {
+    pub fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
         self.float_type.fn_type(param_types, is_var_args)
     }
 
-    pub fn array_type(&self, size: u32) -> ArrayType {
+    pub fn array_type(&self, size: u32) -> ArrayType<'ctx> {
         self.float_type.array_type(size)
     }
 
-    pub fn vec_type(&self, size: u32) -> VectorType {
+    pub fn vec_type(&self, size: u32) -> VectorType<'ctx> {
         self.float_type.vec_type(size)
     }
 
-    pub fn const_float(&self, value: f64) -> FloatValue {
+    pub fn const_float(&self, value: f64) -> FloatValue<'ctx, 'ctx> {
         let value = unsafe {
             LLVMConstReal(self.float_type.type_, value)
         };
@@ -45,7 +44,7 @@ impl FloatType {
     }
 
     // REVIEW: What happens when string is invalid? Nullptr?
-    pub fn const_float_from_string(&self, slice: &str) -> FloatValue {
+    pub fn const_float_from_string(&self, slice: &str) -> FloatValue<'ctx, 'ctx> {
         let value = unsafe {
             LLVMConstRealOfStringAndSize(self.as_type_ref(), slice.as_ptr() as *const i8, slice.len() as u32)
         };
@@ -53,11 +52,11 @@ impl FloatType {
         FloatValue::new(value)
     }
 
-    pub fn const_null_ptr(&self) -> PointerValue {
+    pub fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
         self.float_type.const_null_ptr()
     }
 
-    pub fn const_null(&self) -> FloatValue {
+    pub fn const_null(&self) -> FloatValue<'ctx, 'ctx> {
         let null = unsafe {
             LLVMConstNull(self.as_type_ref())
         };
@@ -70,18 +69,40 @@ impl FloatType {
         self.float_type.is_sized()
     }
 
-    pub fn size_of(&self) -> IntValue {
+    pub fn size_of(&self) -> IntValue<'ctx, 'ctx> {
         self.float_type.size_of()
     }
 
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         self.float_type.get_context()
     }
 
-    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType {
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
         self.float_type.ptr_type(address_space)
     }
 
+    pub fn print_to_string(&self) -> LLVMString {
+        self.float_type.print_to_string()
+    }
+
+    pub fn print_to_stderr(&self) {
+        self.float_type.print_to_stderr()
+    }
+
+    pub fn get_undef(&self) -> FloatValue<'ctx, 'ctx> {
+        FloatValue::new(self.float_type.get_undef())
+    }
+
+    pub fn create_generic_value(&self, value: f64) -> GenericValue<'ctx> {
+        let value = unsafe {
+            LLVMCreateGenericValueOfFloat(self.as_type_ref(), value)
+        };
+
+        GenericValue::new(value)
+    }
+}
+
+impl FloatType<'static> {
     pub fn f16_type() -> Self {
         let float_type = unsafe {
             LLVMHalfType()
@@ -121,29 +142,9 @@ impl FloatType {
 
         FloatType::new(float_type)
     }
-
-    pub fn print_to_string(&self) -> &CStr {
-        self.float_type.print_to_string()
-    }
-
-    pub fn print_to_stderr(&self) {
-        self.float_type.print_to_stderr()
-    }
-
-    pub fn get_undef(&self) -> FloatValue {
-        FloatValue::new(self.float_type.get_undef())
-    }
-
-    pub fn create_generic_value(&self, value: f64) -> GenericValue {
-        let value = unsafe {
-            LLVMCreateGenericValueOfFloat(self.as_type_ref(), value)
-        };
-
-        GenericValue::new(value)
-    }
 }
 
-impl AsTypeRef for FloatType {
+impl<'ctx> AsTypeRef for FloatType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.float_type.type_
     }
@@ -1,22 +1,24 @@
-use llvm_sys::core::{LLVMGetParamTypes, LLVMIsFunctionVarArg, LLVMCountParamTypes};
+use llvm_sys::core::{LLVMGetParamTypes, LLVMIsFunctionVarArg, LLVMCountParamTypes, LLVMGetReturnType, LLVMGetTypeKind};
+use llvm_sys::LLVMTypeKind;
 use llvm_sys::prelude::LLVMTypeRef;
 
-use std::ffi::CStr;
 use std::fmt;
 use std::mem::forget;
 
+use AddressSpace;
 use context::ContextRef;
+use support::LLVMString;
 use types::traits::AsTypeRef;
-use types::{Type, BasicTypeEnum};
+use types::{Type, BasicTypeEnum, PointerType};
 // use values::FunctionValue;
 
 #[derive(PartialEq, Eq, Clone, Copy)]
-pub struct FunctionType {
-    fn_type: Type,
+pub struct FunctionType<'ctx> {
+    fn_type: Type<'ctx>,
 }
 
-impl FunctionType {
-    pub(crate) fn new(fn_type: LLVMTypeRef) -> FunctionType {
+impl<'ctx> FunctionType<'ctx> {
+    pub(crate) fn new(fn_type: LLVMTypeRef) -> FunctionType<'ctx> {
         assert!(!fn_type.is_null());
 
         FunctionType {
@@ -24,13 +26,17 @@ impl FunctionType {
         }
     }
 
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
+        self.fn_type.ptr_type(address_space)
+    }
+
     pub fn is_var_arg(&self) -> bool {
         unsafe {
             LLVMIsFunctionVarArg(self.as_type_ref()) != 0
         }
     }
 
-    pub fn get_param_types(&self) -> Vec<BasicTypeEnum> {
+    pub fn get_param_types(&self) -> Vec<BasicTypeEnum<'ctx>> {
         let count = self.count_param_types();
         let mut raw_vec: Vec<LLVMTypeRef> = Vec::with_capacity(count as usize);
         let ptr = raw_vec.as_mut_ptr();
@@ -46,6 +52,23 @@ impl FunctionType {
         raw_vec.iter().map(|val| BasicTypeEnum::new(*val)).collect()
     }
 
+    /// Gets the return type of this `FunctionType`, or `None` if it returns void.
+    pub fn get_return_type(&self) -> Option<BasicTypeEnum<'ctx>> {
+        let type_ = unsafe {
+            LLVMGetReturnType(self.as_type_ref())
+        };
+
+        let kind = unsafe {
+            LLVMGetTypeKind(type_)
+        };
+
+        if let LLVMTypeKind::LLVMVoidTypeKind = kind {
+            return None;
+        }
+
+        Some(BasicTypeEnum::new(type_))
+    }
+
     pub fn count_param_types(&self) -> u32 {
         unsafe {
             LLVMCountParamTypes(self.as_type_ref())
@@ -57,11 +80,11 @@ impl FunctionType {
         self.fn_type.is_sized()
     }
 
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         self.fn_type.get_context()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.fn_type.print_to_string()
     }
 
@@ -77,7 +100,7 @@ impl FunctionType {
     // }
 }
 
-impl fmt::Debug for FunctionType {
+impl<'ctx> fmt::Debug for FunctionType<'ctx> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let llvm_type = self.print_to_string();
 
@@ -85,7 +108,7 @@ impl fmt::Debug for FunctionType {
     }
 }
 
-impl AsTypeRef for FunctionType {
+impl<'ctx> AsTypeRef for FunctionType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.fn_type.type_
     }
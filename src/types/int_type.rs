@@ -2,20 +2,19 @@ use llvm_sys::core::{LLVMInt1Type, LLVMInt8Type, LLVMInt16Type, LLVMInt32Type, L
 use llvm_sys::execution_engine::LLVMCreateGenericValueOfInt;
 use llvm_sys::prelude::LLVMTypeRef;
 
-use std::ffi::CStr;
-
 use AddressSpace;
 use context::ContextRef;
+use support::LLVMString;
 use types::traits::AsTypeRef;
 use types::{Type, ArrayType, BasicType, VectorType, PointerType, FunctionType};
 use values::{GenericValue, IntValue, PointerValue};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct IntType {
-    int_type: Type,
+pub struct IntType<'ctx> {
+    int_type: Type<'ctx>,
 }
 
-impl IntType {
+impl<'ctx> IntType<'ctx> {
     pub(crate) fn new(int_type: LLVMTypeRef) -> Self {
         assert!(!int_type.is_null());
 
@@ -24,6 +23,158 @@ impl IntType {
         }
     }
 
+    /// Creates an `IntValue` repesenting a constant value of this `IntType`. It will be automatically assigned this `IntType`'s `Context`.
+    ///
+    /// # Example
+    /// ```
+    /// use inkwell::context::Context;
+    /// use inkwell::types::IntType;
+    ///
+    /// // Global Context
+    /// let i32_type = IntType::i32_type();
+    /// let i32_value = i32_type.const_int(42, false);
+    ///
+    /// // Custom Context
+    /// let context = Context::create();
+    /// let i32_type = context.i32_type();
+    /// let i32_value = i32_type.const_int(42, false);
+    /// ```
+    // TODOC: Maybe better explain sign extension
+    pub fn const_int(&self, value: u64, sign_extend: bool) -> IntValue<'ctx, 'ctx> {
+        let value = unsafe {
+            LLVMConstInt(self.as_type_ref(), value, sign_extend as i32)
+        };
+
+        IntValue::new(value)
+    }
+
+    // TODOC: LLVM will parse as best as it can, without any error for invalid input
+    // ie ("012", 2) => int 1
+    pub fn const_int_from_string(&self, slice: &str, radix: u8) -> IntValue<'ctx, 'ctx> {
+        let value = unsafe {
+            LLVMConstIntOfStringAndSize(self.as_type_ref(), slice.as_ptr() as *const i8, slice.len() as u32, radix)
+        };
+
+        IntValue::new(value)
+    }
+
+    pub fn const_int_arbitrary_precision(&self, words: &[u64]) -> IntValue<'ctx, 'ctx> {
+        let value = unsafe {
+            LLVMConstIntOfArbitraryPrecision(self.as_type_ref(), words.len() as u32, words.as_ptr())
+        };
+
+        IntValue::new(value)
+    }
+
+    /// Creates an `IntValue` representing a constant value of all one bits of this `IntType`. It will be automatically assigned this `IntType`'s `Context`.
+    ///
+    /// # Example
+    /// ```
+    /// use inkwell::context::Context;
+    /// use inkwell::types::IntType;
+    ///
+    /// // Global Context
+    /// let i32_type = IntType::i32_type();
+    /// let i32_ptr_value = i32_type.const_all_ones();
+    ///
+    /// // Custom Context
+    /// let context = Context::create();
+    /// let i32_type = context.i32_type();
+    /// let i32_ptr_value = i32_type.const_all_ones();
+    /// ```
+    pub fn const_all_ones(&self) -> IntValue<'ctx, 'ctx> {
+        let value = unsafe {
+            LLVMConstAllOnes(self.as_type_ref())
+        };
+
+        IntValue::new(value)
+    }
+
+    /// Creates a `PointerValue` representing a constant value of zero (null pointer) pointing to this `IntType`. It will be automatically assigned this `IntType`'s `Context`.
+    ///
+    /// # Example
+    /// ```
+    /// use inkwell::context::Context;
+    /// use inkwell::types::IntType;
+    ///
+    /// // Global Context
+    /// let i32_type = IntType::i32_type();
+    /// let i32_value = i32_type.const_null_ptr();
+    ///
+    /// // Custom Context
+    /// let context = Context::create();
+    /// let i32_type = context.i32_type();
+    /// let i32_value = i32_type.const_null_ptr();
+    /// ```
+    pub fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
+        self.int_type.const_null_ptr()
+    }
+
+    pub fn const_null(&self) -> IntValue<'ctx, 'ctx> {
+        let null = unsafe {
+            LLVMConstNull(self.as_type_ref())
+        };
+
+        IntValue::new(null)
+    }
+
+    pub fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
+        self.int_type.fn_type(param_types, is_var_args)
+    }
+
+    pub fn array_type(&self, size: u32) -> ArrayType<'ctx> {
+        self.int_type.array_type(size)
+    }
+
+    pub fn vec_type(&self, size: u32) -> VectorType<'ctx> {
+        self.int_type.vec_type(size)
+    }
+
+    pub fn get_context(&self) -> ContextRef<'ctx> {
+        self.int_type.get_context()
+    }
+
+    // REVIEW: Always true -> const fn?
+    pub fn is_sized(&self) -> bool {
+        self.int_type.is_sized()
+    }
+
+    pub fn size_of(&self) -> IntValue<'ctx, 'ctx> {
+        self.int_type.size_of()
+    }
+
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
+        self.int_type.ptr_type(address_space)
+    }
+
+    pub fn get_bit_width(&self) -> u32 {
+        unsafe {
+            LLVMGetIntTypeWidth(self.as_type_ref())
+        }
+    }
+
+    pub fn print_to_string(&self) -> LLVMString {
+        self.int_type.print_to_string()
+    }
+
+    pub fn print_to_stderr(&self) {
+        self.int_type.print_to_stderr()
+    }
+
+    pub fn get_undef(&self) -> IntValue<'ctx, 'ctx> {
+        IntValue::new(self.int_type.get_undef())
+    }
+
+    pub fn create_generic_value(&self, value: u64, is_signed: bool) -> GenericValue<'ctx> {
+        let value = unsafe {
+            LLVMCreateGenericValueOfInt(self.as_type_ref(), value, is_signed as i32)
+        };
+
+        GenericValue::new(value)
+    }
+}
+
+impl IntType<'static> {
     /// Gets the `IntType` representing 1 bit width. It will be automatically assigned the global context.
     ///
     /// To use your own `Context`, see [inkwell::context::bool_type()](../context/struct.Context.html#method.bool_type)
@@ -176,159 +327,9 @@ impl IntType {
 
         IntType::new(type_)
     }
-
-    /// Creates an `IntValue` repesenting a constant value of this `IntType`. It will be automatically assigned this `IntType`'s `Context`.
-    ///
-    /// # Example
-    /// ```
-    /// use inkwell::context::Context;
-    /// use inkwell::types::IntType;
-    ///
-    /// // Global Context
-    /// let i32_type = IntType::i32_type();
-    /// let i32_value = i32_type.const_int(42, false);
-    ///
-    /// // Custom Context
-    /// let context = Context::create();
-    /// let i32_type = context.i32_type();
-    /// let i32_value = i32_type.const_int(42, false);
-    /// ```
-    // TODOC: Maybe better explain sign extension
-    pub fn const_int(&self, value: u64, sign_extend: bool) -> IntValue {
-        let value = unsafe {
-            LLVMConstInt(self.as_type_ref(), value, sign_extend as i32)
-        };
-
-        IntValue::new(value)
-    }
-
-    // TODOC: LLVM will parse as best as it can, without any error for invalid input
-    // ie ("012", 2) => int 1
-    pub fn const_int_from_string(&self, slice: &str, radix: u8) -> IntValue {
-        let value = unsafe {
-            LLVMConstIntOfStringAndSize(self.as_type_ref(), slice.as_ptr() as *const i8, slice.len() as u32, radix)
-        };
-
-        IntValue::new(value)
-    }
-
-    pub fn const_int_arbitrary_precision(&self, words: &[u64]) -> IntValue {
-        let value = unsafe {
-            LLVMConstIntOfArbitraryPrecision(self.as_type_ref(), words.len() as u32, words.as_ptr())
-        };
-
-        IntValue::new(value)
-    }
-
-    /// Creates an `IntValue` representing a constant value of all one bits of this `IntType`. It will be automatically assigned this `IntType`'s `Context`.
-    ///
-    /// # Example
-    /// ```
-    /// use inkwell::context::Context;
-    /// use inkwell::types::IntType;
-    ///
-    /// // Global Context
-    /// let i32_type = IntType::i32_type();
-    /// let i32_ptr_value = i32_type.const_all_ones();
-    ///
-    /// // Custom Context
-    /// let context = Context::create();
-    /// let i32_type = context.i32_type();
-    /// let i32_ptr_value = i32_type.const_all_ones();
-    /// ```
-    pub fn const_all_ones(&self) -> IntValue {
-        let value = unsafe {
-            LLVMConstAllOnes(self.as_type_ref())
-        };
-
-        IntValue::new(value)
-    }
-
-    /// Creates a `PointerValue` representing a constant value of zero (null pointer) pointing to this `IntType`. It will be automatically assigned this `IntType`'s `Context`.
-    ///
-    /// # Example
-    /// ```
-    /// use inkwell::context::Context;
-    /// use inkwell::types::IntType;
-    ///
-    /// // Global Context
-    /// let i32_type = IntType::i32_type();
-    /// let i32_value = i32_type.const_null_ptr();
-    ///
-    /// // Custom Context
-    /// let context = Context::create();
-    /// let i32_type = context.i32_type();
-    /// let i32_value = i32_type.const_null_ptr();
-    /// ```
-    pub fn const_null_ptr(&self) -> PointerValue {
-        self.int_type.const_null_ptr()
-    }
-
-    pub fn const_null(&self) -> IntValue {
-        let null = unsafe {
-            LLVMConstNull(self.as_type_ref())
-        };
-
-        IntValue::new(null)
-    }
-
-    pub fn fn_type(&self, param_types: &[&BasicType], is_var_args: bool) -> FunctionType {
-        self.int_type.fn_type(param_types, is_var_args)
-    }
-
-    pub fn array_type(&self, size: u32) -> ArrayType {
-        self.int_type.array_type(size)
-    }
-
-    pub fn vec_type(&self, size: u32) -> VectorType {
-        self.int_type.vec_type(size)
-    }
-
-    pub fn get_context(&self) -> ContextRef {
-        self.int_type.get_context()
-    }
-
-    // REVIEW: Always true -> const fn?
-    pub fn is_sized(&self) -> bool {
-        self.int_type.is_sized()
-    }
-
-    pub fn size_of(&self) -> IntValue {
-        self.int_type.size_of()
-    }
-
-    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType {
-        self.int_type.ptr_type(address_space)
-    }
-
-    pub fn get_bit_width(&self) -> u32 {
-        unsafe {
-            LLVMGetIntTypeWidth(self.as_type_ref())
-        }
-    }
-
-    pub fn print_to_string(&self) -> &CStr {
-        self.int_type.print_to_string()
-    }
-
-    pub fn print_to_stderr(&self) {
-        self.int_type.print_to_stderr()
-    }
-
-    pub fn get_undef(&self) -> IntValue {
-        IntValue::new(self.int_type.get_undef())
-    }
-
-    pub fn create_generic_value(&self, value: u64, is_signed: bool) -> GenericValue {
-        let value = unsafe {
-            LLVMCreateGenericValueOfInt(self.as_type_ref(), value, is_signed as i32)
-        };
-
-        GenericValue::new(value)
-    }
 }
 
-impl AsTypeRef for IntType {
+impl<'ctx> AsTypeRef for IntType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.int_type.type_
     }
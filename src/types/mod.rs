@@ -9,6 +9,7 @@ mod traits;
 mod vec_type;
 mod void_type;
 
+use support::LLVMString;
 pub use types::array_type::ArrayType;
 pub use types::enums::{AnyTypeEnum, BasicTypeEnum};
 pub use types::float_type::FloatType;
@@ -25,8 +26,8 @@ use llvm_sys::core::{LLVMAlignOf, LLVMGetTypeContext, LLVMFunctionType, LLVMArra
 use llvm_sys::LLVMTypeKind;
 use llvm_sys::prelude::{LLVMTypeRef, LLVMValueRef};
 
-use std::ffi::CStr;
 use std::fmt;
+use std::marker::PhantomData;
 use std::rc::Rc;
 
 use AddressSpace;
@@ -37,16 +38,18 @@ use values::{IntValue, PointerValue};
 // Though this is likely only true per thread since LLVM claims to not be very thread-safe.
 // REVIEW: Maybe move this into its own module?
 #[derive(PartialEq, Eq, Clone, Copy)]
-struct Type {
+struct Type<'ctx> {
     type_: LLVMTypeRef,
+    _marker: PhantomData<fn(&'ctx ()) -> &'ctx ()>,
 }
 
-impl Type {
+impl<'ctx> Type<'ctx> {
     fn new(type_: LLVMTypeRef) -> Self {
         assert!(!type_.is_null());
 
         Type {
             type_: type_,
+            _marker: PhantomData,
         }
     }
 
@@ -56,7 +59,7 @@ impl Type {
         }
     }
 
-    fn const_null_ptr(&self) -> PointerValue {
+    fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
         let ptr_type = unsafe {
             LLVMConstPointerNull(self.type_)
         };
@@ -64,7 +67,7 @@ impl Type {
         PointerValue::new(ptr_type)
     }
 
-    fn ptr_type(&self, address_space: AddressSpace) -> PointerType {
+    fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
         let ptr_type = unsafe {
             LLVMPointerType(self.type_, address_space as u32)
         };
@@ -72,7 +75,7 @@ impl Type {
         PointerType::new(ptr_type)
     }
 
-    fn vec_type(&self, size: u32) -> VectorType {
+    fn vec_type(&self, size: u32) -> VectorType<'ctx> {
         let vec_type = unsafe {
             LLVMVectorType(self.type_, size)
         };
@@ -82,7 +85,7 @@ impl Type {
 
     // REVIEW: Is this actually AnyType except FunctionType? VoidType? Can you make a FunctionType from a FunctionType???
     // Probably should just be BasicType
-    fn fn_type(&self, param_types: &[&BasicType], is_var_args: bool) -> FunctionType {
+    fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
         let mut param_types: Vec<LLVMTypeRef> = param_types.iter()
                                                            .map(|val| val.as_type_ref())
                                                            .collect();
@@ -93,7 +96,7 @@ impl Type {
         FunctionType::new(fn_type)
     }
 
-    fn array_type(&self, size: u32) -> ArrayType {
+    fn array_type(&self, size: u32) -> ArrayType<'ctx> {
         let type_ = unsafe {
             LLVMArrayType(self.type_, size)
         };
@@ -115,7 +118,7 @@ impl Type {
     }
 
     // REVIEW: Return IntValue?
-    fn get_alignment(&self) -> IntValue {
+    fn get_alignment(&self) -> IntValue<'ctx, 'ctx> {
         let val = unsafe {
             LLVMAlignOf(self.type_)
         };
@@ -123,7 +126,7 @@ impl Type {
         IntValue::new(val)
     }
 
-    fn get_context(&self) -> ContextRef {
+    fn get_context(&self) -> ContextRef<'ctx> {
         // We don't return an option because LLVM seems
         // to always assign a context, even to types
         // created without an explicit context, somehow
@@ -147,7 +150,7 @@ impl Type {
 
     // REVIEW: Option<IntValue>? If we want to provide it on enums that
     // contain unsized types
-    fn size_of(&self) -> IntValue {
+    fn size_of(&self) -> IntValue<'ctx, 'ctx> {
         debug_assert!(self.is_sized());
 
         let int_value = unsafe {
@@ -157,14 +160,16 @@ impl Type {
         IntValue::new(int_value)
     }
 
-    fn print_to_string(&self) -> &CStr {
-        unsafe {
-            CStr::from_ptr(LLVMPrintTypeToString(self.type_))
-        }
+    fn print_to_string(&self) -> LLVMString {
+        let c_string = unsafe {
+            LLVMPrintTypeToString(self.type_)
+        };
+
+        LLVMString::new(c_string)
     }
 }
 
-impl fmt::Debug for Type {
+impl<'ctx> fmt::Debug for Type<'ctx> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let llvm_type = self.print_to_string();
 
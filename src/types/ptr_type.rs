@@ -1,20 +1,19 @@
 use llvm_sys::core::{LLVMGetPointerAddressSpace, LLVMConstNull};
 use llvm_sys::prelude::LLVMTypeRef;
 
-use std::ffi::CStr;
-
 use AddressSpace;
 use context::ContextRef;
+use support::LLVMString;
 use types::traits::AsTypeRef;
 use types::{Type, BasicType, ArrayType, FunctionType, VectorType};
 use values::{PointerValue, IntValue};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct PointerType {
-    ptr_type: Type,
+pub struct PointerType<'ctx> {
+    ptr_type: Type<'ctx>,
 }
 
-impl PointerType {
+impl<'ctx> PointerType<'ctx> {
     pub(crate) fn new(ptr_type: LLVMTypeRef) -> Self {
         assert!(!ptr_type.is_null());
 
@@ -27,23 +26,23 @@ impl PointerType {
         self.ptr_type.is_sized()
     }
 
-    pub fn size_of(&self) -> IntValue {
+    pub fn size_of(&self) -> IntValue<'ctx, 'ctx> {
         self.ptr_type.size_of()
     }
 
-    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType {
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
         self.ptr_type.ptr_type(address_space)
     }
 
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         self.ptr_type.get_context()
     }
 
-    pub fn fn_type(&self, param_types: &[&BasicType], is_var_args: bool) -> FunctionType {
+    pub fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
         self.ptr_type.fn_type(param_types, is_var_args)
     }
 
-    pub fn array_type(&self, size: u32) -> ArrayType {
+    pub fn array_type(&self, size: u32) -> ArrayType<'ctx> {
         self.ptr_type.array_type(size)
     }
 
@@ -53,7 +52,7 @@ impl PointerType {
         }
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.ptr_type.print_to_string()
     }
 
@@ -61,11 +60,11 @@ impl PointerType {
         self.ptr_type.print_to_stderr()
     }
 
-    pub fn const_null_ptr(&self) -> PointerValue {
+    pub fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
         self.ptr_type.const_null_ptr()
     }
 
-    pub fn const_null(&self) -> PointerValue {
+    pub fn const_null(&self) -> PointerValue<'ctx, 'ctx> {
         let null = unsafe {
             LLVMConstNull(self.as_type_ref())
         };
@@ -73,16 +72,16 @@ impl PointerType {
         PointerValue::new(null)
     }
 
-    pub fn get_undef(&self) -> PointerValue {
+    pub fn get_undef(&self) -> PointerValue<'ctx, 'ctx> {
         PointerValue::new(self.ptr_type.get_undef())
     }
 
-    pub fn vec_type(&self, size: u32) -> VectorType {
+    pub fn vec_type(&self, size: u32) -> VectorType<'ctx> {
         self.ptr_type.vec_type(size)
     }
 }
 
-impl AsTypeRef for PointerType {
+impl<'ctx> AsTypeRef for PointerType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.ptr_type.type_
     }
@@ -6,16 +6,17 @@ use std::mem::forget;
 
 use AddressSpace;
 use context::ContextRef;
+use support::LLVMString;
 use types::traits::AsTypeRef;
 use types::{Type, BasicType, BasicTypeEnum, ArrayType, PointerType, FunctionType, VectorType};
 use values::{BasicValue, StructValue, PointerValue, IntValue};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct StructType {
-    struct_type: Type,
+pub struct StructType<'ctx> {
+    struct_type: Type<'ctx>,
 }
 
-impl StructType {
+impl<'ctx> StructType<'ctx> {
     pub(crate) fn new(struct_type: LLVMTypeRef) -> Self {
         assert!(!struct_type.is_null());
 
@@ -26,7 +27,7 @@ impl StructType {
 
     // TODO: Would be great to be able to smartly be able to do this by field name
     // TODO: LLVM 3.7+ only
-    pub fn get_field_type_at_index(&self, index: u32) -> Option<BasicTypeEnum> {
+    pub fn get_field_type_at_index(&self, index: u32) -> Option<BasicTypeEnum<'ctx>> {
         // LLVM doesn't seem to just return null if opaque.
         // TODO: One day, with SubTypes (& maybe specialization?) we could just
         // impl this method for non opaque structs only
@@ -47,7 +48,7 @@ impl StructType {
     }
 
     // REVIEW: What's the difference between these two??
-    pub fn const_named_struct(&self, values: &[&BasicValue]) -> StructValue {
+    pub fn const_named_struct<'m>(&self, values: &[&BasicValue<'ctx, 'm>]) -> StructValue<'ctx, 'm> {
         let mut args: Vec<LLVMValueRef> = values.iter()
                                                 .map(|val| val.as_value_ref())
                                                 .collect();
@@ -58,22 +59,11 @@ impl StructType {
         StructValue::new(value)
     }
 
-    pub fn const_struct(values: &[&BasicValue], packed: bool) -> StructValue {
-        let mut args: Vec<LLVMValueRef> = values.iter()
-                                                .map(|val| val.as_value_ref())
-                                                .collect();
-        let value = unsafe {
-            LLVMConstStruct(args.as_mut_ptr(), args.len() as u32, packed as i32)
-        };
-
-        StructValue::new(value)
-    }
-
-    pub fn const_null_ptr(&self) -> PointerValue {
+    pub fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
         self.struct_type.const_null_ptr()
     }
 
-    pub fn const_null(&self) -> StructValue {
+    pub fn const_null(&self) -> StructValue<'ctx, 'ctx> {
         let null = unsafe {
             LLVMConstNull(self.as_type_ref())
         };
@@ -88,7 +78,7 @@ impl StructType {
     }
 
     // TODO: impl it only for StructType<T*>
-    pub fn size_of(&self) -> Option<IntValue> {
+    pub fn size_of(&self) -> Option<IntValue<'ctx, 'ctx>> {
         if self.is_sized() {
             return Some(self.struct_type.size_of());
         }
@@ -96,7 +86,7 @@ impl StructType {
         None
     }
 
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         self.struct_type.get_context()
     }
 
@@ -116,15 +106,15 @@ impl StructType {
         Some(c_str)
     }
 
-    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType {
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
         self.struct_type.ptr_type(address_space)
     }
 
-    pub fn fn_type(&self, param_types: &[&BasicType], is_var_args: bool) -> FunctionType {
+    pub fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
         self.struct_type.fn_type(param_types, is_var_args)
     }
 
-    pub fn array_type(&self, size: u32) -> ArrayType {
+    pub fn array_type(&self, size: u32) -> ArrayType<'ctx> {
         self.struct_type.array_type(size)
     }
 
@@ -142,20 +132,6 @@ impl StructType {
         }
     }
 
-    // REVIEW: Is there an equivalent method to make opaque?
-    // REVIEW: No way to set name like in context.struct_type() method?
-    // DOC: This method will not create an opaque struct, even if empty array is passed!
-    pub fn struct_type(field_types: &[&BasicType], packed: bool) -> Self {
-        let mut field_types: Vec<LLVMTypeRef> = field_types.iter()
-                                                           .map(|val| val.as_type_ref())
-                                                           .collect();
-        let struct_type = unsafe {
-            LLVMStructType(field_types.as_mut_ptr(), field_types.len() as u32, packed as i32)
-        };
-
-        StructType::new(struct_type)
-    }
-
     pub fn count_fields(&self) -> u32 {
         unsafe {
             LLVMCountStructElementTypes(self.as_type_ref())
@@ -163,7 +139,7 @@ impl StructType {
     }
 
     // REVIEW: Method name
-    pub fn get_field_types(&self) -> Vec<BasicTypeEnum> {
+    pub fn get_field_types(&self) -> Vec<BasicTypeEnum<'ctx>> {
         let count = self.count_fields();
         let mut raw_vec: Vec<LLVMTypeRef> = Vec::with_capacity(count as usize);
         let ptr = raw_vec.as_mut_ptr();
@@ -179,7 +155,7 @@ impl StructType {
         raw_vec.iter().map(|val| BasicTypeEnum::new(*val)).collect()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.struct_type.print_to_string()
     }
 
@@ -187,14 +163,14 @@ impl StructType {
         self.struct_type.print_to_stderr()
     }
 
-    pub fn get_undef(&self) -> StructValue {
+    pub fn get_undef(&self) -> StructValue<'ctx, 'ctx> {
         StructValue::new(self.struct_type.get_undef())
     }
 
     // REVIEW: SubTypes should allow this to only be implemented for StructType<Opaque> one day
     // but would have to return StructType<Tys>
     // REVIEW: What happens if called with &[]? Should that still be opaque? Does the call break?
-    pub fn set_body(&self, field_types: &[&BasicType], packed: bool) -> bool {
+    pub fn set_body(&self, field_types: &[&BasicType<'ctx>], packed: bool) -> bool {
         let is_opaque = self.is_opaque();
         let mut field_types: Vec<LLVMTypeRef> = field_types.iter()
                                                            .map(|val| val.as_type_ref())
@@ -208,12 +184,39 @@ impl StructType {
         is_opaque
     }
 
-    pub fn vec_type(&self, size: u32) -> VectorType {
+    pub fn vec_type(&self, size: u32) -> VectorType<'ctx> {
         self.struct_type.vec_type(size)
     }
 }
 
-impl AsTypeRef for StructType {
+impl StructType<'static> {
+    // REVIEW: Is there an equivalent method to make opaque?
+    // REVIEW: No way to set name like in context.struct_type() method?
+    // DOC: This method will not create an opaque struct, even if empty array is passed!
+    pub fn struct_type(field_types: &[&BasicType<'static>], packed: bool) -> Self {
+        let mut field_types: Vec<LLVMTypeRef> = field_types.iter()
+                                                           .map(|val| val.as_type_ref())
+                                                           .collect();
+        let struct_type = unsafe {
+            LLVMStructType(field_types.as_mut_ptr(), field_types.len() as u32, packed as i32)
+        };
+
+        StructType::new(struct_type)
+    }
+
+    pub fn const_struct<'m>(values: &[&BasicValue<'static, 'm>], packed: bool) -> StructValue<'static, 'm> {
+        let mut args: Vec<LLVMValueRef> = values.iter()
+                                                .map(|val| val.as_value_ref())
+                                                .collect();
+        let value = unsafe {
+            LLVMConstStruct(args.as_mut_ptr(), args.len() as u32, packed as i32)
+        };
+
+        StructValue::new(value)
+    }
+}
+
+impl<'ctx> AsTypeRef for StructType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.struct_type.type_
     }
@@ -15,23 +15,23 @@ pub trait AsTypeRef {
 macro_rules! trait_type_set {
     ($trait_name:ident: $($args:ident),*) => (
         $(
-            impl $trait_name for $args {}
+            impl<'ctx> $trait_name<'ctx> for $args<'ctx> {}
         )*
     );
 }
 
 /// Represents any LLVM type.
-pub trait AnyType: AsTypeRef + Debug {
+pub trait AnyType<'ctx>: AsTypeRef + Debug {
     /// Returns an `AnyTypeEnum` that represents the current type.
-    fn as_any_type_enum(&self) -> AnyTypeEnum {
+    fn as_any_type_enum(&self) -> AnyTypeEnum<'ctx> {
         AnyTypeEnum::new(self.as_type_ref())
     }
 }
 
 /// Represents a basic LLVM type, that may be used in functions and struct declarations.
-pub trait BasicType: AnyType {
+pub trait BasicType<'ctx>: AnyType<'ctx> {
     /// Returns a `BasicTypeEnum` that represents the current type.
-    fn as_basic_type_enum(&self) -> BasicTypeEnum {
+    fn as_basic_type_enum(&self) -> BasicTypeEnum<'ctx> {
         BasicTypeEnum::new(self.as_type_ref())
     }
 }
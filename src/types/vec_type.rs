@@ -1,20 +1,19 @@
 use llvm_sys::core::{LLVMConstVector, LLVMConstNull, LLVMGetVectorSize};
 use llvm_sys::prelude::{LLVMTypeRef, LLVMValueRef};
 
-use std::ffi::CStr;
-
+use support::LLVMString;
 use types::traits::AsTypeRef;
-use types::Type;
+use types::{BasicType, FunctionType, Type};
 use values::{BasicValue, PointerValue, VectorValue, IntValue};
 
 // REVIEW: vec_type() is impl for IntType & FloatType. Need to
 // find out if it is valid for other types too. Maybe PointerType?
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct VectorType {
-    vec_type: Type,
+pub struct VectorType<'ctx> {
+    vec_type: Type<'ctx>,
 }
 
-impl VectorType {
+impl<'ctx> VectorType<'ctx> {
     pub(crate) fn new(vector_type: LLVMTypeRef) -> Self {
         assert!(!vector_type.is_null());
 
@@ -31,7 +30,7 @@ impl VectorType {
     // TODO: impl only for VectorType<!StructType<Opaque>>
     // REVIEW: What about Opaque struct hiding in deeper levels?
     // like VectorType<ArrayType<StructType<Opaque>>>?
-    pub fn size_of(&self) -> Option<IntValue> {
+    pub fn size_of(&self) -> Option<IntValue<'ctx, 'ctx>> {
         if self.is_sized() {
             return Some(self.vec_type.size_of())
         }
@@ -53,7 +52,7 @@ impl VectorType {
     // REVIEW: Maybe we could make this use &self if the vector size
     // is stored as a const and the input values took a const size?
     // Something like: values: &[&V; self.size]. Doesn't sound possible though
-    pub fn const_vector<V: BasicValue>(values: &[&V]) -> VectorValue {
+    pub fn const_vector<'m, V: BasicValue<'ctx, 'm>>(values: &[&V]) -> VectorValue<'ctx, 'm> {
         let mut values: Vec<LLVMValueRef> = values.iter()
                                                   .map(|val| val.as_value_ref())
                                                   .collect();
@@ -64,11 +63,11 @@ impl VectorType {
         VectorValue::new(vec_value)
     }
 
-    pub fn const_null_ptr(&self) -> PointerValue {
+    pub fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
         self.vec_type.const_null_ptr()
     }
 
-    pub fn const_null(&self) -> VectorValue {
+    pub fn const_null(&self) -> VectorValue<'ctx, 'ctx> {
         let null = unsafe {
             LLVMConstNull(self.as_type_ref())
         };
@@ -76,7 +75,7 @@ impl VectorType {
         VectorValue::new(null)
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.vec_type.print_to_string()
     }
 
@@ -84,12 +83,16 @@ impl VectorType {
         self.vec_type.print_to_stderr()
     }
 
-    pub fn get_undef(&self) -> VectorValue {
+    pub fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
+        self.vec_type.fn_type(param_types, is_var_args)
+    }
+
+    pub fn get_undef(&self) -> VectorValue<'ctx, 'ctx> {
         VectorValue::new(self.vec_type.get_undef())
     }
 }
 
-impl AsTypeRef for VectorType {
+impl<'ctx> AsTypeRef for VectorType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.vec_type.type_
     }
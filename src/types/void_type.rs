@@ -3,18 +3,17 @@ use llvm_sys::prelude::LLVMTypeRef;
 
 use AddressSpace;
 use context::ContextRef;
+use support::LLVMString;
 use types::traits::AsTypeRef;
 use types::{Type, BasicType, FunctionType, PointerType};
 use values::PointerValue;
 
-use std::ffi::CStr;
-
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct VoidType {
-    void_type: Type,
+pub struct VoidType<'ctx> {
+    void_type: Type<'ctx>,
 }
 
-impl VoidType {
+impl<'ctx> VoidType<'ctx> {
     pub(crate) fn new(void_type: LLVMTypeRef) -> Self {
         assert!(!void_type.is_null());
 
@@ -28,27 +27,19 @@ impl VoidType {
         self.void_type.is_sized()
     }
 
-    pub fn get_context(&self) -> ContextRef {
+    pub fn get_context(&self) -> ContextRef<'ctx> {
         self.void_type.get_context()
     }
 
-    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType {
+    pub fn ptr_type(&self, address_space: AddressSpace) -> PointerType<'ctx> {
         self.void_type.ptr_type(address_space)
     }
 
-    pub fn fn_type(&self, param_types: &[&BasicType], is_var_args: bool) -> FunctionType {
+    pub fn fn_type(&self, param_types: &[&BasicType<'ctx>], is_var_args: bool) -> FunctionType<'ctx> {
         self.void_type.fn_type(param_types, is_var_args)
     }
 
-    pub fn void_type() -> Self {
-        let void_type = unsafe {
-            LLVMVoidType()
-        };
-
-        VoidType::new(void_type)
-    }
-
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.void_type.print_to_string()
     }
 
@@ -56,12 +47,22 @@ impl VoidType {
         self.void_type.print_to_stderr()
     }
 
-    pub fn const_null_ptr(&self) -> PointerValue {
+    pub fn const_null_ptr(&self) -> PointerValue<'ctx, 'ctx> {
         self.void_type.const_null_ptr()
     }
 }
 
-impl AsTypeRef for VoidType {
+impl VoidType<'static> {
+    pub fn void_type() -> Self {
+        let void_type = unsafe {
+            LLVMVoidType()
+        };
+
+        VoidType::new(void_type)
+    }
+}
+
+impl<'ctx> AsTypeRef for VoidType<'ctx> {
     fn as_type_ref(&self) -> LLVMTypeRef {
         self.void_type.type_
     }
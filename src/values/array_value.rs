@@ -4,16 +4,17 @@ use llvm_sys::prelude::LLVMValueRef;
 use std::ffi::CStr;
 use std::fmt;
 
+use support::LLVMString;
 use types::ArrayType;
 use values::traits::AsValueRef;
-use values::{Value, InstructionValue, MetadataValue};
+use values::{Value, InstructionValue, MetadataValue, BasicValueUse, BasicValueUseIter};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
-pub struct ArrayValue {
-    array_value: Value
+pub struct ArrayValue<'ctx: 'm, 'm> {
+    array_value: Value<'ctx, 'm>
 }
 
-impl ArrayValue {
+impl<'ctx, 'm> ArrayValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -30,7 +31,7 @@ impl ArrayValue {
         self.array_value.set_name(name);
     }
 
-    pub fn get_type(&self) -> ArrayType {
+    pub fn get_type(&self) -> ArrayType<'ctx> {
         ArrayType::new(self.array_value.get_type())
     }
 
@@ -42,7 +43,7 @@ impl ArrayValue {
         self.array_value.is_undef()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.array_value.print_to_string()
     }
 
@@ -50,7 +51,7 @@ impl ArrayValue {
         self.array_value.print_to_stderr()
     }
 
-    pub fn as_instruction(&self) -> Option<InstructionValue> {
+    pub fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         self.array_value.as_instruction()
     }
 
@@ -58,26 +59,34 @@ impl ArrayValue {
         self.array_value.has_metadata()
     }
 
-    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         self.array_value.get_metadata(kind_id)
     }
 
-    pub fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         self.array_value.set_metadata(metadata, kind_id)
     }
 
-    pub fn replace_all_uses_with(&self, other: &ArrayValue) {
+    pub fn replace_all_uses_with(&self, other: &ArrayValue<'ctx, 'm>) {
         self.array_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.array_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.array_value.uses()
+    }
 }
 
-impl AsValueRef for ArrayValue {
+impl<'ctx, 'm> AsValueRef for ArrayValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.array_value.value
     }
 }
 
-impl fmt::Debug for ArrayValue {
+impl<'ctx, 'm> fmt::Debug for ArrayValue<'ctx, 'm> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let llvm_value = self.print_to_string();
         let llvm_type = self.get_type();
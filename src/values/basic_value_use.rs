@@ -0,0 +1,157 @@
+use either::Either;
+use llvm_sys::core::{LLVMGetNextUse, LLVMGetUser, LLVMGetUsedValue, LLVMGetNumOperands, LLVMGetOperandUse, LLVMIsABasicBlock, LLVMIsAInstruction, LLVMValueAsBasicBlock};
+use llvm_sys::prelude::LLVMUseRef;
+
+use std::marker::PhantomData;
+
+use basic_block::BasicBlock;
+use values::{AnyValueEnum, BasicMetadataValueEnum, InstructionValue};
+use ModuleMarker;
+
+/// A usage of a value by a user, such as an instruction or constant expression
+/// which takes the value as one of its operands.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BasicValueUse<'ctx: 'm, 'm> {
+    use_: LLVMUseRef,
+    _marker: ModuleMarker<'ctx, 'm>,
+}
+
+impl<'ctx, 'm> BasicValueUse<'ctx, 'm> {
+    pub(crate) fn new(use_: LLVMUseRef) -> Self {
+        debug_assert!(!use_.is_null());
+
+        BasicValueUse {
+            use_,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets the next use of the same value, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inkwell::AddressSpace;
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("uses");
+    /// let builder = context.create_builder();
+    /// let void_type = context.void_type();
+    /// let f32_type = context.f32_type();
+    /// let f32_ptr_type = f32_type.ptr_type(AddressSpace::Generic);
+    /// let fn_type = void_type.fn_type(&[&f32_ptr_type], false);
+    ///
+    /// let function = module.add_function("take_f32_ptr", &fn_type, None);
+    /// let basic_block = context.append_basic_block(&function, "entry");
+    ///
+    /// builder.position_at_end(&basic_block);
+    ///
+    /// let arg1 = function.get_first_param().unwrap().into_pointer_value();
+    /// let loaded = builder.build_load(&arg1, "loaded").into_float_value();
+    /// let store = builder.build_store(&arg1, &loaded);
+    ///
+    /// // The most recently added use comes first
+    /// let first_use = arg1.get_first_use().unwrap();
+    /// let second_use = first_use.get_next_use().unwrap();
+    ///
+    /// assert_eq!(first_use.get_user().into_instruction_value(), store);
+    /// assert_eq!(first_use.get_operand_index(), 1);
+    /// assert_eq!(second_use.get_user().into_instruction_value(), loaded.as_instruction().unwrap());
+    /// assert!(second_use.get_next_use().is_none());
+    /// ```
+    pub fn get_next_use(&self) -> Option<Self> {
+        let use_ = unsafe {
+            LLVMGetNextUse(self.use_)
+        };
+
+        if use_.is_null() {
+            return None;
+        }
+
+        Some(BasicValueUse::new(use_))
+    }
+
+    /// Gets the user of this use, which is usually an instruction but may also
+    /// be a constant expression or a global initializer. Instructions are always
+    /// returned as an `InstructionValue`, whatever their type.
+    pub fn get_user(&self) -> AnyValueEnum<'ctx, 'm> {
+        let user = unsafe {
+            LLVMGetUser(self.use_)
+        };
+
+        let is_instruction = unsafe {
+            !LLVMIsAInstruction(user).is_null()
+        };
+
+        if is_instruction {
+            return AnyValueEnum::InstructionValue(InstructionValue::new(user));
+        }
+
+        AnyValueEnum::new(user)
+    }
+
+    /// Gets the value being used, which is a `BasicBlock` when the user is a
+    /// terminator branching to it and may be a `MetadataValue` when the user
+    /// is a call to an intrinsic taking metadata.
+    pub fn get_used_value(&self) -> Either<BasicMetadataValueEnum<'ctx, 'm>, BasicBlock<'ctx, 'm>> {
+        let used_value = unsafe {
+            LLVMGetUsedValue(self.use_)
+        };
+
+        let is_basic_block = unsafe {
+            !LLVMIsABasicBlock(used_value).is_null()
+        };
+
+        if is_basic_block {
+            let bb = unsafe {
+                LLVMValueAsBasicBlock(used_value)
+            };
+
+            Either::Right(BasicBlock::new(bb).expect("BasicBlock should always be valid"))
+        } else {
+            Either::Left(BasicMetadataValueEnum::new(used_value))
+        }
+    }
+
+    /// Gets the index of the user's operand which this use occupies.
+    // REVIEW: The C API doesn't expose Use::getOperandNo, so we have to
+    // search the user's operands for this use
+    pub fn get_operand_index(&self) -> u32 {
+        let user = unsafe {
+            LLVMGetUser(self.use_)
+        };
+        let num_operands = unsafe {
+            LLVMGetNumOperands(user) as u32
+        };
+
+        (0..num_operands).find(|&index| unsafe { LLVMGetOperandUse(user, index) } == self.use_)
+                         .expect("A use should always be one of its user's operands")
+    }
+}
+
+/// An iterator over the uses of a value, starting with the most recently added one.
+#[derive(Debug)]
+pub struct BasicValueUseIter<'ctx: 'm, 'm> {
+    next_use: Option<BasicValueUse<'ctx, 'm>>,
+}
+
+impl<'ctx, 'm> BasicValueUseIter<'ctx, 'm> {
+    pub(crate) fn new(first_use: Option<BasicValueUse<'ctx, 'm>>) -> Self {
+        BasicValueUseIter {
+            next_use: first_use,
+        }
+    }
+}
+
+impl<'ctx, 'm> Iterator for BasicValueUseIter<'ctx, 'm> {
+    type Item = BasicValueUse<'ctx, 'm>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let use_ = self.next_use?;
+
+        self.next_use = use_.get_next_use();
+
+        Some(use_)
+    }
+}
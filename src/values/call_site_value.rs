@@ -0,0 +1,249 @@
+use either::Either;
+use llvm_sys::core::{LLVMIsTailCall, LLVMSetTailCall, LLVMGetTypeKind, LLVMTypeOf, LLVMSetInstructionCallConv, LLVMGetInstructionCallConv, LLVMSetInstrParamAlignment};
+use llvm_sys::prelude::LLVMValueRef;
+use llvm_sys::LLVMTypeKind;
+
+use std::ffi::CStr;
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use std::mem::forget;
+
+use CallingConvention;
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use attributes::Attribute;
+use attributes::{AttributeKind, AttributeLoc};
+use support::LLVMString;
+use values::traits::AsValueRef;
+use values::{BasicValueEnum, BasicValueUse, BasicValueUseIter, InstructionValue, Value};
+
+/// A value resulting from a call or invoke instruction.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CallSiteValue<'ctx: 'm, 'm> {
+    call_site_value: Value<'ctx, 'm>,
+}
+
+impl<'ctx, 'm> CallSiteValue<'ctx, 'm> {
+    pub(crate) fn new(value: LLVMValueRef) -> Self {
+        assert!(!value.is_null());
+
+        CallSiteValue {
+            call_site_value: Value::new(value),
+        }
+    }
+
+    /// Sets whether this call is marked `tail`, a hint that the callee doesn't access
+    /// the caller's stack, so the call may be turned into a jump.
+    ///
+    /// The C API of the supported LLVM versions has no way to mark a call `musttail`
+    /// or `notail`, so a tail call can't be guaranteed or forbidden.
+    // SubTypes: Only applies to call instructions
+    pub fn set_tail_call(&self, tail_call: bool) {
+        unsafe {
+            LLVMSetTailCall(self.as_value_ref(), tail_call as i32)
+        }
+    }
+
+    // SubTypes: Only applies to call instructions
+    pub fn is_tail_call(&self) -> bool {
+        unsafe {
+            LLVMIsTailCall(self.as_value_ref()) == 1
+        }
+    }
+
+    /// Returns the value produced by the call, or the call instruction itself
+    /// if the callee returns void.
+    pub fn try_as_basic_value(&self) -> Either<BasicValueEnum<'ctx, 'm>, InstructionValue<'ctx, 'm>> {
+        unsafe {
+            match LLVMGetTypeKind(LLVMTypeOf(self.as_value_ref())) {
+                LLVMTypeKind::LLVMVoidTypeKind => Either::Right(InstructionValue::new(self.as_value_ref())),
+                _ => Either::Left(BasicValueEnum::new(self.as_value_ref())),
+            }
+        }
+    }
+
+    pub fn get_call_convention(&self) -> CallingConvention {
+        let call_convention = unsafe {
+            LLVMGetInstructionCallConv(self.as_value_ref())
+        };
+
+        CallingConvention::new(call_convention)
+    }
+
+    pub fn set_call_convention(&self, call_convention: CallingConvention) {
+        unsafe {
+            LLVMSetInstructionCallConv(self.as_value_ref(), call_convention.as_llvm_call_conv())
+        }
+    }
+
+    // REVIEW: Index 0 is the return value, so arguments start at 1
+    pub fn set_param_alignment(&self, index: u32, alignment: u32) {
+        unsafe {
+            LLVMSetInstrParamAlignment(self.as_value_ref(), index, alignment)
+        }
+    }
+
+    pub fn get_name(&self) -> &CStr {
+        self.call_site_value.get_name()
+    }
+
+    pub fn print_to_string(&self) -> LLVMString {
+        self.call_site_value.print_to_string()
+    }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.call_site_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.call_site_value.uses()
+    }
+
+    pub fn print_to_stderr(&self) {
+        self.call_site_value.print_to_stderr()
+    }
+
+    // SubType: -> InstructionValue<Call | Invoke>
+    pub fn as_instruction(&self) -> InstructionValue<'ctx, 'm> {
+        self.call_site_value.as_instruction().expect("CallSiteValue should always be a Call or Invoke InstructionValue")
+    }
+
+    /// Adds an attribute of the given kind.
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    pub fn add_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) {
+        use llvm_sys::core::LLVMAddInstrAttribute;
+
+        unsafe {
+            LLVMAddInstrAttribute(self.as_value_ref(), loc.get_index(), kind.as_llvm_attribute())
+        }
+    }
+
+    /// Adds an attribute of the given kind.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn add_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) {
+        use llvm_sys::core::{LLVMCreateEnumAttribute, LLVMGetTypeContext};
+
+        let attribute = unsafe {
+            LLVMCreateEnumAttribute(LLVMGetTypeContext(LLVMTypeOf(self.as_value_ref())), kind.get_kind_id(), kind.get_value())
+        };
+
+        self.add_attribute(loc, Attribute::new(attribute))
+    }
+
+    // REVIEW: LLVM 3.7 and 3.8 can't look up the attributes of a call site, so there is
+    // no has_attribute_kind until LLVM 3.9
+    /// Checks whether an attribute of the given kind is applied, with the same value in the
+    /// case of `AttributeKind::Dereferenceable`.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn has_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) -> bool {
+        let attribute = self.get_enum_attribute(loc, kind.get_kind_id());
+
+        attribute.map_or(false, |attribute| attribute.get_enum_value() == kind.get_value())
+    }
+
+    /// Removes the attribute of the given kind, whatever its value.
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    pub fn remove_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) {
+        use llvm_sys::core::LLVMRemoveInstrAttribute;
+
+        unsafe {
+            LLVMRemoveInstrAttribute(self.as_value_ref(), loc.get_index(), kind.as_llvm_attribute())
+        }
+    }
+
+    /// Removes the attribute of the given kind, whatever its value.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn remove_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) {
+        self.remove_enum_attribute(loc, kind.get_kind_id())
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn add_attribute(&self, loc: AttributeLoc, attribute: Attribute) {
+        use llvm_sys::core::LLVMAddCallSiteAttribute;
+
+        unsafe {
+            LLVMAddCallSiteAttribute(self.as_value_ref(), loc.get_index(), attribute.attribute)
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn count_attributes(&self, loc: AttributeLoc) -> u32 {
+        use llvm_sys::core::LLVMGetCallSiteAttributeCount;
+
+        unsafe {
+            LLVMGetCallSiteAttributeCount(self.as_value_ref(), loc.get_index())
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn attributes(&self, loc: AttributeLoc) -> Vec<Attribute> {
+        use llvm_sys::core::LLVMGetCallSiteAttributes;
+        use llvm_sys::prelude::LLVMAttributeRef;
+
+        let count = self.count_attributes(loc);
+        let mut raw_vec: Vec<LLVMAttributeRef> = Vec::with_capacity(count as usize);
+        let ptr = raw_vec.as_mut_ptr();
+
+        forget(raw_vec);
+
+        let raw_vec = unsafe {
+            LLVMGetCallSiteAttributes(self.as_value_ref(), loc.get_index(), ptr);
+
+            Vec::from_raw_parts(ptr, count as usize, count as usize)
+        };
+
+        raw_vec.iter().map(|val| Attribute::new(*val)).collect()
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_enum_attribute(&self, loc: AttributeLoc, kind_id: u32) -> Option<Attribute> {
+        use llvm_sys::core::LLVMGetCallSiteEnumAttribute;
+
+        let attribute = unsafe {
+            LLVMGetCallSiteEnumAttribute(self.as_value_ref(), loc.get_index(), kind_id)
+        };
+
+        if attribute.is_null() {
+            return None;
+        }
+
+        Some(Attribute::new(attribute))
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_string_attribute(&self, loc: AttributeLoc, key: &str) -> Option<Attribute> {
+        use llvm_sys::core::LLVMGetCallSiteStringAttribute;
+
+        let attribute = unsafe {
+            LLVMGetCallSiteStringAttribute(self.as_value_ref(), loc.get_index(), key.as_ptr() as *const _, key.len() as u32)
+        };
+
+        if attribute.is_null() {
+            return None;
+        }
+
+        Some(Attribute::new(attribute))
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn remove_enum_attribute(&self, loc: AttributeLoc, kind_id: u32) {
+        use llvm_sys::core::LLVMRemoveCallSiteEnumAttribute;
+
+        unsafe {
+            LLVMRemoveCallSiteEnumAttribute(self.as_value_ref(), loc.get_index(), kind_id)
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn remove_string_attribute(&self, loc: AttributeLoc, key: &str) {
+        use llvm_sys::core::LLVMRemoveCallSiteStringAttribute;
+
+        unsafe {
+            LLVMRemoveCallSiteStringAttribute(self.as_value_ref(), loc.get_index(), key.as_ptr() as *const _, key.len() as u32)
+        }
+    }
+}
+
+impl<'ctx, 'm> AsValueRef for CallSiteValue<'ctx, 'm> {
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.call_site_value.value
+    }
+}
@@ -1,4 +1,4 @@
-use llvm_sys::core::{LLVMTypeOf, LLVMGetTypeKind};
+use llvm_sys::core::{LLVMTypeOf, LLVMGetTypeKind, LLVMIsAInstruction};
 use llvm_sys::LLVMTypeKind;
 use llvm_sys::prelude::LLVMValueRef;
 
@@ -8,14 +8,14 @@ use values::{IntValue, FunctionValue, PointerValue, VectorValue, ArrayValue, Str
 
 macro_rules! enum_value_set {
     ($enum_name:ident: $($args:ident),*) => (
-        #[derive(Debug, EnumAsGetters, EnumIntoGetters, EnumIsA, Clone, Copy)]
-        pub enum $enum_name {
+        #[derive(Debug, Clone, Copy)]
+        pub enum $enum_name<'ctx: 'm, 'm> {
             $(
-                $args($args),
+                $args($args<'ctx, 'm>),
             )*
         }
 
-        impl AsValueRef for $enum_name {
+        impl<'ctx, 'm> AsValueRef for $enum_name<'ctx, 'm> {
             fn as_value_ref(&self) -> LLVMValueRef {
                 match *self {
                     $(
@@ -26,8 +26,8 @@ macro_rules! enum_value_set {
         }
 
         $(
-            impl From<$args> for $enum_name {
-                fn from(value: $args) -> $enum_name {
+            impl<'ctx, 'm> From<$args<'ctx, 'm>> for $enum_name<'ctx, 'm> {
+                fn from(value: $args<'ctx, 'm>) -> $enum_name<'ctx, 'm> {
                     $enum_name::$args(value)
                 }
             }
@@ -38,13 +38,80 @@ macro_rules! enum_value_set {
     );
 }
 
+// The is_*, as_* and into_* getters are implemented by hand as derives for
+// them do not support generic (ie lifetime parameterized) enums
+macro_rules! enum_value_getters {
+    ($enum_name:ident: $($args:ident: $is_a:ident, $as_a:ident, $into_a:ident;)*) => (
+        impl<'ctx, 'm> $enum_name<'ctx, 'm> {
+            $(
+                pub fn $is_a(&self) -> bool {
+                    if let $enum_name::$args(_) = *self {
+                        true
+                    } else {
+                        false
+                    }
+                }
+
+                pub fn $as_a(&self) -> &$args<'ctx, 'm> {
+                    if let $enum_name::$args(ref v) = *self {
+                        v
+                    } else {
+                        panic!(concat!("called ", stringify!($as_a), "() on {:?}"), self);
+                    }
+                }
+
+                pub fn $into_a(self) -> $args<'ctx, 'm> {
+                    if let $enum_name::$args(v) = self {
+                        v
+                    } else {
+                        panic!(concat!("called ", stringify!($into_a), "() on {:?}"), self);
+                    }
+                }
+            )*
+        }
+    );
+}
+
 enum_value_set! {AggregateValueEnum: ArrayValue, StructValue}
-enum_value_set! {AnyValueEnum: ArrayValue, IntValue, FloatValue, PhiValue, FunctionValue, PointerValue, StructValue, VectorValue}
+enum_value_set! {AnyValueEnum: ArrayValue, IntValue, FloatValue, PhiValue, FunctionValue, PointerValue, StructValue, VectorValue, InstructionValue}
 enum_value_set! {BasicValueEnum: ArrayValue, IntValue, FloatValue, PointerValue, StructValue, VectorValue}
 enum_value_set! {BasicMetadataValueEnum: ArrayValue, IntValue, FloatValue, PointerValue, StructValue, VectorValue, MetadataValue}
 
-impl AnyValueEnum {
-    pub(crate) fn new(value: LLVMValueRef) -> AnyValueEnum {
+enum_value_getters! {AggregateValueEnum:
+    ArrayValue: is_array_value, as_array_value, into_array_value;
+    StructValue: is_struct_value, as_struct_value, into_struct_value;
+}
+enum_value_getters! {AnyValueEnum:
+    ArrayValue: is_array_value, as_array_value, into_array_value;
+    IntValue: is_int_value, as_int_value, into_int_value;
+    FloatValue: is_float_value, as_float_value, into_float_value;
+    PhiValue: is_phi_value, as_phi_value, into_phi_value;
+    FunctionValue: is_function_value, as_function_value, into_function_value;
+    PointerValue: is_pointer_value, as_pointer_value, into_pointer_value;
+    StructValue: is_struct_value, as_struct_value, into_struct_value;
+    VectorValue: is_vector_value, as_vector_value, into_vector_value;
+    InstructionValue: is_instruction_value, as_instruction_value, into_instruction_value;
+}
+enum_value_getters! {BasicValueEnum:
+    ArrayValue: is_array_value, as_array_value, into_array_value;
+    IntValue: is_int_value, as_int_value, into_int_value;
+    FloatValue: is_float_value, as_float_value, into_float_value;
+    PointerValue: is_pointer_value, as_pointer_value, into_pointer_value;
+    StructValue: is_struct_value, as_struct_value, into_struct_value;
+    VectorValue: is_vector_value, as_vector_value, into_vector_value;
+}
+enum_value_getters! {BasicMetadataValueEnum:
+    ArrayValue: is_array_value, as_array_value, into_array_value;
+    IntValue: is_int_value, as_int_value, into_int_value;
+    FloatValue: is_float_value, as_float_value, into_float_value;
+    PointerValue: is_pointer_value, as_pointer_value, into_pointer_value;
+    StructValue: is_struct_value, as_struct_value, into_struct_value;
+    VectorValue: is_vector_value, as_vector_value, into_vector_value;
+    MetadataValue: is_metadata_value, as_metadata_value, into_metadata_value;
+}
+
+impl<'ctx, 'm> AnyValueEnum<'ctx, 'm> {
+    pub(crate) fn new(value: LLVMValueRef) -> AnyValueEnum<'ctx, 'm> {
         let type_kind = unsafe {
             LLVMGetTypeKind(LLVMTypeOf(value))
         };
@@ -62,13 +129,20 @@ impl AnyValueEnum {
             LLVMTypeKind::LLVMArrayTypeKind => AnyValueEnum::ArrayValue(ArrayValue::new(value)),
             LLVMTypeKind::LLVMVectorTypeKind => AnyValueEnum::VectorValue(VectorValue::new(value)),
             LLVMTypeKind::LLVMFunctionTypeKind => AnyValueEnum::FunctionValue(FunctionValue::new(value).unwrap()),
-            LLVMTypeKind::LLVMVoidTypeKind => panic!("Void values shouldn't exist."),
+            LLVMTypeKind::LLVMVoidTypeKind => {
+                // Void typed instructions such as stores and branches are the only void values
+                if unsafe { LLVMIsAInstruction(value) }.is_null() {
+                    panic!("Void values shouldn't exist except in instructions.");
+                }
+
+                AnyValueEnum::InstructionValue(InstructionValue::new(value))
+            },
             LLVMTypeKind::LLVMMetadataTypeKind => panic!("Metadata values are not supported as AnyValue's."),
             _ => panic!("The given type is not supported.")
         }
     }
 
-    pub fn get_type(&self) -> AnyTypeEnum {
+    pub fn get_type(&self) -> AnyTypeEnum<'ctx> {
         let type_ = unsafe {
             LLVMTypeOf(self.as_value_ref())
         };
@@ -77,8 +151,8 @@ impl AnyValueEnum {
     }
 }
 
-impl BasicValueEnum {
-    pub(crate) fn new(value: LLVMValueRef) -> BasicValueEnum {
+impl<'ctx, 'm> BasicValueEnum<'ctx, 'm> {
+    pub(crate) fn new(value: LLVMValueRef) -> BasicValueEnum<'ctx, 'm> {
         let type_kind = unsafe {
             LLVMGetTypeKind(LLVMTypeOf(value))
         };
@@ -99,7 +173,7 @@ impl BasicValueEnum {
         }
     }
 
-    pub fn get_type(&self) -> BasicTypeEnum {
+    pub fn get_type(&self) -> BasicTypeEnum<'ctx> {
         let type_ = unsafe {
             LLVMTypeOf(self.as_value_ref())
         };
@@ -107,7 +181,7 @@ impl BasicValueEnum {
         BasicTypeEnum::new(type_)
     }
 
-    pub fn as_instruction(&self) -> Option<InstructionValue> {
+    pub fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         match *self {
             BasicValueEnum::ArrayValue(ref val) => val.as_instruction(),
             BasicValueEnum::IntValue(ref val) => val.as_instruction(),
@@ -119,8 +193,8 @@ impl BasicValueEnum {
     }
 }
 
-impl AggregateValueEnum {
-    pub(crate) fn new(value: LLVMValueRef) -> AggregateValueEnum {
+impl<'ctx, 'm> AggregateValueEnum<'ctx, 'm> {
+    pub(crate) fn new(value: LLVMValueRef) -> AggregateValueEnum<'ctx, 'm> {
         let type_kind = unsafe {
             LLVMGetTypeKind(LLVMTypeOf(value))
         };
@@ -133,8 +207,8 @@ impl AggregateValueEnum {
     }
 }
 
-impl BasicMetadataValueEnum {
-    pub(crate) fn new(value: LLVMValueRef) -> BasicMetadataValueEnum {
+impl<'ctx, 'm> BasicMetadataValueEnum<'ctx, 'm> {
+    pub(crate) fn new(value: LLVMValueRef) -> BasicMetadataValueEnum<'ctx, 'm> {
         let type_kind = unsafe {
             LLVMGetTypeKind(LLVMTypeOf(value))
         };
@@ -4,16 +4,17 @@ use llvm_sys::prelude::LLVMValueRef;
 use std::ffi::CStr;
 
 use FloatPredicate;
+use support::LLVMString;
 use types::{AsTypeRef, FloatType, IntType};
 use values::traits::AsValueRef;
-use values::{InstructionValue, IntValue, Value, MetadataValue};
+use values::{InstructionValue, IntValue, Value, MetadataValue, BasicValueUse, BasicValueUseIter};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct FloatValue {
-    float_value: Value
+pub struct FloatValue<'ctx: 'm, 'm> {
+    float_value: Value<'ctx, 'm>
 }
 
-impl FloatValue {
+impl<'ctx, 'm> FloatValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -30,7 +31,7 @@ impl FloatValue {
         self.float_value.set_name(name);
     }
 
-    pub fn get_type(&self) -> FloatType {
+    pub fn get_type(&self) -> FloatType<'ctx> {
         FloatType::new(self.float_value.get_type())
     }
 
@@ -42,7 +43,7 @@ impl FloatValue {
         self.float_value.is_undef()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.float_value.print_to_string()
     }
 
@@ -50,7 +51,7 @@ impl FloatValue {
         self.float_value.print_to_stderr()
     }
 
-    pub fn as_instruction(&self) -> Option<InstructionValue> {
+    pub fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         self.float_value.as_instruction()
     }
 
@@ -63,7 +64,7 @@ impl FloatValue {
     }
 
     // TODO: operator overloading to call this
-    pub fn const_add(&self, rhs: &FloatValue) -> Self {
+    pub fn const_add(&self, rhs: &FloatValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstFAdd(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -72,7 +73,7 @@ impl FloatValue {
     }
 
     // TODO: operator overloading to call this
-    pub fn const_sub(&self, rhs: &FloatValue) -> Self {
+    pub fn const_sub(&self, rhs: &FloatValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstFSub(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -81,7 +82,7 @@ impl FloatValue {
     }
 
     // TODO: operator overloading to call this
-    pub fn const_mul(&self, rhs: &FloatValue) -> Self {
+    pub fn const_mul(&self, rhs: &FloatValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstFMul(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -90,7 +91,7 @@ impl FloatValue {
     }
 
     // TODO: operator overloading to call this
-    pub fn const_div(&self, rhs: &FloatValue) -> Self {
+    pub fn const_div(&self, rhs: &FloatValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstFDiv(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -98,7 +99,7 @@ impl FloatValue {
         FloatValue::new(value)
     }
 
-    pub fn const_remainder(&self, rhs: &FloatValue) -> Self {
+    pub fn const_remainder(&self, rhs: &FloatValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstFRem(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -106,7 +107,7 @@ impl FloatValue {
         FloatValue::new(value)
     }
 
-    pub fn const_cast(&self, float_type: &FloatType) -> Self {
+    pub fn const_cast(&self, float_type: &FloatType<'ctx>) -> Self {
         let value = unsafe {
             LLVMConstFPCast(self.as_value_ref(), float_type.as_type_ref())
         };
@@ -114,7 +115,7 @@ impl FloatValue {
         FloatValue::new(value)
     }
 
-    pub fn const_to_unsigned_int(&self, int_type: &IntType) -> IntValue {
+    pub fn const_to_unsigned_int(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstFPToUI(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -122,7 +123,7 @@ impl FloatValue {
         IntValue::new(value)
     }
 
-    pub fn const_to_signed_int(&self, int_type: &IntType) -> IntValue {
+    pub fn const_to_signed_int(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstFPToSI(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -130,7 +131,7 @@ impl FloatValue {
         IntValue::new(value)
     }
 
-    pub fn const_truncate(&self, float_type: &FloatType) -> FloatValue {
+    pub fn const_truncate(&self, float_type: &FloatType<'ctx>) -> FloatValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstFPTrunc(self.as_value_ref(), float_type.as_type_ref())
         };
@@ -138,7 +139,7 @@ impl FloatValue {
         FloatValue::new(value)
     }
 
-    pub fn const_extend(&self, float_type: &FloatType) -> FloatValue {
+    pub fn const_extend(&self, float_type: &FloatType<'ctx>) -> FloatValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstFPExt(self.as_value_ref(), float_type.as_type_ref())
         };
@@ -150,16 +151,16 @@ impl FloatValue {
         self.float_value.has_metadata()
     }
 
-    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         self.float_value.get_metadata(kind_id)
     }
 
-    pub fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         self.float_value.set_metadata(metadata, kind_id)
     }
 
     // SubType: rhs same as lhs; return IntValue<bool>
-    pub fn const_compare(&self, op: FloatPredicate, rhs: &FloatValue) -> IntValue {
+    pub fn const_compare(&self, op: FloatPredicate, rhs: &FloatValue<'ctx, 'm>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstFCmp(op.as_llvm_predicate(), self.as_value_ref(), rhs.as_value_ref())
         };
@@ -167,12 +168,20 @@ impl FloatValue {
         IntValue::new(value)
     }
 
-    pub fn replace_all_uses_with(&self, other: &FloatValue) {
+    pub fn replace_all_uses_with(&self, other: &FloatValue<'ctx, 'm>) {
         self.float_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.float_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.float_value.uses()
+    }
 }
 
-impl AsValueRef for FloatValue {
+impl<'ctx, 'm> AsValueRef for FloatValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.float_value.value
     }
@@ -1,23 +1,30 @@
 use llvm_sys::analysis::{LLVMVerifierFailureAction, LLVMVerifyFunction, LLVMViewFunctionCFG, LLVMViewFunctionCFGOnly};
-use llvm_sys::core::{LLVMIsAFunction, LLVMIsConstant, LLVMGetLinkage, LLVMTypeOf, LLVMGetPreviousFunction, LLVMGetNextFunction, LLVMGetParam, LLVMCountParams, LLVMGetLastParam, LLVMCountBasicBlocks, LLVMGetFirstParam, LLVMGetNextParam, LLVMGetBasicBlocks, LLVMGetReturnType, LLVMAppendBasicBlock, LLVMDeleteFunction, LLVMGetElementType, LLVMGetLastBasicBlock, LLVMGetFirstBasicBlock, LLVMGetEntryBasicBlock, LLVMGetPersonalityFn, LLVMSetPersonalityFn, LLVMGetIntrinsicID, LLVMGetFunctionCallConv, LLVMSetFunctionCallConv, LLVMGetGC, LLVMSetGC};
+use llvm_sys::core::{LLVMIsAFunction, LLVMIsConstant, LLVMGetLinkage, LLVMTypeOf, LLVMGetPreviousFunction, LLVMGetNextFunction, LLVMGetParam, LLVMCountParams, LLVMGetLastParam, LLVMCountBasicBlocks, LLVMGetFirstParam, LLVMGetNextParam, LLVMGetBasicBlocks, LLVMGetReturnType, LLVMAppendBasicBlockInContext, LLVMGetTypeContext, LLVMDeleteFunction, LLVMGetElementType, LLVMGetLastBasicBlock, LLVMGetFirstBasicBlock, LLVMGetNextBasicBlock, LLVMGetPreviousBasicBlock, LLVMGetEntryBasicBlock, LLVMGetPersonalityFn, LLVMSetPersonalityFn, LLVMGetIntrinsicID, LLVMGetFunctionCallConv, LLVMSetFunctionCallConv, LLVMGetGC, LLVMSetGC, LLVMGetGlobalParent};
 use llvm_sys::prelude::{LLVMValueRef, LLVMBasicBlockRef};
 
 use std::ffi::{CStr, CString};
 use std::mem::forget;
 use std::fmt;
+use std::marker::PhantomData;
+use std::ptr;
 
+use {CallingConvention, ModuleMarker};
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use attributes::Attribute;
+use attributes::{AttributeKind, AttributeLoc};
 use basic_block::BasicBlock;
-use module::Linkage;
+use module::{Linkage, VerificationError, verify_module};
+use support::LLVMString;
 use types::{BasicTypeEnum, FunctionType};
 use values::traits::AsValueRef;
-use values::{BasicValueEnum, Value, MetadataValue};
+use values::{BasicValueEnum, Value, MetadataValue, BasicValueUse, BasicValueUseIter};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
-pub struct FunctionValue {
-    fn_value: Value,
+pub struct FunctionValue<'ctx: 'm, 'm> {
+    fn_value: Value<'ctx, 'm>,
 }
 
-impl FunctionValue {
+impl<'ctx, 'm> FunctionValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Option<Self> {
         if value.is_null() {
             return None;
@@ -46,7 +53,7 @@ impl FunctionValue {
         self.fn_value.is_undef()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.fn_value.print_to_string()
     }
 
@@ -54,23 +61,28 @@ impl FunctionValue {
         self.fn_value.print_to_stderr()
     }
 
-    // TODO: Maybe support LLVMAbortProcessAction?
-    // FIXME: Better error returns, code 1 is error
-    pub fn verify(&self, print: bool) -> bool {
-        let action = if print {
-            LLVMVerifierFailureAction::LLVMPrintMessageAction
-        } else {
-            LLVMVerifierFailureAction::LLVMReturnStatusAction
+    /// Ensures that this function is valid, returning a `VerificationError` if it is not.
+    // REVIEW: LLVM can only describe what is wrong with a whole module, so the message comes
+    // from verifying the parent module and may mention problems in other functions too
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        let code = unsafe {
+            LLVMVerifyFunction(self.fn_value.value, LLVMVerifierFailureAction::LLVMReturnStatusAction)
         };
 
-        let code = unsafe {
-            LLVMVerifyFunction(self.fn_value.value, action)
+        if code != 1 {
+            return Ok(());
+        }
+
+        let module = unsafe {
+            LLVMGetGlobalParent(self.as_value_ref())
         };
 
-        code != 1
+        match verify_module(module) {
+            Err(err) => Err(err),
+            Ok(()) => Err(VerificationError::new(format!("Function {:?} is invalid", self.get_name()))),
+        }
     }
 
-    // REVIEW: If there's a demand, could easily create a module.get_functions() -> Iterator
     pub fn get_next_function(&self) -> Option<Self> {
         let function = unsafe {
             LLVMGetNextFunction(self.as_value_ref())
@@ -87,7 +99,7 @@ impl FunctionValue {
         FunctionValue::new(function)
     }
 
-    pub fn get_first_param(&self) -> Option<BasicValueEnum> {
+    pub fn get_first_param(&self) -> Option<BasicValueEnum<'ctx, 'm>> {
         let param = unsafe {
             LLVMGetFirstParam(self.as_value_ref())
         };
@@ -99,7 +111,7 @@ impl FunctionValue {
         Some(BasicValueEnum::new(param))
     }
 
-    pub fn get_last_param(&self) -> Option<BasicValueEnum> {
+    pub fn get_last_param(&self) -> Option<BasicValueEnum<'ctx, 'm>> {
         let param = unsafe {
             LLVMGetLastParam(self.as_value_ref())
         };
@@ -115,7 +127,7 @@ impl FunctionValue {
     // that isn't actually a basic_block and seems to get corrupted
     // Should check filed LLVM bugs - maybe just return None since
     // we can catch it with "LLVMIsABasicBlock"
-    pub fn get_entry_basic_block(&self) -> Option<BasicBlock> {
+    pub fn get_entry_basic_block(&self) -> Option<BasicBlock<'ctx, 'm>> {
         let bb = unsafe {
             LLVMGetEntryBasicBlock(self.as_value_ref())
         };
@@ -127,7 +139,7 @@ impl FunctionValue {
     // that isn't actually a basic_block and seems to get corrupted
     // Should check filed LLVM bugs - maybe just return None since
     // we can catch it with "LLVMIsABasicBlock"
-    pub fn get_first_basic_block(&self) -> Option<BasicBlock> {
+    pub fn get_first_basic_block(&self) -> Option<BasicBlock<'ctx, 'm>> {
         let bb = unsafe {
             LLVMGetFirstBasicBlock(self.as_value_ref())
         };
@@ -135,19 +147,17 @@ impl FunctionValue {
         BasicBlock::new(bb)
     }
 
-    // TODOC: This applies the global context to the basic block. To keep the existing context
-    // prefer context.append_basic_block()
-    pub fn append_basic_block(&self, name: &str) -> BasicBlock {
+    pub fn append_basic_block(&self, name: &str) -> BasicBlock<'ctx, 'm> {
         let c_string = CString::new(name).expect("Conversion to CString failed unexpectedly");
 
         let bb = unsafe {
-            LLVMAppendBasicBlock(self.as_value_ref(), c_string.as_ptr())
+            LLVMAppendBasicBlockInContext(LLVMGetTypeContext(self.fn_value.get_type()), self.as_value_ref(), c_string.as_ptr())
         };
 
         BasicBlock::new(bb).expect("Appending basic block should never fail")
     }
 
-    pub fn get_nth_param(&self, nth: u32) -> Option<BasicValueEnum> {
+    pub fn get_nth_param(&self, nth: u32) -> Option<BasicValueEnum<'ctx, 'm>> {
         let count = self.count_params();
 
         if nth + 1 > count {
@@ -173,7 +183,7 @@ impl FunctionValue {
         }
     }
 
-    pub fn get_basic_blocks(&self) -> Vec<BasicBlock> {
+    pub fn get_basic_blocks(&self) -> Vec<BasicBlock<'ctx, 'm>> {
         let count = self.count_basic_blocks();
         let mut raw_vec: Vec<LLVMBasicBlockRef> = Vec::with_capacity(count as usize);
         let ptr = raw_vec.as_mut_ptr();
@@ -189,7 +199,7 @@ impl FunctionValue {
         raw_vec.iter().map(|val| BasicBlock::new(*val).unwrap()).collect()
     }
 
-    pub fn get_return_type(&self) -> BasicTypeEnum {
+    pub fn get_return_type(&self) -> BasicTypeEnum<'ctx> {
         let type_ = unsafe {
             LLVMGetReturnType(LLVMGetElementType(LLVMTypeOf(self.fn_value.value)))
         };
@@ -197,14 +207,15 @@ impl FunctionValue {
         BasicTypeEnum::new(type_)
     }
 
-    pub fn params(&self) -> ParamValueIter {
+    pub fn params(&self) -> ParamValueIter<'ctx, 'm> {
         ParamValueIter {
             param_iter_value: self.fn_value.value,
             start: true,
+            _marker: PhantomData,
         }
     }
 
-    pub fn get_last_basic_block(&self) -> Option<BasicBlock> {
+    pub fn get_last_basic_block(&self) -> Option<BasicBlock<'ctx, 'm>> {
         let bb = unsafe {
             LLVMGetLastBasicBlock(self.fn_value.value)
         };
@@ -212,6 +223,20 @@ impl FunctionValue {
         BasicBlock::new(bb)
     }
 
+    /// Gets an iterator over the `BasicBlock`s of this function, in layout
+    /// order. Unlike `get_basic_blocks`, this does not allocate.
+    pub fn basic_blocks(&self) -> BasicBlockIter<'ctx, 'm> {
+        let (first, last) = unsafe {
+            (LLVMGetFirstBasicBlock(self.as_value_ref()), LLVMGetLastBasicBlock(self.as_value_ref()))
+        };
+
+        BasicBlockIter {
+            front: first,
+            back: last,
+            _marker: PhantomData,
+        }
+    }
+
     pub fn get_name(&self) -> &CStr {
         self.fn_value.get_name()
     }
@@ -235,31 +260,38 @@ impl FunctionValue {
         // }
     }
 
-    pub fn get_type(&self) -> FunctionType {
-        FunctionType::new(self.fn_value.get_type())
+    pub fn get_type(&self) -> FunctionType<'ctx> {
+        // A function's value type is a pointer to its FunctionType
+        let fn_type = unsafe {
+            LLVMGetElementType(self.fn_value.get_type())
+        };
+
+        FunctionType::new(fn_type)
     }
 
     pub fn has_metadata(&self) -> bool {
         self.fn_value.has_metadata()
     }
 
-    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         self.fn_value.get_metadata(kind_id)
     }
 
-    pub fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         self.fn_value.set_metadata(metadata, kind_id)
     }
 
     // TODOC: How this works as an exception handler
-    // TODO: LLVM 3.9+
-    // pub fn has_personality_function(&self) -> bool {
-    //     unsafe {
-    //         LLVMHasPersonalityFunction(self.as_value_ref())
-    //     }
-    // }
-
-    pub fn get_personality_function(&self) -> Option<FunctionValue> {
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn has_personality_function(&self) -> bool {
+        use llvm_sys::core::LLVMHasPersonalityFn;
+
+        unsafe {
+            LLVMHasPersonalityFn(self.as_value_ref()) == 1
+        }
+    }
+
+    pub fn get_personality_function(&self) -> Option<FunctionValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetPersonalityFn(self.as_value_ref())
         };
@@ -267,7 +299,7 @@ impl FunctionValue {
         FunctionValue::new(value)
     }
 
-    pub fn set_personality_function(&self, personality_fn: &FunctionValue) {
+    pub fn set_personality_function(&self, personality_fn: &FunctionValue<'ctx, 'm>) {
         unsafe {
             LLVMSetPersonalityFn(self.as_value_ref(), personality_fn.as_value_ref())
         }
@@ -279,15 +311,17 @@ impl FunctionValue {
         }
     }
 
-    pub fn get_call_conventions(&self) -> u32 {
-        unsafe {
+    pub fn get_call_conventions(&self) -> CallingConvention {
+        let call_conventions = unsafe {
             LLVMGetFunctionCallConv(self.as_value_ref())
-        }
+        };
+
+        CallingConvention::new(call_conventions)
     }
 
-    pub fn set_call_conventions(&self, call_conventions: u32) {
+    pub fn set_call_conventions(&self, call_conventions: CallingConvention) {
         unsafe {
-            LLVMSetFunctionCallConv(self.as_value_ref(), call_conventions)
+            LLVMSetFunctionCallConv(self.as_value_ref(), call_conventions.as_llvm_call_conv())
         }
     }
 
@@ -305,18 +339,227 @@ impl FunctionValue {
         }
     }
 
-    pub fn replace_all_uses_with(&self, other: &FunctionValue) {
+    pub fn replace_all_uses_with(&self, other: &FunctionValue<'ctx, 'm>) {
         self.fn_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.fn_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.fn_value.uses()
+    }
+
+    /// Adds an attribute of the given kind. Before LLVM 3.9, the C API has no way to apply
+    /// attributes to the return value of a function (only to the return value of a
+    /// `CallSiteValue`), so this errors for `AttributeLoc::Return` there.
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    pub fn add_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) -> Result<(), String> {
+        use llvm_sys::core::{LLVMAddAttribute, LLVMAddFunctionAttr};
+
+        match loc {
+            AttributeLoc::Return => return Err("Return value attributes can only be applied to functions from LLVM 3.9".to_string()),
+            AttributeLoc::Param(index) => unsafe {
+                LLVMAddAttribute(self.get_raw_param(index), kind.as_llvm_attribute())
+            },
+            AttributeLoc::Function => unsafe {
+                LLVMAddFunctionAttr(self.as_value_ref(), kind.as_llvm_attribute())
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Adds an attribute of the given kind. Before LLVM 3.9, the C API has no way to apply
+    /// attributes to the return value of a function (only to the return value of a
+    /// `CallSiteValue`), so this errors for `AttributeLoc::Return` there.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn add_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) -> Result<(), String> {
+        use llvm_sys::core::{LLVMCreateEnumAttribute, LLVMGetTypeContext};
+
+        let attribute = unsafe {
+            LLVMCreateEnumAttribute(LLVMGetTypeContext(self.fn_value.get_type()), kind.get_kind_id(), kind.get_value())
+        };
+
+        self.add_attribute(loc, Attribute::new(attribute));
+
+        Ok(())
+    }
+
+    /// Checks whether an attribute of the given kind is applied. Like `add_attribute_kind`,
+    /// this errors for `AttributeLoc::Return` before LLVM 3.9.
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    pub fn has_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) -> Result<bool, String> {
+        use llvm_sys::core::{LLVMGetAttribute, LLVMGetFunctionAttr};
+
+        let attributes = match loc {
+            AttributeLoc::Return => return Err("Return value attributes can only be applied to functions from LLVM 3.9".to_string()),
+            AttributeLoc::Param(index) => unsafe {
+                LLVMGetAttribute(self.get_raw_param(index))
+            },
+            AttributeLoc::Function => unsafe {
+                LLVMGetFunctionAttr(self.as_value_ref())
+            },
+        };
+
+        Ok(attributes.contains(kind.as_llvm_attribute()))
+    }
+
+    /// Checks whether an attribute of the given kind is applied, with the same value in the
+    /// case of `AttributeKind::Dereferenceable`. Like `add_attribute_kind`, this errors for
+    /// `AttributeLoc::Return` before LLVM 3.9.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn has_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) -> Result<bool, String> {
+        let attribute = self.get_enum_attribute(loc, kind.get_kind_id());
+
+        Ok(attribute.map_or(false, |attribute| attribute.get_enum_value() == kind.get_value()))
+    }
+
+    /// Removes the attribute of the given kind, whatever its value. Like `add_attribute_kind`,
+    /// this errors for `AttributeLoc::Return` before LLVM 3.9.
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    pub fn remove_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) -> Result<(), String> {
+        use llvm_sys::core::{LLVMRemoveAttribute, LLVMRemoveFunctionAttr};
+
+        match loc {
+            AttributeLoc::Return => return Err("Return value attributes can only be applied to functions from LLVM 3.9".to_string()),
+            AttributeLoc::Param(index) => unsafe {
+                LLVMRemoveAttribute(self.get_raw_param(index), kind.as_llvm_attribute())
+            },
+            AttributeLoc::Function => unsafe {
+                LLVMRemoveFunctionAttr(self.as_value_ref(), kind.as_llvm_attribute())
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Removes the attribute of the given kind, whatever its value. Like `add_attribute_kind`,
+    /// this errors for `AttributeLoc::Return` before LLVM 3.9.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn remove_attribute_kind(&self, loc: AttributeLoc, kind: AttributeKind) -> Result<(), String> {
+        self.remove_enum_attribute(loc, kind.get_kind_id());
+
+        Ok(())
+    }
+
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    fn get_raw_param(&self, index: u32) -> LLVMValueRef {
+        assert!(index < self.count_params(), "Param index out of range");
+
+        unsafe {
+            LLVMGetParam(self.as_value_ref(), index)
+        }
+    }
+
+    /// Adds a target dependent string attribute, such as `"no-frame-pointer-elim"="true"`,
+    /// to the function itself.
+    pub fn add_target_dependent_attribute(&self, key: &str, val: &str) {
+        use llvm_sys::core::LLVMAddTargetDependentFunctionAttr;
+
+        let c_key = CString::new(key).expect("Conversion to CString failed unexpectedly");
+        let c_val = CString::new(val).expect("Conversion to CString failed unexpectedly");
+
+        unsafe {
+            LLVMAddTargetDependentFunctionAttr(self.as_value_ref(), c_key.as_ptr(), c_val.as_ptr())
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn add_attribute(&self, loc: AttributeLoc, attribute: Attribute) {
+        use llvm_sys::core::LLVMAddAttributeAtIndex;
+
+        unsafe {
+            LLVMAddAttributeAtIndex(self.as_value_ref(), loc.get_index(), attribute.attribute)
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn count_attributes(&self, loc: AttributeLoc) -> u32 {
+        use llvm_sys::core::LLVMGetAttributeCountAtIndex;
+
+        unsafe {
+            LLVMGetAttributeCountAtIndex(self.as_value_ref(), loc.get_index())
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn attributes(&self, loc: AttributeLoc) -> Vec<Attribute> {
+        use llvm_sys::core::LLVMGetAttributesAtIndex;
+        use llvm_sys::prelude::LLVMAttributeRef;
+
+        let count = self.count_attributes(loc);
+        let mut raw_vec: Vec<LLVMAttributeRef> = Vec::with_capacity(count as usize);
+        let ptr = raw_vec.as_mut_ptr();
+
+        forget(raw_vec);
+
+        let raw_vec = unsafe {
+            LLVMGetAttributesAtIndex(self.as_value_ref(), loc.get_index(), ptr);
+
+            Vec::from_raw_parts(ptr, count as usize, count as usize)
+        };
+
+        raw_vec.iter().map(|val| Attribute::new(*val)).collect()
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_enum_attribute(&self, loc: AttributeLoc, kind_id: u32) -> Option<Attribute> {
+        use llvm_sys::core::LLVMGetEnumAttributeAtIndex;
+
+        let attribute = unsafe {
+            LLVMGetEnumAttributeAtIndex(self.as_value_ref(), loc.get_index(), kind_id)
+        };
+
+        if attribute.is_null() {
+            return None;
+        }
+
+        Some(Attribute::new(attribute))
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_string_attribute(&self, loc: AttributeLoc, key: &str) -> Option<Attribute> {
+        use llvm_sys::core::LLVMGetStringAttributeAtIndex;
+
+        let attribute = unsafe {
+            LLVMGetStringAttributeAtIndex(self.as_value_ref(), loc.get_index(), key.as_ptr() as *const _, key.len() as u32)
+        };
+
+        if attribute.is_null() {
+            return None;
+        }
+
+        Some(Attribute::new(attribute))
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn remove_enum_attribute(&self, loc: AttributeLoc, kind_id: u32) {
+        use llvm_sys::core::LLVMRemoveEnumAttributeAtIndex;
+
+        unsafe {
+            LLVMRemoveEnumAttributeAtIndex(self.as_value_ref(), loc.get_index(), kind_id)
+        }
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn remove_string_attribute(&self, loc: AttributeLoc, key: &str) {
+        use llvm_sys::core::LLVMRemoveStringAttributeAtIndex;
+
+        unsafe {
+            LLVMRemoveStringAttributeAtIndex(self.as_value_ref(), loc.get_index(), key.as_ptr() as *const _, key.len() as u32)
+        }
+    }
 }
 
-impl AsValueRef for FunctionValue {
+impl<'ctx, 'm> AsValueRef for FunctionValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.fn_value.value
     }
 }
 
-impl fmt::Debug for FunctionValue {
+impl<'ctx, 'm> fmt::Debug for FunctionValue<'ctx, 'm> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let llvm_value = self.print_to_string();
         let llvm_type = self.get_type();
@@ -330,13 +573,14 @@ impl fmt::Debug for FunctionValue {
     }
 }
 
-pub struct ParamValueIter {
+pub struct ParamValueIter<'ctx: 'm, 'm> {
     param_iter_value: LLVMValueRef,
     start: bool,
+    _marker: ModuleMarker<'ctx, 'm>,
 }
 
-impl Iterator for ParamValueIter {
-    type Item = BasicValueEnum;
+impl<'ctx, 'm> Iterator for ParamValueIter<'ctx, 'm> {
+    type Item = BasicValueEnum<'ctx, 'm>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.start {
@@ -368,3 +612,53 @@ impl Iterator for ParamValueIter {
         Some(BasicValueEnum::new(next_value))
     }
 }
+
+pub struct BasicBlockIter<'ctx: 'm, 'm> {
+    front: LLVMBasicBlockRef,
+    back: LLVMBasicBlockRef,
+    _marker: ModuleMarker<'ctx, 'm>,
+}
+
+impl<'ctx, 'm> Iterator for BasicBlockIter<'ctx, 'm> {
+    type Item = BasicBlock<'ctx, 'm>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front.is_null() {
+            return None;
+        }
+
+        let bb = self.front;
+
+        if bb == self.back {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.front = unsafe {
+                LLVMGetNextBasicBlock(bb)
+            };
+        }
+
+        BasicBlock::new(bb)
+    }
+}
+
+impl<'ctx, 'm> DoubleEndedIterator for BasicBlockIter<'ctx, 'm> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.back.is_null() {
+            return None;
+        }
+
+        let bb = self.back;
+
+        if bb == self.front {
+            self.front = ptr::null_mut();
+            self.back = ptr::null_mut();
+        } else {
+            self.back = unsafe {
+                LLVMGetPreviousBasicBlock(bb)
+            };
+        }
+
+        BasicBlock::new(bb)
+    }
+}
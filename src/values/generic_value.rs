@@ -3,17 +3,21 @@ use llvm_sys::execution_engine::{LLVMCreateGenericValueOfPointer, LLVMDisposeGen
 
 use types::{AsTypeRef, FloatType};
 
+use std::marker::PhantomData;
+
 // SubTypes: GenericValue<IntValue, FloatValue, or PointerValue>
-pub struct GenericValue {
+pub struct GenericValue<'ctx> {
     pub(crate) generic_value: LLVMGenericValueRef,
+    _marker: PhantomData<fn(&'ctx ()) -> &'ctx ()>,
 }
 
-impl GenericValue {
+impl<'ctx> GenericValue<'ctx> {
     pub(crate) fn new(generic_value: LLVMGenericValueRef) -> Self {
         assert!(!generic_value.is_null());
 
         GenericValue {
             generic_value,
+            _marker: PhantomData,
         }
     }
 
@@ -40,7 +44,7 @@ impl GenericValue {
     }
 
     // SubType: impl only for GenericValue<FloatValue>
-    pub fn as_float(&self, float_type: &FloatType) -> f64 {
+    pub fn as_float(&self, float_type: &FloatType<'ctx>) -> f64 {
         unsafe {
             LLVMGenericValueToFloat(float_type.as_type_ref(), self.generic_value)
         }
@@ -53,7 +57,7 @@ impl GenericValue {
     }
 }
 
-impl Drop for GenericValue {
+impl<'ctx> Drop for GenericValue<'ctx> {
     fn drop(&mut self) {
         unsafe {
             LLVMDisposeGenericValue(self.generic_value)
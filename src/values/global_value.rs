@@ -6,16 +6,16 @@ use std::ffi::{CString, CStr};
 
 use {GlobalVisibility, ThreadLocalMode, DLLStorageClass};
 use values::traits::AsValueRef;
-use values::{BasicValueEnum, BasicValue, PointerValue, Value};
+use values::{BasicValueEnum, BasicValue, BasicValueUse, BasicValueUseIter, PointerValue, Value};
 
 // REVIEW: GlobalValues are always PointerValues. With SubTypes, we should
 // compress this into a PointerValue<Global> type
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct GlobalValue {
-    global_value: Value,
+pub struct GlobalValue<'ctx: 'm, 'm> {
+    global_value: Value<'ctx, 'm>,
 }
 
-impl GlobalValue {
+impl<'ctx, 'm> GlobalValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -24,7 +24,7 @@ impl GlobalValue {
         }
     }
 
-    pub fn get_previous_global(&self) -> Option<GlobalValue> {
+    pub fn get_previous_global(&self) -> Option<GlobalValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetPreviousGlobal(self.as_value_ref())
         };
@@ -36,7 +36,7 @@ impl GlobalValue {
         Some(GlobalValue::new(value))
     }
 
-    pub fn get_next_global(&self) -> Option<GlobalValue> {
+    pub fn get_next_global(&self) -> Option<GlobalValue<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetNextGlobal(self.as_value_ref())
         };
@@ -62,7 +62,7 @@ impl GlobalValue {
         }
     }
 
-    pub fn get_initializer(&self) -> Option<BasicValueEnum> {
+    pub fn get_initializer(&self) -> Option<BasicValueEnum<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetInitializer(self.as_value_ref())
         };
@@ -75,7 +75,7 @@ impl GlobalValue {
     }
 
     // SubType: This input type should be tied to the BasicType
-    pub fn set_initializer(&self, value: &BasicValue) {
+    pub fn set_initializer(&self, value: &BasicValue<'ctx, 'm>) {
         unsafe {
             LLVMSetInitializer(self.as_value_ref(), value.as_value_ref())
         }
@@ -190,12 +190,20 @@ impl GlobalValue {
         LLVMDeleteGlobal(self.as_value_ref())
     }
 
-    pub fn as_pointer_value(&self) -> PointerValue {
+    pub fn as_pointer_value(&self) -> PointerValue<'ctx, 'm> {
         PointerValue::new(self.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.global_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.global_value.uses()
+    }
 }
 
-impl AsValueRef for GlobalValue {
+impl<'ctx, 'm> AsValueRef for GlobalValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.global_value.value
     }
@@ -1,10 +1,13 @@
-use llvm_sys::core::{LLVMGetInstructionOpcode, LLVMIsTailCall, LLVMGetPreviousInstruction, LLVMGetNextInstruction, LLVMGetInstructionParent, LLVMInstructionEraseFromParent, LLVMInstructionClone, LLVMSetVolatile, LLVMGetVolatile};
+use either::Either;
+use llvm_sys::core::{LLVMGetInstructionOpcode, LLVMGetNumOperands, LLVMGetOperand, LLVMSetOperand, LLVMGetOperandUse, LLVMIsABasicBlock, LLVMValueAsBasicBlock, LLVMIsATerminatorInst, LLVMGetNumSuccessors, LLVMGetSuccessor, LLVMSetSuccessor, LLVMIsTailCall, LLVMGetPreviousInstruction, LLVMGetNextInstruction, LLVMGetInstructionParent, LLVMInstructionEraseFromParent, LLVMInstructionClone, LLVMSetVolatile, LLVMGetVolatile, LLVMAddClause, LLVMSetCleanup};
 use llvm_sys::LLVMOpcode;
 use llvm_sys::prelude::LLVMValueRef;
 
 use basic_block::BasicBlock;
-use values::traits::AsValueRef;
-use values::Value;
+use values::traits::{AsValueRef, BasicValue};
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use values::BasicValueEnum;
+use values::{BasicMetadataValueEnum, BasicValueUse, BasicValueUseIter, MetadataValue, Value};
 
 // REVIEW: Split up into structs for SubTypes on InstructionValues?
 // REVIEW: This should maybe be split up into InstructionOpcode and ConstOpcode?
@@ -22,12 +25,16 @@ pub enum InstructionOpcode {
     BitCast,
     Br,
     Call,
-    // Later versions:
-    // CatchPad,
-    // CatchRet,
-    // CatchSwitch,
-    // CleanupPad,
-    // CleanupRet,
+    #[cfg(not(feature = "llvm3-7"))]
+    CatchPad,
+    #[cfg(not(feature = "llvm3-7"))]
+    CatchRet,
+    #[cfg(not(feature = "llvm3-7"))]
+    CatchSwitch,
+    #[cfg(not(feature = "llvm3-7"))]
+    CleanupPad,
+    #[cfg(not(feature = "llvm3-7"))]
+    CleanupRet,
     ExtractElement,
     ExtractValue,
     FAdd,
@@ -92,12 +99,16 @@ impl InstructionOpcode {
             LLVMOpcode::LLVMBitCast => InstructionOpcode::BitCast,
             LLVMOpcode::LLVMBr => InstructionOpcode::Br,
             LLVMOpcode::LLVMCall => InstructionOpcode::Call,
-            // Newer versions:
-            // LLVMOpcode::LLVMCatchPad => InstructionOpcode::CatchPad,
-            // LLVMOpcode::LLVMCatchRet => InstructionOpcode::CatchRet,
-            // LLVMOpcode::LLVMCatchSwitch => InstructionOpcode::CatchSwitch,
-            // LLVMOpcode::LLVMCleanupPad => InstructionOpcode::CleanupPad,
-            // LLVMOpcode::LLVMCleanupRet => InstructionOpcode::CleanupRet,
+            #[cfg(not(feature = "llvm3-7"))]
+            LLVMOpcode::LLVMCatchPad => InstructionOpcode::CatchPad,
+            #[cfg(not(feature = "llvm3-7"))]
+            LLVMOpcode::LLVMCatchRet => InstructionOpcode::CatchRet,
+            #[cfg(not(feature = "llvm3-7"))]
+            LLVMOpcode::LLVMCatchSwitch => InstructionOpcode::CatchSwitch,
+            #[cfg(not(feature = "llvm3-7"))]
+            LLVMOpcode::LLVMCleanupPad => InstructionOpcode::CleanupPad,
+            #[cfg(not(feature = "llvm3-7"))]
+            LLVMOpcode::LLVMCleanupRet => InstructionOpcode::CleanupRet,
             LLVMOpcode::LLVMExtractElement => InstructionOpcode::ExtractElement,
             LLVMOpcode::LLVMExtractValue => InstructionOpcode::ExtractValue,
             LLVMOpcode::LLVMFAdd => InstructionOpcode::FAdd,
@@ -152,11 +163,11 @@ impl InstructionOpcode {
 }
 
 #[derive(Debug, PartialEq, Eq, Copy)]
-pub struct InstructionValue {
-    instruction_value: Value,
+pub struct InstructionValue<'ctx: 'm, 'm> {
+    instruction_value: Value<'ctx, 'm>,
 }
 
-impl InstructionValue {
+impl<'ctx, 'm> InstructionValue<'ctx, 'm> {
     pub(crate) fn new(instruction_value: LLVMValueRef) -> Self {
         assert!(!instruction_value.is_null());
 
@@ -213,7 +224,7 @@ impl InstructionValue {
     // REVIEW: Should this *not* be an option? Parent should always exist,
     // but I doubt LLVM returns null if the parent BB (or grandparent FN)
     // was deleted... Invalid memory is more likely
-    pub fn get_parent(&self) -> Option<BasicBlock> {
+    pub fn get_parent(&self) -> Option<BasicBlock<'ctx, 'm>> {
         let value = unsafe {
             LLVMGetInstructionParent(self.as_value_ref())
         };
@@ -229,10 +240,179 @@ impl InstructionValue {
         }
     }
 
-    pub fn replace_all_uses_with(&self, other: &InstructionValue) {
+    pub fn replace_all_uses_with(&self, other: &InstructionValue<'ctx, 'm>) {
         self.instruction_value.replace_all_uses_with(other.as_value_ref())
     }
 
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.instruction_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.instruction_value.uses()
+    }
+
+    /// Gets the number of operands of this instruction, including any
+    /// `BasicBlock`s a terminator branches to.
+    pub fn get_num_operands(&self) -> u32 {
+        unsafe {
+            LLVMGetNumOperands(self.as_value_ref()) as u32
+        }
+    }
+
+    /// Gets the operand at the given index, which is a `BasicBlock` for the
+    /// destinations of a terminator and may be a `MetadataValue` for calls to
+    /// intrinsics such as `llvm.dbg.value`. Returns `None` if the index is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inkwell::AddressSpace;
+    /// use inkwell::context::Context;
+    ///
+    /// let context = Context::create();
+    /// let module = context.create_module("operands");
+    /// let builder = context.create_builder();
+    /// let void_type = context.void_type();
+    /// let f32_type = context.f32_type();
+    /// let f32_ptr_type = f32_type.ptr_type(AddressSpace::Generic);
+    /// let fn_type = void_type.fn_type(&[&f32_ptr_type], false);
+    ///
+    /// let function = module.add_function("take_f32_ptr", &fn_type, None);
+    /// let basic_block = context.append_basic_block(&function, "entry");
+    ///
+    /// builder.position_at_end(&basic_block);
+    ///
+    /// let arg1 = function.get_first_param().unwrap().into_pointer_value();
+    /// let f32_val = f32_type.const_float(1.0);
+    /// let store_instruction = builder.build_store(&arg1, &f32_val);
+    ///
+    /// assert_eq!(store_instruction.get_num_operands(), 2);
+    /// assert_eq!(store_instruction.get_operand(0).unwrap().left().unwrap().into_float_value(), f32_val);
+    /// assert_eq!(store_instruction.get_operand(1).unwrap().left().unwrap().into_pointer_value(), arg1);
+    /// assert!(store_instruction.get_operand(2).is_none());
+    /// ```
+    pub fn get_operand(&self, index: u32) -> Option<Either<BasicMetadataValueEnum<'ctx, 'm>, BasicBlock<'ctx, 'm>>> {
+        if index >= self.get_num_operands() {
+            return None;
+        }
+
+        let operand = unsafe {
+            LLVMGetOperand(self.as_value_ref(), index)
+        };
+
+        // REVIEW: Can an in range operand actually be null?
+        if operand.is_null() {
+            return None;
+        }
+
+        let is_basic_block = unsafe {
+            !LLVMIsABasicBlock(operand).is_null()
+        };
+
+        if is_basic_block {
+            let bb = unsafe {
+                LLVMValueAsBasicBlock(operand)
+            };
+
+            Some(Either::Right(BasicBlock::new(bb).expect("BasicBlock should always be valid")))
+        } else {
+            Some(Either::Left(BasicMetadataValueEnum::new(operand)))
+        }
+    }
+
+    /// Sets the operand at the given index. Returns `false` if the index is out
+    /// of range. It is up to the caller to keep the instruction well typed.
+    pub fn set_operand<BV: BasicValue<'ctx, 'm>>(&self, index: u32, value: &BV) -> bool {
+        if index >= self.get_num_operands() {
+            return false;
+        }
+
+        unsafe {
+            LLVMSetOperand(self.as_value_ref(), index, value.as_value_ref())
+        }
+
+        true
+    }
+
+    /// Gets the use of the operand at the given index. Returns `None` if the
+    /// index is out of range.
+    pub fn get_operand_use(&self, index: u32) -> Option<BasicValueUse<'ctx, 'm>> {
+        if index >= self.get_num_operands() {
+            return None;
+        }
+
+        let use_ = unsafe {
+            LLVMGetOperandUse(self.as_value_ref(), index)
+        };
+
+        if use_.is_null() {
+            return None;
+        }
+
+        Some(BasicValueUse::new(use_))
+    }
+
+    // SubTypes: Only apply to terminator instructions
+    /// Gets the number of `BasicBlock`s this instruction may branch to, which
+    /// is zero for any instruction that isn't a terminator.
+    pub fn get_num_successors(&self) -> u32 {
+        let is_terminator = unsafe {
+            !LLVMIsATerminatorInst(self.as_value_ref()).is_null()
+        };
+
+        if !is_terminator {
+            return 0;
+        }
+
+        unsafe {
+            LLVMGetNumSuccessors(self.as_value_ref())
+        }
+    }
+
+    // SubTypes: Only apply to terminator instructions
+    /// Gets the successor `BasicBlock` at the given index. Returns `None` if the
+    /// index is out of range.
+    pub fn get_successor(&self, index: u32) -> Option<BasicBlock<'ctx, 'm>> {
+        if index >= self.get_num_successors() {
+            return None;
+        }
+
+        let bb = unsafe {
+            LLVMGetSuccessor(self.as_value_ref(), index)
+        };
+
+        BasicBlock::new(bb)
+    }
+
+    // SubTypes: Only apply to terminator instructions
+    /// Sets the successor `BasicBlock` at the given index. Returns `false` if the
+    /// index is out of range. Note that phi nodes in the old and new successors
+    /// are not updated.
+    pub fn set_successor(&self, index: u32, basic_block: &BasicBlock<'ctx, 'm>) -> bool {
+        if index >= self.get_num_successors() {
+            return false;
+        }
+
+        unsafe {
+            LLVMSetSuccessor(self.as_value_ref(), index, basic_block.basic_block)
+        }
+
+        true
+    }
+
+    pub fn has_metadata(&self) -> bool {
+        self.instruction_value.has_metadata()
+    }
+
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
+        self.instruction_value.get_metadata(kind_id)
+    }
+
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
+        self.instruction_value.set_metadata(metadata, kind_id)
+    }
+
     // SubTypes: Only apply to memory access instructions
     /// Returns whether or not a memory access instruction is volatile.
     pub fn get_volatile(&self) -> bool {
@@ -248,9 +428,64 @@ impl InstructionValue {
             LLVMSetVolatile(self.as_value_ref(), volatile as i32)
         }
     }
+
+    // SubTypes: Only apply to landing pad instructions
+    /// Appends a catch or filter clause to a landing pad instruction.
+    pub fn add_clause(&self, clause: &BasicValue<'ctx, 'm>) {
+        unsafe {
+            LLVMAddClause(self.as_value_ref(), clause.as_value_ref())
+        }
+    }
+
+    // SubTypes: Only apply to landing pad instructions
+    /// Sets whether or not a landing pad instruction is entered on cleanup.
+    pub fn set_cleanup(&self, cleanup: bool) {
+        unsafe {
+            LLVMSetCleanup(self.as_value_ref(), cleanup as i32)
+        }
+    }
+
+    // SubTypes: Only apply to landing pad instructions
+    /// Returns whether or not a landing pad instruction is entered on cleanup.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn is_cleanup(&self) -> bool {
+        use llvm_sys::core::LLVMIsCleanup;
+
+        unsafe {
+            LLVMIsCleanup(self.as_value_ref()) == 1
+        }
+    }
+
+    // SubTypes: Only apply to landing pad instructions
+    /// Returns the number of catch and filter clauses of a landing pad instruction.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_num_clauses(&self) -> u32 {
+        use llvm_sys::core::LLVMGetNumClauses;
+
+        unsafe {
+            LLVMGetNumClauses(self.as_value_ref())
+        }
+    }
+
+    // SubTypes: Only apply to landing pad instructions
+    /// Returns the clause of a landing pad instruction at the given index.
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    pub fn get_clause(&self, index: u32) -> Option<BasicValueEnum<'ctx, 'm>> {
+        use llvm_sys::core::LLVMGetClause;
+
+        if index >= self.get_num_clauses() {
+            return None;
+        }
+
+        let value = unsafe {
+            LLVMGetClause(self.as_value_ref(), index)
+        };
+
+        Some(BasicValueEnum::new(value))
+    }
 }
 
-impl Clone for InstructionValue {
+impl<'ctx, 'm> Clone for InstructionValue<'ctx, 'm> {
     /// Creates a clone of this `InstructionValue`, and returns it.
     /// The clone will have no parent, and no name.
     fn clone(&self) -> Self {
@@ -262,7 +497,7 @@ impl Clone for InstructionValue {
     }
 }
 
-impl AsValueRef for InstructionValue {
+impl<'ctx, 'm> AsValueRef for InstructionValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.instruction_value.value
     }
@@ -4,16 +4,17 @@ use llvm_sys::prelude::LLVMValueRef;
 use std::ffi::CStr;
 
 use IntPredicate;
+use support::LLVMString;
 use types::{AsTypeRef, FloatType, PointerType, IntType};
 use values::traits::AsValueRef;
-use values::{FloatValue, InstructionValue, PointerValue, Value, MetadataValue};
+use values::{FloatValue, InstructionValue, PointerValue, Value, MetadataValue, BasicValueUse, BasicValueUseIter};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct IntValue {
-    int_value: Value,
+pub struct IntValue<'ctx: 'm, 'm> {
+    int_value: Value<'ctx, 'm>,
 }
 
-impl IntValue {
+impl<'ctx, 'm> IntValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -30,7 +31,7 @@ impl IntValue {
         self.int_value.set_name(name);
     }
 
-    pub fn get_type(&self) -> IntType {
+    pub fn get_type(&self) -> IntType<'ctx> {
         IntType::new(self.int_value.get_type())
     }
 
@@ -42,7 +43,7 @@ impl IntValue {
         self.int_value.is_undef()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.int_value.print_to_string()
     }
 
@@ -50,7 +51,7 @@ impl IntValue {
         self.int_value.print_to_stderr()
     }
 
-    pub fn as_instruction(&self) -> Option<InstructionValue> {
+    pub fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         self.int_value.as_instruction()
     }
 
@@ -87,7 +88,7 @@ impl IntValue {
     }
 
     // TODO: operator overloading to call this
-    pub fn const_add(&self, rhs: &IntValue) -> Self {
+    pub fn const_add(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstAdd(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -95,7 +96,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_nsw_add(&self, rhs: &IntValue) -> Self {
+    pub fn const_nsw_add(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstNSWAdd(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -103,7 +104,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_nuw_add(&self, rhs: &IntValue) -> Self {
+    pub fn const_nuw_add(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstNUWAdd(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -112,7 +113,7 @@ impl IntValue {
     }
 
     // TODO: operator overloading to call this
-    pub fn const_sub(&self, rhs: &IntValue) -> Self {
+    pub fn const_sub(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstSub(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -120,7 +121,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_nsw_sub(&self, rhs: &IntValue) -> Self {
+    pub fn const_nsw_sub(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstNSWSub(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -128,7 +129,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_nuw_sub(&self, rhs: &IntValue) -> Self {
+    pub fn const_nuw_sub(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstNUWSub(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -137,7 +138,7 @@ impl IntValue {
     }
 
     // TODO: operator overloading to call this
-    pub fn const_mul(&self, rhs: &IntValue) -> Self {
+    pub fn const_mul(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstMul(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -145,7 +146,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_nsw_mul(&self, rhs: &IntValue) -> Self {
+    pub fn const_nsw_mul(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstNSWMul(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -153,7 +154,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_nuw_mul(&self, rhs: &IntValue) -> Self {
+    pub fn const_nuw_mul(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstNUWMul(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -161,7 +162,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_unsigned_div(&self, rhs: &IntValue) -> Self {
+    pub fn const_unsigned_div(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstUDiv(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -169,7 +170,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_signed_div(&self, rhs: &IntValue) -> Self {
+    pub fn const_signed_div(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstSDiv(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -177,7 +178,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_exact_signed_div(&self, rhs: &IntValue) -> Self {
+    pub fn const_exact_signed_div(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstExactSDiv(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -185,7 +186,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_unsigned_remainder(&self, rhs: &IntValue) -> Self {
+    pub fn const_unsigned_remainder(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstURem(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -193,7 +194,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_signed_remainder(&self, rhs: &IntValue) -> Self {
+    pub fn const_signed_remainder(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstSRem(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -201,7 +202,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_and(&self, rhs: &IntValue) -> Self {
+    pub fn const_and(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstAnd(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -209,7 +210,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_or(&self, rhs: &IntValue) -> Self {
+    pub fn const_or(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstOr(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -217,7 +218,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_xor(&self, rhs: &IntValue) -> Self {
+    pub fn const_xor(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstXor(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -226,7 +227,7 @@ impl IntValue {
     }
 
     // TODO: Could infer is_signed from type (one day)
-    pub fn const_cast(&self, int_type: &IntType, is_signed: bool) -> Self {
+    pub fn const_cast(&self, int_type: &IntType<'ctx>, is_signed: bool) -> Self {
         let value = unsafe {
             LLVMConstIntCast(self.as_value_ref(), int_type.as_type_ref(), is_signed as i32)
         };
@@ -235,7 +236,7 @@ impl IntValue {
     }
 
     // TODO: Give shift methods more descriptive names
-    pub fn const_shl(&self, rhs: &IntValue) -> Self {
+    pub fn const_shl(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstShl(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -243,7 +244,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_rshr(&self, rhs: &IntValue) -> Self {
+    pub fn const_rshr(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstLShr(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -251,7 +252,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_ashr(&self, rhs: &IntValue) -> Self {
+    pub fn const_ashr(&self, rhs: &IntValue<'ctx, 'm>) -> Self {
         let value = unsafe {
             LLVMConstAShr(self.as_value_ref(), rhs.as_value_ref())
         };
@@ -260,7 +261,7 @@ impl IntValue {
     }
 
     // SubType: const_to_float impl only for unsigned types
-    pub fn const_unsigned_to_float(&self, float_type: &FloatType) -> FloatValue {
+    pub fn const_unsigned_to_float(&self, float_type: &FloatType<'ctx>) -> FloatValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstUIToFP(self.as_value_ref(), float_type.as_type_ref())
         };
@@ -269,7 +270,7 @@ impl IntValue {
     }
 
     // SubType: const_to_float impl only for signed types
-    pub fn const_signed_to_float(&self, float_type: &FloatType) -> FloatValue {
+    pub fn const_signed_to_float(&self, float_type: &FloatType<'ctx>) -> FloatValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstSIToFP(self.as_value_ref(), float_type.as_type_ref())
         };
@@ -277,7 +278,7 @@ impl IntValue {
         FloatValue::new(value)
     }
 
-    pub fn const_to_pointer(&self, ptr_type: &PointerType) -> PointerValue {
+    pub fn const_to_pointer(&self, ptr_type: &PointerType<'ctx>) -> PointerValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstIntToPtr(self.as_value_ref(), ptr_type.as_type_ref())
         };
@@ -285,7 +286,7 @@ impl IntValue {
         PointerValue::new(value)
     }
 
-    pub fn const_truncate(&self, int_type: &IntType) -> IntValue {
+    pub fn const_truncate(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstTrunc(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -294,7 +295,7 @@ impl IntValue {
     }
 
     // TODO: More descriptive name
-    pub fn const_s_extend(&self, int_type: &IntType) -> IntValue {
+    pub fn const_s_extend(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstSExt(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -303,7 +304,7 @@ impl IntValue {
     }
 
     // TODO: More descriptive name
-    pub fn const_z_ext(&self, int_type: &IntType) -> IntValue {
+    pub fn const_z_ext(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstZExt(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -311,7 +312,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_truncate_or_bit_cast(&self, int_type: &IntType) -> IntValue {
+    pub fn const_truncate_or_bit_cast(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstTruncOrBitCast(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -320,7 +321,7 @@ impl IntValue {
     }
 
     // TODO: More descriptive name
-    pub fn const_s_extend_or_bit_cast(&self, int_type: &IntType) -> IntValue {
+    pub fn const_s_extend_or_bit_cast(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstSExtOrBitCast(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -329,7 +330,7 @@ impl IntValue {
     }
 
     // TODO: More descriptive name
-    pub fn const_z_ext_or_bit_cast(&self, int_type: &IntType) -> IntValue {
+    pub fn const_z_ext_or_bit_cast(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstZExtOrBitCast(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -337,7 +338,7 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn const_bit_cast(&self, int_type: &IntType) -> IntValue {
+    pub fn const_bit_cast(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstBitCast(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -349,16 +350,16 @@ impl IntValue {
         self.int_value.has_metadata()
     }
 
-    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         self.int_value.get_metadata(kind_id)
     }
 
-    pub fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         self.int_value.set_metadata(metadata, kind_id)
     }
 
     // SubType: rhs same as lhs; return IntValue<bool>
-    pub fn const_int_compare(&self, op: IntPredicate, rhs: &IntValue) -> IntValue {
+    pub fn const_int_compare(&self, op: IntPredicate, rhs: &IntValue<'ctx, 'm>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstICmp(op.as_llvm_predicate(), self.as_value_ref(), rhs.as_value_ref())
         };
@@ -366,12 +367,20 @@ impl IntValue {
         IntValue::new(value)
     }
 
-    pub fn replace_all_uses_with(&self, other: &IntValue) {
+    pub fn replace_all_uses_with(&self, other: &IntValue<'ctx, 'm>) {
         self.int_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.int_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.int_value.uses()
+    }
 }
 
-impl AsValueRef for IntValue {
+impl<'ctx, 'm> AsValueRef for IntValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.int_value.value
     }
@@ -1,8 +1,9 @@
 use llvm_sys::core::{LLVMMDNode, LLVMMDString, LLVMIsAMDNode, LLVMIsAMDString, LLVMGetMDString, LLVMGetMDNodeNumOperands, LLVMGetMDNodeOperands, LLVMGetMDKindID};
 use llvm_sys::prelude::LLVMValueRef;
 
+use support::LLVMString;
 use values::traits::AsValueRef;
-use values::{BasicValue, BasicMetadataValueEnum, Value};
+use values::{BasicValue, BasicMetadataValueEnum, Value, BasicValueUse, BasicValueUseIter};
 
 use std::ffi::{CString, CStr};
 use std::fmt;
@@ -12,11 +13,11 @@ use std::slice::from_raw_parts;
 pub const FIRST_CUSTOM_METADATA_KIND_ID: u32 = 14; // TODO: Varies by version
 
 #[derive(PartialEq, Eq, Clone, Copy)]
-pub struct MetadataValue {
-    metadata_value: Value,
+pub struct MetadataValue<'ctx: 'm, 'm> {
+    metadata_value: Value<'ctx, 'm>,
 }
 
-impl MetadataValue {
+impl<'ctx, 'm> MetadataValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -43,27 +44,6 @@ impl MetadataValue {
         }
     }
 
-    pub fn create_node(values: &[&BasicValue]) -> Self {
-        let mut tuple_values: Vec<LLVMValueRef> = values.iter()
-                                                        .map(|val| val.as_value_ref())
-                                                        .collect();
-        let metadata_value = unsafe {
-            LLVMMDNode(tuple_values.as_mut_ptr(), tuple_values.len() as u32)
-        };
-
-        MetadataValue::new(metadata_value)
-    }
-
-    pub fn create_string(string: &str) -> Self {
-        let c_string = CString::new(string).expect("Conversion to CString failed unexpectedly");
-
-        let metadata_value = unsafe {
-            LLVMMDString(c_string.as_ptr(), string.len() as u32)
-        };
-
-        MetadataValue::new(metadata_value)
-    }
-
     pub fn get_string_value(&self) -> Option<&CStr> {
         if self.is_node() {
             return None;
@@ -90,7 +70,7 @@ impl MetadataValue {
 
     // SubTypes: Node only one day
     // REVIEW: BasicMetadataValueEnum only if you can put metadata in metadata...
-    pub fn get_node_values(&self) -> Vec<BasicMetadataValueEnum> {
+    pub fn get_node_values(&self) -> Vec<BasicMetadataValueEnum<'ctx, 'm>> {
         if self.is_string() {
             return Vec::new();
         }
@@ -118,7 +98,7 @@ impl MetadataValue {
         }
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.metadata_value.print_to_string()
     }
 
@@ -126,18 +106,49 @@ impl MetadataValue {
         self.metadata_value.print_to_stderr()
     }
 
-    pub fn replace_all_uses_with(&self, other: &MetadataValue) {
+    pub fn replace_all_uses_with(&self, other: &MetadataValue<'ctx, 'm>) {
         self.metadata_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.metadata_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.metadata_value.uses()
+    }
+}
+
+impl<'m> MetadataValue<'static, 'm> {
+    pub fn create_node(values: &[&BasicValue<'static, 'm>]) -> Self {
+        let mut tuple_values: Vec<LLVMValueRef> = values.iter()
+                                                        .map(|val| val.as_value_ref())
+                                                        .collect();
+        let metadata_value = unsafe {
+            LLVMMDNode(tuple_values.as_mut_ptr(), tuple_values.len() as u32)
+        };
+
+        MetadataValue::new(metadata_value)
+    }
+
+    pub fn create_string(string: &str) -> Self {
+        let c_string = CString::new(string).expect("Conversion to CString failed unexpectedly");
+
+        let metadata_value = unsafe {
+            LLVMMDString(c_string.as_ptr(), string.len() as u32)
+        };
+
+        MetadataValue::new(metadata_value)
+    }
 }
 
-impl AsValueRef for MetadataValue {
+impl<'ctx, 'm> AsValueRef for MetadataValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.metadata_value.value
     }
 }
 
-impl fmt::Debug for MetadataValue {
+impl<'ctx, 'm> fmt::Debug for MetadataValue<'ctx, 'm> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "MetadataValue {{\n    ")?;
         write!(f, "address: {:?}\n", self.as_value_ref())?;
@@ -1,4 +1,6 @@
 mod array_value;
+mod basic_value_use;
+mod call_site_value;
 mod enums;
 mod float_value;
 mod fn_value;
@@ -13,10 +15,14 @@ mod struct_value;
 mod traits;
 mod vec_value;
 
+use support::LLVMString;
+use ModuleMarker;
 pub use values::array_value::ArrayValue;
+pub use values::basic_value_use::{BasicValueUse, BasicValueUseIter};
+pub use values::call_site_value::CallSiteValue;
 pub use values::enums::{AnyValueEnum, AggregateValueEnum, BasicValueEnum, BasicMetadataValueEnum};
 pub use values::float_value::FloatValue;
-pub use values::fn_value::FunctionValue;
+pub use values::fn_value::{FunctionValue, BasicBlockIter, ParamValueIter};
 pub use values::generic_value::GenericValue;
 pub use values::global_value::GlobalValue;
 pub use values::instruction_value::{InstructionValue, InstructionOpcode};
@@ -29,23 +35,26 @@ pub use values::traits::{AnyValue, AggregateValue, BasicValue};
 pub use values::vec_value::VectorValue;
 pub(crate) use values::traits::AsValueRef;
 
-use llvm_sys::core::{LLVMGetValueName, LLVMIsConstant, LLVMIsNull, LLVMIsUndef, LLVMPrintTypeToString, LLVMPrintValueToString, LLVMSetValueName, LLVMTypeOf, LLVMDumpValue, LLVMIsAInstruction, LLVMGetMetadata, LLVMHasMetadata, LLVMSetMetadata, LLVMReplaceAllUsesWith};
+use llvm_sys::core::{LLVMGetValueName, LLVMIsConstant, LLVMIsNull, LLVMIsUndef, LLVMPrintTypeToString, LLVMPrintValueToString, LLVMSetValueName, LLVMTypeOf, LLVMDumpValue, LLVMIsAInstruction, LLVMGetMetadata, LLVMHasMetadata, LLVMSetMetadata, LLVMReplaceAllUsesWith, LLVMGetFirstUse};
 use llvm_sys::prelude::{LLVMValueRef, LLVMTypeRef};
 
 use std::ffi::{CString, CStr};
 use std::fmt;
+use std::marker::PhantomData;
 
 #[derive(PartialEq, Eq, Clone, Copy)]
-struct Value {
+struct Value<'ctx: 'm, 'm> {
     value: LLVMValueRef,
+    _marker: ModuleMarker<'ctx, 'm>,
 }
 
-impl Value {
-    pub(crate) fn new(value: LLVMValueRef) -> Value {
+impl<'ctx, 'm> Value<'ctx, 'm> {
+    pub(crate) fn new(value: LLVMValueRef) -> Value<'ctx, 'm> {
         debug_assert!(!value.is_null(), "This should never happen since containing struct should check null ptrs");
 
         Value {
-            value: value
+            value: value,
+            _marker: PhantomData,
         }
     }
 
@@ -55,7 +64,7 @@ impl Value {
         }
     }
 
-    fn as_instruction(&self) -> Option<InstructionValue> {
+    fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         if !self.is_instruction() {
             return None;
         }
@@ -101,10 +110,12 @@ impl Value {
         }
     }
 
-    fn print_to_string(&self) -> &CStr {
-        unsafe {
-            CStr::from_ptr(LLVMPrintValueToString(self.value))
-        }
+    fn print_to_string(&self) -> LLVMString {
+        let c_string = unsafe {
+            LLVMPrintValueToString(self.value)
+        };
+
+        LLVMString::new(c_string)
     }
 
     fn print_to_stderr(&self) {
@@ -121,7 +132,7 @@ impl Value {
 
     // SubTypes: -> Option<MetadataValue<Node>>
     // TODOC: This always returns a metadata node, which can be used to get its node values
-    fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         let metadata_value = unsafe {
             LLVMGetMetadata(self.value, kind_id)
         };
@@ -133,7 +144,7 @@ impl Value {
         Some(MetadataValue::new(metadata_value))
     }
 
-    fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         unsafe {
             LLVMSetMetadata(self.value, kind_id, metadata.as_value_ref())
         }
@@ -147,17 +158,33 @@ impl Value {
         }
     }
 
+    fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        let use_ = unsafe {
+            LLVMGetFirstUse(self.value)
+        };
+
+        if use_.is_null() {
+            return None;
+        }
+
+        Some(BasicValueUse::new(use_))
+    }
+
+    fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        BasicValueUseIter::new(self.get_first_use())
+    }
+
     // REVIEW: Remove?
     // fn get_type_kind(&self) -> LLVMTypeKind {
     //     (*self.get_type()).as_llvm_type_ref().get_kind()
     // }
 }
 
-impl fmt::Debug for Value {
+impl<'ctx, 'm> fmt::Debug for Value<'ctx, 'm> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let llvm_value = self.print_to_string();
         let llvm_type = unsafe {
-            CStr::from_ptr(LLVMPrintTypeToString(LLVMTypeOf(self.value)))
+            LLVMString::new(LLVMPrintTypeToString(LLVMTypeOf(self.value)))
         };
         let name = unsafe {
             CStr::from_ptr(LLVMGetValueName(self.value))
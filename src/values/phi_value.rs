@@ -4,16 +4,17 @@ use llvm_sys::prelude::{LLVMBasicBlockRef, LLVMValueRef};
 use std::ffi::CStr;
 
 use basic_block::BasicBlock;
+use support::LLVMString;
 use values::traits::AsValueRef;
-use values::{BasicValue, BasicValueEnum, InstructionValue, Value};
+use values::{BasicValue, BasicValueEnum, InstructionValue, Value, BasicValueUse, BasicValueUseIter};
 
 // REVIEW: Metadata for phi values?
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct PhiValue {
-    phi_value: Value
+pub struct PhiValue<'ctx: 'm, 'm> {
+    phi_value: Value<'ctx, 'm>
 }
 
-impl PhiValue {
+impl<'ctx, 'm> PhiValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -22,7 +23,7 @@ impl PhiValue {
         }
     }
 
-    pub fn add_incoming(&self, incoming: &[(&BasicValue, &BasicBlock)]) {
+    pub fn add_incoming(&self, incoming: &[(&BasicValue<'ctx, 'm>, &BasicBlock<'ctx, 'm>)]) {
         let (mut values, mut basic_blocks): (Vec<LLVMValueRef>, Vec<LLVMBasicBlockRef>) = {
             incoming.iter()
                     .map(|&(v, bb)| (v.as_value_ref(), bb.basic_block))
@@ -40,7 +41,7 @@ impl PhiValue {
         }
     }
 
-    pub fn get_incoming(&self, index: u32) -> Option<(BasicValueEnum, BasicBlock)> {
+    pub fn get_incoming(&self, index: u32) -> Option<(BasicValueEnum<'ctx, 'm>, BasicBlock<'ctx, 'm>)> {
         if index >= self.count_incoming() {
             return None;
         }
@@ -72,7 +73,7 @@ impl PhiValue {
         self.phi_value.is_undef()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.phi_value.print_to_string()
     }
 
@@ -81,20 +82,28 @@ impl PhiValue {
     }
 
     // SubType: -> InstructionValue<Phi>
-    pub fn as_instruction(&self) -> InstructionValue {
+    pub fn as_instruction(&self) -> InstructionValue<'ctx, 'm> {
         self.phi_value.as_instruction().expect("PhiValue should always be a Phi InstructionValue")
     }
 
-    pub fn replace_all_uses_with(&self, other: &PhiValue) {
+    pub fn replace_all_uses_with(&self, other: &PhiValue<'ctx, 'm>) {
         self.phi_value.replace_all_uses_with(other.as_value_ref())
     }
 
-    pub fn as_basic_value(&self) -> BasicValueEnum {
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.phi_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.phi_value.uses()
+    }
+
+    pub fn as_basic_value(&self) -> BasicValueEnum<'ctx, 'm> {
         BasicValueEnum::new(self.as_value_ref())
     }
 }
 
-impl AsValueRef for PhiValue {
+impl<'ctx, 'm> AsValueRef for PhiValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.phi_value.value
     }
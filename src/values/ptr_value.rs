@@ -3,15 +3,16 @@ use llvm_sys::prelude::LLVMValueRef;
 
 use std::ffi::CStr;
 
+use support::LLVMString;
 use types::{AsTypeRef, IntType, PointerType};
-use values::{AsValueRef, InstructionValue, IntValue, Value, MetadataValue};
+use values::{AsValueRef, InstructionValue, IntValue, Value, MetadataValue, BasicValueUse, BasicValueUseIter};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct PointerValue {
-    ptr_value: Value,
+pub struct PointerValue<'ctx: 'm, 'm> {
+    ptr_value: Value<'ctx, 'm>,
 }
 
-impl PointerValue {
+impl<'ctx, 'm> PointerValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -28,7 +29,7 @@ impl PointerValue {
         self.ptr_value.set_name(name);
     }
 
-    pub fn get_type(&self) -> PointerType {
+    pub fn get_type(&self) -> PointerType<'ctx> {
         PointerType::new(self.ptr_value.get_type())
     }
 
@@ -40,7 +41,7 @@ impl PointerValue {
         self.ptr_value.is_undef()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.ptr_value.print_to_string()
     }
 
@@ -48,7 +49,7 @@ impl PointerValue {
         self.ptr_value.print_to_stderr()
     }
 
-    pub fn as_instruction(&self) -> Option<InstructionValue> {
+    pub fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         self.ptr_value.as_instruction()
     }
 
@@ -56,16 +57,16 @@ impl PointerValue {
         self.ptr_value.has_metadata()
     }
 
-    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         self.ptr_value.get_metadata(kind_id)
     }
 
-    pub fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         self.ptr_value.set_metadata(metadata, kind_id)
     }
 
     // REVIEW: Should this be on array value too?
-    pub fn const_gep(&self, ordered_indexes: &[&IntValue]) -> PointerValue {
+    pub fn const_gep(&self, ordered_indexes: &[&IntValue<'ctx, 'm>]) -> PointerValue<'ctx, 'm> {
         let mut index_values: Vec<LLVMValueRef> = ordered_indexes.iter()
                                                                  .map(|val| val.as_value_ref())
                                                                  .collect();
@@ -76,7 +77,7 @@ impl PointerValue {
         PointerValue::new(value)
     }
 
-    pub fn const_in_bounds_gep(&self, ordered_indexes: &[&IntValue]) -> PointerValue {
+    pub fn const_in_bounds_gep(&self, ordered_indexes: &[&IntValue<'ctx, 'm>]) -> PointerValue<'ctx, 'm> {
         let mut index_values: Vec<LLVMValueRef> = ordered_indexes.iter()
                                                                  .map(|val| val.as_value_ref())
                                                                  .collect();
@@ -87,7 +88,7 @@ impl PointerValue {
         PointerValue::new(value)
     }
 
-    pub fn const_to_int(&self, int_type: &IntType) -> IntValue {
+    pub fn const_to_int(&self, int_type: &IntType<'ctx>) -> IntValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstPtrToInt(self.as_value_ref(), int_type.as_type_ref())
         };
@@ -95,7 +96,7 @@ impl PointerValue {
         IntValue::new(value)
     }
 
-    pub fn const_cast(&self, ptr_type: &PointerType) -> PointerValue {
+    pub fn const_cast(&self, ptr_type: &PointerType<'ctx>) -> PointerValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstPointerCast(self.as_value_ref(), ptr_type.as_type_ref())
         };
@@ -103,7 +104,7 @@ impl PointerValue {
         PointerValue::new(value)
     }
 
-    pub fn const_address_space_cast(&self, ptr_type: &PointerType) -> PointerValue {
+    pub fn const_address_space_cast(&self, ptr_type: &PointerType<'ctx>) -> PointerValue<'ctx, 'm> {
         let value = unsafe {
             LLVMConstAddrSpaceCast(self.as_value_ref(), ptr_type.as_type_ref())
         };
@@ -111,12 +112,20 @@ impl PointerValue {
         PointerValue::new(value)
     }
 
-    pub fn replace_all_uses_with(&self, other: &PointerValue) {
+    pub fn replace_all_uses_with(&self, other: &PointerValue<'ctx, 'm>) {
         self.ptr_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.ptr_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.ptr_value.uses()
+    }
 }
 
-impl AsValueRef for PointerValue {
+impl<'ctx, 'm> AsValueRef for PointerValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.ptr_value.value
     }
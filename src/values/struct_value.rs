@@ -2,16 +2,17 @@ use llvm_sys::prelude::LLVMValueRef;
 
 use std::ffi::CStr;
 
+use support::LLVMString;
 use types::StructType;
 use values::traits::AsValueRef;
-use values::{InstructionValue, Value, MetadataValue};
+use values::{InstructionValue, Value, MetadataValue, BasicValueUse, BasicValueUseIter};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct StructValue {
-    struct_value: Value
+pub struct StructValue<'ctx: 'm, 'm> {
+    struct_value: Value<'ctx, 'm>
 }
 
-impl StructValue {
+impl<'ctx, 'm> StructValue<'ctx, 'm> {
     pub(crate) fn new(value: LLVMValueRef) -> Self {
         assert!(!value.is_null());
 
@@ -28,7 +29,7 @@ impl StructValue {
         self.struct_value.set_name(name);
     }
 
-    pub fn get_type(&self) -> StructType {
+    pub fn get_type(&self) -> StructType<'ctx> {
         StructType::new(self.struct_value.get_type())
     }
 
@@ -40,7 +41,7 @@ impl StructValue {
         self.struct_value.is_undef()
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.struct_value.print_to_string()
     }
 
@@ -48,7 +49,7 @@ impl StructValue {
         self.struct_value.print_to_stderr()
     }
 
-    pub fn as_instruction(&self) -> Option<InstructionValue> {
+    pub fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         self.struct_value.as_instruction()
     }
 
@@ -56,20 +57,28 @@ impl StructValue {
         self.struct_value.has_metadata()
     }
 
-    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         self.struct_value.get_metadata(kind_id)
     }
 
-    pub fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         self.struct_value.set_metadata(metadata, kind_id)
     }
 
-    pub fn replace_all_uses_with(&self, other: &StructValue) {
+    pub fn replace_all_uses_with(&self, other: &StructValue<'ctx, 'm>) {
         self.struct_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.struct_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.struct_value.uses()
+    }
 }
 
-impl AsValueRef for StructValue {
+impl<'ctx, 'm> AsValueRef for StructValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.struct_value.value
     }
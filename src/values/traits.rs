@@ -2,7 +2,7 @@ use llvm_sys::prelude::LLVMValueRef;
 
 use std::fmt::Debug;
 
-use values::{ArrayValue, AggregateValueEnum, GlobalValue, StructValue, BasicValueEnum, AnyValueEnum, IntValue, FloatValue, PointerValue, PhiValue, VectorValue, FunctionValue, InstructionValue};
+use values::{ArrayValue, AggregateValueEnum, CallSiteValue, GlobalValue, StructValue, BasicValueEnum, AnyValueEnum, IntValue, FloatValue, PointerValue, PhiValue, VectorValue, FunctionValue, InstructionValue};
 
 // This is an ugly privacy hack so that Type can stay private to this module
 // and so that super traits using this trait will be not be implementable
@@ -14,7 +14,7 @@ pub trait AsValueRef {
 macro_rules! trait_value_set {
     ($trait_name:ident: $($args:ident),*) => (
         $(
-            impl $trait_name for $args {}
+            impl<'ctx, 'm> $trait_name<'ctx, 'm> for $args<'ctx, 'm> {}
         )*
 
         // REVIEW: Possible encompassing methods to implement:
@@ -23,29 +23,29 @@ macro_rules! trait_value_set {
 }
 
 /// Represents an aggregate value, built on top of other values.
-pub trait AggregateValue: BasicValue {
+pub trait AggregateValue<'ctx, 'm>: BasicValue<'ctx, 'm> {
     /// Returns an enum containing a typed version of the `AggregateValue`.
-    fn as_aggregate_value_enum(&self) -> AggregateValueEnum {
+    fn as_aggregate_value_enum(&self) -> AggregateValueEnum<'ctx, 'm> {
         AggregateValueEnum::new(self.as_value_ref())
     }
 }
 
 /// Represents a basic value, which can be used both by itself, or in an `AggregateValue`.
-pub trait BasicValue: AnyValue {
+pub trait BasicValue<'ctx, 'm>: AnyValue<'ctx, 'm> {
     /// Returns an enum containing a typed version of the `BasicValue`.
-    fn as_basic_value_enum(&self) -> BasicValueEnum {
+    fn as_basic_value_enum(&self) -> BasicValueEnum<'ctx, 'm> {
         BasicValueEnum::new(self.as_value_ref())
     }
 }
 
 /// Defines any struct wrapping an LLVM value.
-pub trait AnyValue: AsValueRef + Debug {
+pub trait AnyValue<'ctx, 'm>: AsValueRef + Debug {
     /// Returns an enum containing a typed version of `AnyValue`.
-    fn as_any_value_enum(&self) -> AnyValueEnum {
+    fn as_any_value_enum(&self) -> AnyValueEnum<'ctx, 'm> {
         AnyValueEnum::new(self.as_value_ref())
     }
 }
 
 trait_value_set! {AggregateValue: ArrayValue, AggregateValueEnum, StructValue}
-trait_value_set! {AnyValue: AnyValueEnum, BasicValueEnum, AggregateValueEnum, ArrayValue, IntValue, FloatValue, GlobalValue, PhiValue, PointerValue, FunctionValue, StructValue, VectorValue, InstructionValue}
+trait_value_set! {AnyValue: AnyValueEnum, BasicValueEnum, AggregateValueEnum, ArrayValue, IntValue, FloatValue, GlobalValue, PhiValue, PointerValue, FunctionValue, StructValue, VectorValue, InstructionValue, CallSiteValue}
 trait_value_set! {BasicValue: ArrayValue, BasicValueEnum, AggregateValueEnum, IntValue, FloatValue, GlobalValue, StructValue, PointerValue, VectorValue}
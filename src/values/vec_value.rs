@@ -3,16 +3,17 @@ use llvm_sys::prelude::LLVMValueRef;
 
 use std::ffi::CStr;
 
+use support::LLVMString;
 use types::{VectorType};
 use values::traits::AsValueRef;
-use values::{BasicValueEnum, BasicValue, InstructionValue, Value, IntValue, MetadataValue};
+use values::{BasicValueEnum, BasicValue, InstructionValue, Value, IntValue, MetadataValue, BasicValueUse, BasicValueUseIter};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct VectorValue {
-    vec_value: Value,
+pub struct VectorValue<'ctx: 'm, 'm> {
+    vec_value: Value<'ctx, 'm>,
 }
 
-impl VectorValue {
+impl<'ctx, 'm> VectorValue<'ctx, 'm> {
     pub(crate) fn new(vector_value: LLVMValueRef) -> Self {
         assert!(!vector_value.is_null());
 
@@ -22,7 +23,7 @@ impl VectorValue {
     }
 
     // REVIEW: Should this be !int_value.is_null() to return bool?
-    pub fn is_constant_vector(&self) -> IntValue { // TSv2: IntValue<bool>
+    pub fn is_constant_vector(&self) -> IntValue<'ctx, 'm> { // TSv2: IntValue<bool>
         let int_value = unsafe {
             LLVMIsAConstantVector(self.as_value_ref())
         };
@@ -31,7 +32,7 @@ impl VectorValue {
     }
 
     // REVIEW: Should this be !int_value.is_null() to return bool?
-    pub fn is_constant_data_vector(&self) -> IntValue { // TSv2: IntValue<bool>
+    pub fn is_constant_data_vector(&self) -> IntValue<'ctx, 'm> { // TSv2: IntValue<bool>
         let int_value = unsafe {
             LLVMIsAConstantDataVector(self.as_value_ref())
         };
@@ -39,7 +40,7 @@ impl VectorValue {
         IntValue::new(int_value)
     }
 
-    pub fn print_to_string(&self) -> &CStr {
+    pub fn print_to_string(&self) -> LLVMString {
         self.vec_value.print_to_string()
     }
 
@@ -55,7 +56,7 @@ impl VectorValue {
         self.vec_value.set_name(name);
     }
 
-    pub fn get_type(&self) -> VectorType {
+    pub fn get_type(&self) -> VectorType<'ctx> {
         VectorType::new(self.vec_value.get_type())
     }
 
@@ -67,11 +68,11 @@ impl VectorValue {
         self.vec_value.is_undef()
     }
 
-    pub fn as_instruction(&self) -> Option<InstructionValue> {
+    pub fn as_instruction(&self) -> Option<InstructionValue<'ctx, 'm>> {
         self.vec_value.as_instruction()
     }
 
-    pub fn const_extract_element(&self, index: &IntValue) -> BasicValueEnum {
+    pub fn const_extract_element(&self, index: &IntValue<'ctx, 'm>) -> BasicValueEnum<'ctx, 'm> {
         let value = unsafe {
             LLVMConstExtractElement(self.as_value_ref(), index.as_value_ref())
         };
@@ -79,7 +80,7 @@ impl VectorValue {
         BasicValueEnum::new(value)
     }
 
-    pub fn const_insert_element(&self, index: &IntValue, value: &BasicValue) -> BasicValueEnum {
+    pub fn const_insert_element(&self, index: &IntValue<'ctx, 'm>, value: &BasicValue<'ctx, 'm>) -> BasicValueEnum<'ctx, 'm> {
         let value = unsafe {
             LLVMConstInsertElement(self.as_value_ref(), value.as_value_ref(), index.as_value_ref())
         };
@@ -91,20 +92,28 @@ impl VectorValue {
         self.vec_value.has_metadata()
     }
 
-    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue> {
+    pub fn get_metadata(&self, kind_id: u32) -> Option<MetadataValue<'ctx, 'm>> {
         self.vec_value.get_metadata(kind_id)
     }
 
-    pub fn set_metadata(&self, metadata: &MetadataValue, kind_id: u32) {
+    pub fn set_metadata(&self, metadata: &MetadataValue<'ctx, 'm>, kind_id: u32) {
         self.vec_value.set_metadata(metadata, kind_id)
     }
 
-    pub fn replace_all_uses_with(&self, other: &VectorValue) {
+    pub fn replace_all_uses_with(&self, other: &VectorValue<'ctx, 'm>) {
         self.vec_value.replace_all_uses_with(other.as_value_ref())
     }
+
+    pub fn get_first_use(&self) -> Option<BasicValueUse<'ctx, 'm>> {
+        self.vec_value.get_first_use()
+    }
+
+    pub fn uses(&self) -> BasicValueUseIter<'ctx, 'm> {
+        self.vec_value.uses()
+    }
 }
 
-impl AsValueRef for VectorValue {
+impl<'ctx, 'm> AsValueRef for VectorValue<'ctx, 'm> {
     fn as_value_ref(&self) -> LLVMValueRef {
         self.vec_value.value
     }
@@ -0,0 +1,168 @@
+extern crate inkwell;
+
+use self::inkwell::IntPredicate;
+use self::inkwell::analysis::{DominatorTree, LoopInfo};
+use self::inkwell::context::Context;
+
+#[test]
+fn test_dominator_tree() {
+    let context = Context::create();
+    let module = context.create_module("test");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type], false);
+
+    let function = module.add_function("testing", &fn_type, None);
+    let entry = context.append_basic_block(&function, "entry");
+    let then_block = context.append_basic_block(&function, "then");
+    let else_block = context.append_basic_block(&function, "else");
+    let end = context.append_basic_block(&function, "end");
+    let unreachable = context.append_basic_block(&function, "unreachable");
+
+    builder.position_at_end(&entry);
+
+    let param = function.get_first_param().unwrap().into_int_value();
+    let zero = i32_type.const_int(0, false);
+    let cond = builder.build_int_compare(IntPredicate::EQ, &param, &zero, "cond");
+
+    builder.build_conditional_branch(&cond, &then_block, &else_block);
+    builder.position_at_end(&then_block);
+    builder.build_unconditional_branch(&end);
+    builder.position_at_end(&else_block);
+    builder.build_unconditional_branch(&end);
+    builder.position_at_end(&end);
+    builder.build_return(Some(&param));
+    builder.position_at_end(&unreachable);
+    builder.build_unconditional_branch(&end);
+
+    let dominator_tree = DominatorTree::compute(&function);
+
+    assert_eq!(dominator_tree.get_root().unwrap(), entry);
+
+    for block in &[&entry, &then_block, &else_block, &end] {
+        assert!(dominator_tree.is_reachable(block));
+        assert!(dominator_tree.dominates(&entry, block));
+        assert!(dominator_tree.dominates(block, block));
+        assert!(!dominator_tree.strictly_dominates(block, block));
+    }
+
+    assert!(!dominator_tree.dominates(&then_block, &end));
+    assert!(!dominator_tree.dominates(&else_block, &end));
+    assert!(!dominator_tree.dominates(&end, &entry));
+    assert!(dominator_tree.strictly_dominates(&entry, &end));
+
+    assert!(dominator_tree.get_immediate_dominator(&entry).is_none());
+    assert_eq!(dominator_tree.get_immediate_dominator(&then_block).unwrap(), entry);
+    assert_eq!(dominator_tree.get_immediate_dominator(&else_block).unwrap(), entry);
+    assert_eq!(dominator_tree.get_immediate_dominator(&end).unwrap(), entry);
+
+    let children = dominator_tree.get_children(&entry);
+
+    assert_eq!(children.len(), 3);
+    assert!(children.contains(&then_block));
+    assert!(children.contains(&else_block));
+    assert!(children.contains(&end));
+    assert!(dominator_tree.get_children(&end).is_empty());
+
+    assert!(dominator_tree.get_dominance_frontier(&entry).is_empty());
+    assert_eq!(dominator_tree.get_dominance_frontier(&then_block), vec![end]);
+    assert_eq!(dominator_tree.get_dominance_frontier(&else_block).len(), 1);
+
+    // Unreachable blocks are ignored, and dominated by everything like in LLVM
+    assert!(!dominator_tree.is_reachable(&unreachable));
+    assert!(dominator_tree.get_immediate_dominator(&unreachable).is_none());
+    assert!(dominator_tree.dominates(&then_block, &unreachable));
+    assert!(!dominator_tree.dominates(&unreachable, &then_block));
+    assert!(dominator_tree.get_dominance_frontier(&unreachable).is_empty());
+}
+
+#[test]
+fn test_dominator_tree_declaration() {
+    let context = Context::create();
+    let module = context.create_module("test");
+
+    let void_type = context.void_type();
+    let fn_type = void_type.fn_type(&[], false);
+
+    let function = module.add_function("testing", &fn_type, None);
+    let dominator_tree = DominatorTree::compute(&function);
+
+    assert!(dominator_tree.get_root().is_none());
+    assert!(LoopInfo::compute(&dominator_tree).get_loops().is_empty());
+}
+
+#[test]
+fn test_loop_info() {
+    let context = Context::create();
+    let module = context.create_module("test");
+    let builder = context.create_builder();
+
+    let void_type = context.void_type();
+    let bool_type = context.bool_type();
+    let fn_type = void_type.fn_type(&[&bool_type], false);
+
+    // entry -> outer -> inner -> inner_latch -> inner
+    //                              inner_latch -> outer_latch -> outer
+    //                                             outer_latch -> end
+    let function = module.add_function("testing", &fn_type, None);
+    let entry = context.append_basic_block(&function, "entry");
+    let outer = context.append_basic_block(&function, "outer");
+    let inner = context.append_basic_block(&function, "inner");
+    let inner_latch = context.append_basic_block(&function, "inner_latch");
+    let outer_latch = context.append_basic_block(&function, "outer_latch");
+    let end = context.append_basic_block(&function, "end");
+
+    let cond = function.get_first_param().unwrap().into_int_value();
+
+    builder.position_at_end(&entry);
+    builder.build_unconditional_branch(&outer);
+    builder.position_at_end(&outer);
+    builder.build_unconditional_branch(&inner);
+    builder.position_at_end(&inner);
+    builder.build_unconditional_branch(&inner_latch);
+    builder.position_at_end(&inner_latch);
+    builder.build_conditional_branch(&cond, &inner, &outer_latch);
+    builder.position_at_end(&outer_latch);
+    builder.build_conditional_branch(&cond, &outer, &end);
+    builder.position_at_end(&end);
+    builder.build_return(None);
+
+    let dominator_tree = DominatorTree::compute(&function);
+    let loop_info = LoopInfo::compute(&dominator_tree);
+
+    assert_eq!(loop_info.get_loops().len(), 2);
+    assert_eq!(loop_info.get_top_level_loops().len(), 1);
+
+    assert_eq!(loop_info.get_loop_depth(&entry), 0);
+    assert_eq!(loop_info.get_loop_depth(&outer), 1);
+    assert_eq!(loop_info.get_loop_depth(&inner), 2);
+    assert_eq!(loop_info.get_loop_depth(&inner_latch), 2);
+    assert_eq!(loop_info.get_loop_depth(&outer_latch), 1);
+    assert_eq!(loop_info.get_loop_depth(&end), 0);
+
+    assert!(loop_info.is_loop_header(&outer));
+    assert!(loop_info.is_loop_header(&inner));
+    assert!(!loop_info.is_loop_header(&inner_latch));
+    assert!(!loop_info.is_loop_header(&entry));
+
+    let outer_loop = loop_info.get_loop_for(&outer_latch).unwrap();
+    let inner_loop = loop_info.get_loop_for(&inner_latch).unwrap();
+
+    assert_eq!(outer_loop.get_header(), outer);
+    assert_eq!(outer_loop.get_depth(), 1);
+    assert_eq!(outer_loop.get_blocks().len(), 4);
+    assert_eq!(outer_loop.get_latches(), vec![outer_latch]);
+    assert!(outer_loop.contains(&inner));
+    assert!(!outer_loop.contains(&end));
+    assert!(loop_info.get_parent_loop(outer_loop).is_none());
+
+    assert_eq!(inner_loop.get_header(), inner);
+    assert_eq!(inner_loop.get_depth(), 2);
+    assert_eq!(inner_loop.get_blocks().len(), 2);
+    assert_eq!(inner_loop.get_latches(), vec![inner_latch]);
+    assert!(!inner_loop.contains(&outer));
+    assert_eq!(loop_info.get_parent_loop(inner_loop).unwrap().get_header(), outer);
+
+    assert!(loop_info.get_loop_for(&entry).is_none());
+}
@@ -0,0 +1,248 @@
+extern crate inkwell;
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use self::inkwell::attributes::Attribute;
+use self::inkwell::attributes::{AttributeKind, AttributeLoc};
+use self::inkwell::context::Context;
+use self::inkwell::AddressSpace;
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_enum_attribute_kinds() {
+    let context = Context::create();
+    let noinline_id = Attribute::get_named_enum_kind_id("noinline");
+    let deref_id = Attribute::get_named_enum_kind_id("dereferenceable");
+
+    assert_ne!(noinline_id, 0);
+    assert_ne!(deref_id, 0);
+    assert_eq!(Attribute::get_named_enum_kind_id("not_an_attribute"), 0);
+    assert!(Attribute::get_last_enum_kind_id() >= deref_id);
+
+    let noinline = context.create_enum_attribute(noinline_id, 0);
+    let deref = context.create_enum_attribute(deref_id, 8);
+
+    assert!(noinline.is_enum());
+    assert!(!noinline.is_string());
+    assert_eq!(noinline.get_enum_kind_id(), noinline_id);
+    assert_eq!(deref.get_enum_value(), 8);
+
+    let string = context.create_string_attribute("my_key", "my_val");
+
+    assert!(string.is_string());
+    assert!(!string.is_enum());
+    assert_eq!(string.get_string_kind_id(), b"my_key");
+    assert_eq!(string.get_string_value(), b"my_val");
+
+    assert_eq!(AttributeKind::NoInline.get_kind_id(), noinline_id);
+    assert_eq!(AttributeKind::ZExt.get_kind_id(), Attribute::get_named_enum_kind_id("zeroext"));
+    assert_ne!(AttributeKind::ReturnsTwice.get_kind_id(), 0);
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_function_attributes() {
+    let context = Context::create();
+    let module = context.create_module("my_mod");
+    let i8_type = context.i8_type();
+    let i8_ptr_type = i8_type.ptr_type(AddressSpace::Generic);
+    let fn_type = i8_type.fn_type(&[&i8_ptr_type, &i8_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+
+    let noinline = context.create_enum_attribute(Attribute::get_named_enum_kind_id("noinline"), 0);
+    let nonnull_id = Attribute::get_named_enum_kind_id("nonnull");
+    let nonnull = context.create_enum_attribute(nonnull_id, 0);
+    let zeroext_id = Attribute::get_named_enum_kind_id("zeroext");
+    let zeroext = context.create_enum_attribute(zeroext_id, 0);
+    let string = context.create_string_attribute("my_key", "my_val");
+
+    assert_eq!(function.count_attributes(AttributeLoc::Function), 0);
+
+    function.add_attribute(AttributeLoc::Function, noinline);
+    function.add_attribute(AttributeLoc::Function, string);
+    function.add_attribute(AttributeLoc::Return, zeroext);
+    function.add_attribute(AttributeLoc::Param(0), nonnull);
+    function.add_attribute(AttributeLoc::Param(1), zeroext);
+
+    assert_eq!(function.count_attributes(AttributeLoc::Function), 2);
+    assert_eq!(function.count_attributes(AttributeLoc::Return), 1);
+    assert_eq!(function.count_attributes(AttributeLoc::Param(0)), 1);
+    assert_eq!(function.attributes(AttributeLoc::Param(1)), vec![zeroext]);
+    assert_eq!(function.get_enum_attribute(AttributeLoc::Param(0), nonnull_id), Some(nonnull));
+    assert_eq!(function.get_enum_attribute(AttributeLoc::Param(1), nonnull_id), None);
+    assert_eq!(function.get_string_attribute(AttributeLoc::Function, "my_key"), Some(string));
+    assert_eq!(function.get_string_attribute(AttributeLoc::Function, "other_key"), None);
+
+    function.remove_enum_attribute(AttributeLoc::Param(0), nonnull_id);
+    function.remove_string_attribute(AttributeLoc::Function, "my_key");
+
+    assert_eq!(function.count_attributes(AttributeLoc::Param(0)), 0);
+    assert_eq!(function.count_attributes(AttributeLoc::Function), 1);
+    assert!(function.verify().is_ok());
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_call_site_attributes() {
+    let context = Context::create();
+    let module = context.create_module("my_mod");
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+    let i16_type = context.i16_type();
+    let fn_type = void_type.fn_type(&[&i16_type], false);
+    let callee = module.add_function("callee", &fn_type, None);
+    let function = module.add_function("caller", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let param = function.get_first_param().unwrap();
+    let call_site = builder.build_call(&callee, &[&param], "");
+    let signext_id = Attribute::get_named_enum_kind_id("signext");
+    let signext = context.create_enum_attribute(signext_id, 0);
+    let nounwind = context.create_enum_attribute(Attribute::get_named_enum_kind_id("nounwind"), 0);
+
+    call_site.add_attribute(AttributeLoc::Param(0), signext);
+    call_site.add_attribute(AttributeLoc::Function, nounwind);
+
+    assert_eq!(call_site.count_attributes(AttributeLoc::Param(0)), 1);
+    assert_eq!(call_site.get_enum_attribute(AttributeLoc::Param(0), signext_id), Some(signext));
+    assert_eq!(call_site.attributes(AttributeLoc::Function), vec![nounwind]);
+
+    call_site.remove_enum_attribute(AttributeLoc::Param(0), signext_id);
+
+    assert_eq!(call_site.count_attributes(AttributeLoc::Param(0)), 0);
+
+    builder.build_return(None);
+
+    assert!(function.verify().is_ok());
+}
+
+#[test]
+fn test_function_attribute_kinds() {
+    let context = Context::create();
+    let module = context.create_module("my_mod");
+    let i8_type = context.i8_type();
+    let i8_ptr_type = i8_type.ptr_type(AddressSpace::Generic);
+    let fn_type = i8_type.fn_type(&[&i8_ptr_type, &i8_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+
+    assert!(!function.has_attribute_kind(AttributeLoc::Function, AttributeKind::NoInline).unwrap());
+
+    function.add_attribute_kind(AttributeLoc::Function, AttributeKind::NoInline).unwrap();
+    function.add_attribute_kind(AttributeLoc::Function, AttributeKind::NoUnwind).unwrap();
+    function.add_attribute_kind(AttributeLoc::Param(0), AttributeKind::NoCapture).unwrap();
+    function.add_attribute_kind(AttributeLoc::Param(1), AttributeKind::ZExt).unwrap();
+    function.add_target_dependent_attribute("no-frame-pointer-elim", "true");
+
+    assert!(function.has_attribute_kind(AttributeLoc::Function, AttributeKind::NoInline).unwrap());
+    assert!(function.has_attribute_kind(AttributeLoc::Function, AttributeKind::NoUnwind).unwrap());
+    assert!(function.has_attribute_kind(AttributeLoc::Param(0), AttributeKind::NoCapture).unwrap());
+    assert!(!function.has_attribute_kind(AttributeLoc::Param(0), AttributeKind::ZExt).unwrap());
+    assert!(function.has_attribute_kind(AttributeLoc::Param(1), AttributeKind::ZExt).unwrap());
+
+    function.remove_attribute_kind(AttributeLoc::Function, AttributeKind::NoInline).unwrap();
+    function.remove_attribute_kind(AttributeLoc::Param(0), AttributeKind::NoCapture).unwrap();
+
+    assert!(!function.has_attribute_kind(AttributeLoc::Function, AttributeKind::NoInline).unwrap());
+    assert!(function.has_attribute_kind(AttributeLoc::Function, AttributeKind::NoUnwind).unwrap());
+    assert!(!function.has_attribute_kind(AttributeLoc::Param(0), AttributeKind::NoCapture).unwrap());
+    assert!(function.verify().is_ok());
+}
+
+#[test]
+fn test_function_return_attribute_kinds() {
+    let context = Context::create();
+    let module = context.create_module("my_mod");
+    let i8_type = context.i8_type();
+    let fn_type = i8_type.fn_type(&[], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+
+    #[cfg(any(feature = "llvm3-7", feature = "llvm3-8"))]
+    {
+        assert!(function.add_attribute_kind(AttributeLoc::Return, AttributeKind::ZExt).is_err());
+        assert!(function.has_attribute_kind(AttributeLoc::Return, AttributeKind::ZExt).is_err());
+        assert!(function.remove_attribute_kind(AttributeLoc::Return, AttributeKind::ZExt).is_err());
+    }
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    {
+        function.add_attribute_kind(AttributeLoc::Return, AttributeKind::ZExt).unwrap();
+
+        assert!(function.has_attribute_kind(AttributeLoc::Return, AttributeKind::ZExt).unwrap());
+
+        function.remove_attribute_kind(AttributeLoc::Return, AttributeKind::ZExt).unwrap();
+
+        assert!(!function.has_attribute_kind(AttributeLoc::Return, AttributeKind::ZExt).unwrap());
+    }
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_pointer_attribute_kinds() {
+    let context = Context::create();
+    let module = context.create_module("my_mod");
+    let i64_type = context.i64_type();
+    let i64_ptr_type = i64_type.ptr_type(AddressSpace::Generic);
+    let fn_type = i64_ptr_type.fn_type(&[&i64_ptr_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+
+    assert_eq!(AttributeKind::NonNull.get_name(), "nonnull");
+    assert_eq!(AttributeKind::Dereferenceable(8).get_name(), "dereferenceable");
+    assert_eq!(AttributeKind::Dereferenceable(8).get_value(), 8);
+
+    function.add_attribute_kind(AttributeLoc::Return, AttributeKind::NonNull).unwrap();
+    function.add_attribute_kind(AttributeLoc::Param(0), AttributeKind::Dereferenceable(8)).unwrap();
+
+    assert!(function.has_attribute_kind(AttributeLoc::Return, AttributeKind::NonNull).unwrap());
+    assert!(function.has_attribute_kind(AttributeLoc::Param(0), AttributeKind::Dereferenceable(8)).unwrap());
+    assert!(!function.has_attribute_kind(AttributeLoc::Param(0), AttributeKind::Dereferenceable(16)).unwrap());
+
+    let dereferenceable = function.get_enum_attribute(AttributeLoc::Param(0), AttributeKind::Dereferenceable(0).get_kind_id()).unwrap();
+
+    assert_eq!(dereferenceable.get_enum_value(), 8);
+
+    // Removal doesn't depend on the value
+    function.remove_attribute_kind(AttributeLoc::Param(0), AttributeKind::Dereferenceable(16)).unwrap();
+
+    assert_eq!(function.count_attributes(AttributeLoc::Param(0)), 0);
+    assert!(function.verify().is_ok());
+}
+
+#[test]
+fn test_call_site_attribute_kinds() {
+    let context = Context::create();
+    let module = context.create_module("my_mod");
+    let builder = context.create_builder();
+    let i16_type = context.i16_type();
+    let fn_type = i16_type.fn_type(&[&i16_type], false);
+    let callee = module.add_function("callee", &fn_type, None);
+    let function = module.add_function("caller", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let param = function.get_first_param().unwrap();
+    let call_site = builder.build_call(&callee, &[&param], "call");
+
+    call_site.add_attribute_kind(AttributeLoc::Return, AttributeKind::SExt);
+    call_site.add_attribute_kind(AttributeLoc::Param(0), AttributeKind::SExt);
+    call_site.add_attribute_kind(AttributeLoc::Function, AttributeKind::NoUnwind);
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    {
+        assert!(call_site.has_attribute_kind(AttributeLoc::Return, AttributeKind::SExt));
+        assert!(call_site.has_attribute_kind(AttributeLoc::Param(0), AttributeKind::SExt));
+        assert!(call_site.has_attribute_kind(AttributeLoc::Function, AttributeKind::NoUnwind));
+    }
+
+    call_site.remove_attribute_kind(AttributeLoc::Param(0), AttributeKind::SExt);
+
+    #[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+    assert!(!call_site.has_attribute_kind(AttributeLoc::Param(0), AttributeKind::SExt));
+
+    let ret = call_site.try_as_basic_value().left().unwrap();
+
+    builder.build_return(Some(&ret));
+
+    assert!(function.verify().is_ok());
+}
@@ -1,5 +1,6 @@
 extern crate inkwell;
 
+use self::inkwell::IntPredicate;
 use self::inkwell::context::Context;
 use self::inkwell::values::InstructionOpcode;
 
@@ -161,3 +162,201 @@ fn test_no_parent() {
 
     assert!(basic_block.get_parent().is_none());
 }
+
+#[test]
+fn test_basic_blocks_iter() {
+    let context = Context::create();
+    let module = context.create_module("test");
+
+    let void_type = context.void_type();
+    let fn_type = void_type.fn_type(&[], false);
+
+    let function = module.add_function("testing", &fn_type, None);
+
+    assert_eq!(function.basic_blocks().count(), 0);
+    assert!(function.basic_blocks().next_back().is_none());
+
+    let entry = context.append_basic_block(&function, "entry");
+    let middle = context.append_basic_block(&function, "middle");
+    let end = context.append_basic_block(&function, "end");
+
+    let basic_blocks: Vec<_> = function.basic_blocks().collect();
+
+    assert_eq!(basic_blocks.len(), 3);
+    assert_eq!(basic_blocks[0], entry);
+    assert_eq!(basic_blocks[1], middle);
+    assert_eq!(basic_blocks[2], end);
+
+    let basic_blocks: Vec<_> = function.basic_blocks().rev().collect();
+
+    assert_eq!(basic_blocks[0], end);
+    assert_eq!(basic_blocks[1], middle);
+    assert_eq!(basic_blocks[2], entry);
+
+    // Both ends meet in the middle without yielding a block twice
+    let mut iter = function.basic_blocks();
+
+    assert_eq!(iter.next().unwrap(), entry);
+    assert_eq!(iter.next_back().unwrap(), end);
+    assert_eq!(iter.next().unwrap(), middle);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+}
+
+#[test]
+fn test_instructions_iter() {
+    let context = Context::create();
+    let module = context.create_module("test");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[], false);
+
+    let function = module.add_function("testing", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    assert_eq!(basic_block.instructions().count(), 0);
+
+    builder.position_at_end(&basic_block);
+
+    let ptr = builder.build_alloca(&i32_type, "ptr");
+
+    builder.build_store(&ptr, &i32_type.const_int(1, false));
+
+    let value = builder.build_load(&ptr, "value");
+
+    builder.build_return(Some(&value));
+
+    let opcodes: Vec<_> = basic_block.instructions().map(|instruction| instruction.get_opcode()).collect();
+
+    assert_eq!(opcodes, vec![InstructionOpcode::Alloca, InstructionOpcode::Store, InstructionOpcode::Load, InstructionOpcode::Return]);
+
+    let opcodes: Vec<_> = basic_block.instructions().rev().map(|instruction| instruction.get_opcode()).collect();
+
+    assert_eq!(opcodes, vec![InstructionOpcode::Return, InstructionOpcode::Load, InstructionOpcode::Store, InstructionOpcode::Alloca]);
+    assert_eq!(basic_block.instructions().filter(|instruction| instruction.get_opcode() == InstructionOpcode::Load).count(), 1);
+}
+
+#[test]
+fn test_successors_and_predecessors() {
+    let context = Context::create();
+    let module = context.create_module("test");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type], false);
+
+    let function = module.add_function("testing", &fn_type, None);
+    let entry = context.append_basic_block(&function, "entry");
+    let then_block = context.append_basic_block(&function, "then");
+    let else_block = context.append_basic_block(&function, "else");
+    let end = context.append_basic_block(&function, "end");
+
+    assert_eq!(entry.successors().count(), 0);
+    assert_eq!(end.predecessors().count(), 0);
+
+    builder.position_at_end(&entry);
+
+    let param = function.get_first_param().unwrap().into_int_value();
+    let zero = i32_type.const_int(0, false);
+    let one = i32_type.const_int(1, false);
+    let cond = builder.build_int_compare(IntPredicate::EQ, &param, &zero, "cond");
+    let branch = builder.build_conditional_branch(&cond, &then_block, &else_block);
+
+    builder.position_at_end(&then_block);
+    builder.build_unconditional_branch(&end);
+
+    // Both edges of the switch lead to the end block
+    builder.position_at_end(&else_block);
+    builder.build_switch(&param, &end, &[(&one, &end)]);
+
+    builder.position_at_end(&end);
+    builder.build_return(Some(&param));
+
+    let successors: Vec<_> = entry.successors().collect();
+
+    assert_eq!(successors.len(), 2);
+    assert_eq!(successors[0], then_block);
+    assert_eq!(successors[1], else_block);
+    assert_eq!(else_block.successors().count(), 2);
+    assert!(else_block.successors().all(|bb| bb == end));
+    assert_eq!(end.successors().count(), 0);
+
+    assert_eq!(entry.predecessors().count(), 0);
+    assert_eq!(then_block.predecessors().count(), 1);
+    assert_eq!(then_block.predecessors().next().unwrap(), entry);
+    assert_eq!(end.predecessors().filter(|bb| *bb == then_block).count(), 1);
+    assert_eq!(end.predecessors().filter(|bb| *bb == else_block).count(), 2);
+
+    assert_eq!(branch.get_num_successors(), 2);
+    assert_eq!(branch.get_successor(0).unwrap(), then_block);
+    assert_eq!(branch.get_successor(1).unwrap(), else_block);
+    assert!(branch.get_successor(2).is_none());
+    assert_eq!(cond.as_instruction().unwrap().get_num_successors(), 0);
+    assert!(cond.as_instruction().unwrap().get_successor(0).is_none());
+
+    assert!(branch.set_successor(1, &end));
+    assert!(!branch.set_successor(2, &end));
+    assert_eq!(branch.get_successor(1).unwrap(), end);
+    assert_eq!(else_block.predecessors().count(), 0);
+    assert_eq!(end.predecessors().filter(|bb| *bb == entry).count(), 1);
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_split_at() {
+    let context = Context::create();
+    let module = context.create_module("test");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type], false);
+
+    let function = module.add_function("testing", &fn_type, None);
+    let entry = context.append_basic_block(&function, "entry");
+    let end = context.append_basic_block(&function, "end");
+
+    builder.position_at_end(&entry);
+
+    let param = function.get_first_param().unwrap().into_int_value();
+    let double = builder.build_int_add(&param, &param, "double");
+    let triple = builder.build_int_add(&double, &param, "triple");
+
+    builder.build_unconditional_branch(&end);
+    builder.position_at_end(&end);
+
+    let phi = builder.build_phi(&i32_type, "phi");
+
+    phi.add_incoming(&[(&triple, &entry)]);
+    builder.build_return(Some(&phi.as_basic_value()));
+
+    let triple_instruction = triple.as_instruction().unwrap();
+
+    // Phi nodes, foreign instructions and unterminated blocks can't be split
+    assert!(end.split_at(&phi.as_instruction(), "tail").is_none());
+    assert!(end.split_at(&triple_instruction, "tail").is_none());
+
+    let tail = entry.split_at(&triple_instruction, "tail").unwrap();
+
+    assert_eq!(entry.get_next_basic_block().unwrap(), tail);
+    assert_eq!(tail.get_next_basic_block().unwrap(), end);
+    assert_eq!(entry.successors().count(), 1);
+    assert_eq!(entry.successors().next().unwrap(), tail);
+    assert_eq!(tail.predecessors().count(), 1);
+    assert_eq!(tail.predecessors().next().unwrap(), entry);
+    assert_eq!(tail.successors().count(), 1);
+    assert_eq!(tail.successors().next().unwrap(), end);
+    assert_eq!(end.predecessors().count(), 1);
+    assert_eq!(end.predecessors().next().unwrap(), tail);
+
+    // The split off instructions keep their identity and names
+    assert_eq!(triple_instruction.get_parent().unwrap(), tail);
+    assert_eq!(triple.get_name(), &*CString::new("triple").unwrap());
+    assert_eq!(entry.get_first_instruction().unwrap(), double.as_instruction().unwrap());
+    assert_eq!(entry.get_last_instruction().unwrap().get_opcode(), InstructionOpcode::Br);
+    assert_eq!(tail.instructions().count(), 2);
+
+    // The successor's phi now flows in from the new block
+    assert_eq!(phi.get_incoming(0).unwrap().1, tail);
+    assert!(function.verify().is_ok());
+}
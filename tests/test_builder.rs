@@ -1,13 +1,29 @@
 extern crate inkwell;
-
-use self::inkwell::{AddressSpace, OptimizationLevel};
+#[cfg(feature = "llvm3-7")]
+extern crate llvm_sys_37 as llvm_sys;
+#[cfg(feature = "llvm3-8")]
+extern crate llvm_sys_38 as llvm_sys;
+#[cfg(feature = "llvm3-9")]
+extern crate llvm_sys_39 as llvm_sys;
+#[cfg(feature = "llvm4-0")]
+extern crate llvm_sys_40 as llvm_sys;
+
+use self::inkwell::{AddressSpace, AtomicOrdering, CallingConvention, AtomicRMWBinOp, FenceOrdering, IntPredicate, OptimizationLevel};
 use self::inkwell::context::Context;
-use self::inkwell::builder::Builder;
+use self::inkwell::builder::{Builder, BuilderError};
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use self::inkwell::{CmpXchgFailureOrdering, CmpXchgOrderings};
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+use self::inkwell::memory_buffer::MemoryBuffer;
 use self::inkwell::targets::{InitializationConfig, Target};
+use self::inkwell::types::VectorType;
+use self::inkwell::values::InstructionOpcode;
+use self::llvm_sys::LLVMOpcode;
 
-use std::ffi::CString;
 use std::mem::transmute;
+use std::panic::{catch_unwind, resume_unwind};
 use std::ptr::null;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[test]
 fn test_build_call() {
@@ -32,11 +48,67 @@ fn test_build_call() {
 
     builder.position_at_end(&basic_block2);
 
-    let pi2 = builder.build_call(&function, &[], "get_pi", false).left().unwrap();
+    let pi2 = builder.build_call(&function, &[], "get_pi").try_as_basic_value().left().unwrap();
 
     builder.build_return(Some(&pi2));
 }
 
+#[test]
+fn test_build_indirect_call() {
+    let context = Context::create();
+    let module = context.create_module("indirect");
+    let builder = context.create_builder();
+
+    let i32_type = context.i32_type();
+    let unary_fn_type = i32_type.fn_type(&[&i32_type], false);
+    let unary_fn_ptr_type = unary_fn_type.ptr_type(AddressSpace::Generic);
+
+    // Roughly: fn apply(f: extern fn(i32) -> i32, x: i32) -> i32 { f(x) }
+    let fn_type = i32_type.fn_type(&[&unary_fn_ptr_type, &i32_type], false);
+    let function = module.add_function("apply", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let fn_ptr = function.get_nth_param(0).unwrap().into_pointer_value();
+    let x = function.get_nth_param(1).unwrap();
+    let call_site = builder.build_indirect_call(&fn_ptr, &[&x], "call").unwrap();
+
+    assert!(!call_site.is_tail_call());
+
+    call_site.set_tail_call(true);
+
+    assert!(call_site.is_tail_call());
+    assert_eq!(call_site.get_call_convention(), CallingConvention::C);
+
+    call_site.set_call_convention(CallingConvention::Fast);
+
+    assert_eq!(call_site.get_call_convention(), CallingConvention::Fast);
+
+    // x86_vectorcallcc has no variant of its own
+    call_site.set_call_convention(CallingConvention::Other(80));
+
+    assert_eq!(call_site.get_call_convention(), CallingConvention::Other(80));
+    assert_eq!(call_site.as_instruction().get_opcode(), InstructionOpcode::Call);
+    assert_eq!(function.get_call_conventions(), CallingConvention::C);
+
+    function.set_call_conventions(CallingConvention::Cold);
+
+    assert_eq!(function.get_call_conventions(), CallingConvention::Cold);
+
+    let result = call_site.try_as_basic_value().left().unwrap();
+    let not_fn_ptr = builder.build_alloca(&i32_type, "not_fn_ptr");
+
+    assert_eq!(builder.build_indirect_call(&not_fn_ptr, &[&x], "bad_call").unwrap_err(), BuilderError::TypeMismatch {
+        expected: "a pointer to a function".into(),
+        found: "i32*".into(),
+    });
+
+    builder.build_return(Some(&result));
+
+    assert!(function.verify().is_ok());
+}
+
 #[test]
 fn test_null_checked_ptr_ops() {
     Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
@@ -421,16 +493,16 @@ fn test_no_builder_double_free() {
 
 #[test]
 fn test_no_builder_double_free2() {
+    let context = Context::create();
     let builder = {
-        let context = Context::create();
+        let context2 = Context::create();
 
-        context.create_builder()
+        drop(context2.create_builder());
 
-        // Original Context drops fine
+        // Builder and its Context drop fine
+        context.create_builder()
     };
 
-    // Builder continues to function with different context
-    let context = Context::create();
     let module = context.create_module("my_mod");
     let void_type = context.void_type();
     let fn_type = void_type.fn_type(&[], false);
@@ -440,8 +512,624 @@ fn test_no_builder_double_free2() {
     builder.position_at_end(&entry);
     builder.build_unreachable();
 
-    assert_eq!(module.print_to_string(), &*CString::new("; ModuleID = \'my_mod\'\n\ndefine void @my_fn() {\nentry:\n  unreachable\n}\n").unwrap());
+    assert_eq!(module.print_to_string().to_string(), "; ModuleID = \'my_mod\'\n\ndefine void @my_fn() {\nentry:\n  unreachable\n}\n");
+
+    // Context drops fine
+    // Builder drops fine
+}
+
+#[test]
+fn test_atomicrmw() {
+    let context = Context::create();
+    let module = context.create_module("atomics");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let i32_ptr_type = i32_type.ptr_type(AddressSpace::Generic);
+    let fn_type = i32_type.fn_type(&[&i32_ptr_type], false);
+    let fn_value = module.add_function("inc_refcount", &fn_type, None);
+    let entry = fn_value.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let ptr = fn_value.get_first_param().unwrap().into_pointer_value();
+    let one = i32_type.const_int(1, false);
+    let old = builder.build_atomicrmw(AtomicRMWBinOp::Add, &ptr, &one, AtomicOrdering::Monotonic, false, "old");
+
+    builder.build_fence(FenceOrdering::Acquire, false, "");
+    builder.build_return(Some(&old));
+
+    assert!(old.as_instruction().is_some());
+    assert_eq!(old.get_name().to_str(), Ok("old"));
+    assert!(fn_value.verify().is_ok());
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_cmpxchg() {
+    let context = Context::create();
+    let module = context.create_module("atomics");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let i32_ptr_type = i32_type.ptr_type(AddressSpace::Generic);
+    let fn_type = context.bool_type().fn_type(&[&i32_ptr_type], false);
+    let fn_value = module.add_function("try_lock", &fn_type, None);
+    let entry = fn_value.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let ptr = fn_value.get_first_param().unwrap().into_pointer_value();
+    let zero = i32_type.const_int(0, false);
+    let one = i32_type.const_int(1, false);
+    let orderings = CmpXchgOrderings {
+        success: AtomicOrdering::AcquireRelease,
+        failure: CmpXchgFailureOrdering::Monotonic,
+    };
+    let pair = builder.build_cmpxchg(&ptr, &zero, &one, orderings, false, "pair").unwrap();
+    let success = builder.build_extract_value(&pair, 1, "success").into_int_value();
+
+    builder.build_return(Some(&success));
+
+    assert_eq!(pair.get_name().to_str(), Ok("pair"));
+    assert!(fn_value.verify().is_ok());
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_cmpxchg_orderings() {
+    let context = Context::create();
+    let module = context.create_module("atomics");
+    let builder = context.create_builder();
+    let checked_builder = context.create_checked_builder();
+    let i32_type = context.i32_type();
+    let i32_ptr_type = i32_type.ptr_type(AddressSpace::Generic);
+    let fn_type = context.void_type().fn_type(&[&i32_ptr_type], false);
+    let fn_value = module.add_function("try_lock", &fn_type, None);
+    let entry = fn_value.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+    checked_builder.position_at_end(&entry);
+
+    let ptr = fn_value.get_first_param().unwrap().into_pointer_value();
+    let zero = i32_type.const_int(0, false);
+    let one = i32_type.const_int(1, false);
+    let orderings = |success, failure| CmpXchgOrderings { success, failure };
+
+    let too_strong = [
+        orderings(AtomicOrdering::Monotonic, CmpXchgFailureOrdering::Acquire),
+        orderings(AtomicOrdering::AcquireRelease, CmpXchgFailureOrdering::SequentiallyConsistent),
+    ];
+
+    for &orderings in &too_strong {
+        let err = Some(BuilderError::StrongerFailureOrdering {
+            success: orderings.success,
+            failure: orderings.failure,
+        });
+
+        assert_eq!(builder.build_cmpxchg(&ptr, &zero, &one, orderings, false, "pair").err(), err);
+        assert_eq!(checked_builder.build_cmpxchg(&ptr, &zero, &one, orderings, false, "pair").err(), err);
+    }
+
+    // Acquire and Release aren't comparable, so neither is stronger than the other
+    let allowed = [
+        orderings(AtomicOrdering::Release, CmpXchgFailureOrdering::Acquire),
+        orderings(AtomicOrdering::SequentiallyConsistent, CmpXchgFailureOrdering::SequentiallyConsistent),
+    ];
+
+    for &orderings in &allowed {
+        assert!(builder.build_cmpxchg(&ptr, &zero, &one, orderings, false, "pair").is_ok());
+        assert!(checked_builder.build_cmpxchg(&ptr, &zero, &one, orderings, false, "pair").is_ok());
+    }
+}
+
+static CLEANUP_RAN: AtomicBool = AtomicBool::new(false);
+
+extern "C-unwind" fn throw_from_rust() {
+    resume_unwind(Box::new(42i32));
+}
+
+extern "C" fn mark_cleanup_ran() {
+    CLEANUP_RAN.store(true, Ordering::SeqCst);
+}
+
+#[test]
+fn test_landing_pad_unwinding() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = context.create_module("unwind");
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    let exception_type = context.struct_type(&[&i8_ptr_type, &context.i32_type()], false);
+    let void_fn_type = void_type.fn_type(&[], false);
+
+    let throw_fn = module.add_function("throw_from_rust", &void_fn_type, None);
+    let mark_fn = module.add_function("mark_cleanup_ran", &void_fn_type, None);
+    let personality_fn = module.add_function("__gcc_personality_v0", &context.i32_type().fn_type(&[], true), None);
+
+    // Roughly: fn run() { defer mark_cleanup_ran(); throw_from_rust(); }
+    let function = module.add_function("run", &void_fn_type, None);
+    let entry = function.append_basic_block("entry");
+    let then_block = function.append_basic_block("then");
+    let catch_block = function.append_basic_block("catch");
+
+    builder.position_at_end(&entry);
+
+    let invoke = builder.build_invoke(&throw_fn, &[], &then_block, &catch_block, "");
+
+    assert_eq!(invoke.as_instruction().get_opcode(), InstructionOpcode::Invoke);
+
+    builder.position_at_end(&then_block);
+    builder.build_return(None);
+
+    builder.position_at_end(&catch_block);
+
+    let exception = builder.build_landing_pad(&exception_type, &personality_fn, &[], true, "exception");
+
+    builder.build_call(&mark_fn, &[], "");
+
+    let resume = builder.build_resume(&exception);
+
+    assert_eq!(resume.get_opcode(), InstructionOpcode::Resume);
+    assert_eq!(exception.as_instruction().unwrap().get_opcode(), InstructionOpcode::LandingPad);
+    assert!(function.verify().is_ok());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    execution_engine.add_global_mapping(&throw_fn, throw_from_rust as *const () as usize);
+    execution_engine.add_global_mapping(&mark_fn, mark_cleanup_ran as *const () as usize);
+
+    let addr = execution_engine.get_function_address("run").unwrap();
+    let run: extern "C-unwind" fn() = unsafe { transmute(addr) };
+
+    let payload = catch_unwind(|| run()).unwrap_err();
+
+    assert_eq!(payload.downcast_ref::<i32>(), Some(&42));
+    assert!(CLEANUP_RAN.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_select() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = context.create_module("select");
+    let builder = context.create_builder();
+    let bool_type = context.bool_type();
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type, &i32_type], false);
+    let function = module.add_function("max", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let a = function.get_nth_param(0).unwrap().into_int_value();
+    let b = function.get_nth_param(1).unwrap().into_int_value();
+    let a_gt_b = builder.build_int_compare(IntPredicate::SGT, &a, &b, "a_gt_b");
+    let max = builder.build_select(&a_gt_b, &a, &b, "max");
+
+    builder.build_return(Some(&max));
+
+    // Vectors of i1 select per element
+    let vec_fn_type = i32_type.vec_type(2).fn_type(&[], false);
+    let vec_function = module.add_function("vec_select", &vec_fn_type, None);
+    let entry = vec_function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let condition = VectorType::const_vector(&[&bool_type.const_int(1, false), &bool_type.const_int(0, false)]);
+    let left = VectorType::const_vector(&[&i32_type.const_int(1, false), &i32_type.const_int(2, false)]);
+    let right = VectorType::const_vector(&[&i32_type.const_int(3, false), &i32_type.const_int(4, false)]);
+    let selected = builder.build_select(&condition, &left, &right, "selected");
+
+    builder.build_return(Some(&selected));
+
+    assert!(module.verify().is_ok());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+    let addr = execution_engine.get_function_address("max").unwrap();
+    let max: extern "C" fn(i32, i32) -> i32 = unsafe { transmute(addr) };
+
+    assert_eq!(max(1, 2), 2);
+    assert_eq!(max(-3, -7), -3);
+}
+
+#[test]
+fn test_select_non_bool_condition() {
+    let context = Context::create();
+    let module = context.create_module("select");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type], false);
+    let function = module.add_function("bad_select", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let a = function.get_first_param().unwrap();
+    let selected = builder.build_select(&a, &a, &a, "bad");
+
+    builder.build_return(Some(&selected));
+
+    // The select is built as is, and left for the verifier to reject
+    assert!(module.verify().unwrap_err().get_message().contains("select"));
+}
+
+#[test]
+fn test_shuffle_vector() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = context.create_module("shuffle");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let vec_type = i32_type.vec_type(2);
+    let fn_type = i32_type.fn_type(&[&i32_type, &i32_type], false);
+    let function = module.add_function("second_of_swapped", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let a = function.get_nth_param(0).unwrap();
+    let b = function.get_nth_param(1).unwrap();
+    let zero = i32_type.const_int(0, false);
+    let one = i32_type.const_int(1, false);
+    let vec = builder.build_insert_element(&vec_type.get_undef(), &a, &zero, "vec").into_vector_value();
+    let vec = builder.build_insert_element(&vec, &b, &one, "vec").into_vector_value();
+
+    // <a, b> -> <b, a>
+    let mask = VectorType::const_vector(&[&one, &zero]);
+    let swapped = builder.build_shuffle_vector(&vec, &vec_type.get_undef(), &mask, "swapped");
+    let second = builder.build_extract_element(&swapped, &one, "second");
+
+    builder.build_return(Some(&second));
+
+    assert!(function.verify().is_ok());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+    let addr = execution_engine.get_function_address("second_of_swapped").unwrap();
+    let second_of_swapped: extern "C" fn(i32, i32) -> i32 = unsafe { transmute(addr) };
+
+    assert_eq!(second_of_swapped(1, 2), 1);
+    assert_eq!(second_of_swapped(5, 7), 5);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn test_va_arg() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = context.create_module("va_arg");
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    let i32_type = context.i32_type();
+
+    // x86_64 SysV va_list: [1 x { i32, i32, i8*, i8* }]
+    let va_list_type = context.struct_type(&[&i32_type, &i32_type, &i8_ptr_type, &i8_ptr_type], false).array_type(1);
+    let va_intrinsic_type = void_type.fn_type(&[&i8_ptr_type], false);
+    let va_start = module.add_function("llvm.va_start", &va_intrinsic_type, None);
+    let va_end = module.add_function("llvm.va_end", &va_intrinsic_type, None);
+
+    // Roughly: fn first_vararg(count: i32, ...) -> i32
+    let fn_type = i32_type.fn_type(&[&i32_type], true);
+    let function = module.add_function("first_vararg", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let va_list = builder.build_alloca(&va_list_type, "va_list");
+    let va_list_i8 = builder.build_pointer_cast(&va_list, &i8_ptr_type, "va_list_i8");
+
+    builder.build_call(&va_start, &[&va_list_i8], "");
+
+    let arg = builder.build_va_arg(&va_list, &i32_type, "arg");
+
+    builder.build_call(&va_end, &[&va_list_i8], "");
+    builder.build_return(Some(&arg));
+
+    assert!(function.verify().is_ok());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+    let addr = execution_engine.get_function_address("first_vararg").unwrap();
+    let first_vararg: unsafe extern "C" fn(i32, ...) -> i32 = unsafe { transmute(addr) };
+
+    assert_eq!(unsafe { first_vararg(1, 42i32) }, 42);
+}
+
+#[test]
+fn test_no_debug_location() {
+    let context = Context::create();
+    let builder = context.create_builder();
+
+    assert!(builder.get_current_debug_location().is_none());
+
+    builder.unset_current_debug_location();
+
+    assert!(builder.get_current_debug_location().is_none());
+}
+
+#[cfg(any(feature = "llvm3-9", feature = "llvm4-0"))]
+#[test]
+fn test_set_current_debug_location() {
+    let context = Context::create();
+    let ir = r#"
+        define i32 @my_fn(i32 %x) !dbg !4 {
+        entry:
+          %y = add i32 %x, 1, !dbg !7
+          ret i32 %y, !dbg !7
+        }
+
+        !llvm.dbg.cu = !{!0}
+        !llvm.module.flags = !{!3}
+
+        !0 = distinct !DICompileUnit(language: DW_LANG_C99, file: !1, producer: "inkwell", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, enums: !2)
+        !1 = !DIFile(filename: "my_file.c", directory: "/tmp")
+        !2 = !{}
+        !3 = !{i32 2, !"Debug Info Version", i32 3}
+        !4 = distinct !DISubprogram(name: "my_fn", scope: !1, file: !1, line: 1, type: !5, isLocal: false, isDefinition: true, scopeLine: 1, isOptimized: false, unit: !0)
+        !5 = !DISubroutineType(types: !6)
+        !6 = !{null}
+        !7 = !DILocation(line: 2, column: 3, scope: !4)
+    "#;
+    let memory_buffer = MemoryBuffer::create_from_memory_range_copy(ir, "my_ir");
+    let module = context.create_module_from_ir(memory_buffer).unwrap();
+    let builder = context.create_builder();
+    let dbg_kind_id = context.get_kind_id("dbg");
+    let function = module.get_function("my_fn").unwrap();
+    let entry = function.get_first_basic_block().unwrap();
+    let add = entry.get_first_instruction().unwrap();
+    let location = add.get_metadata(dbg_kind_id).unwrap();
+
+    builder.position_before(&entry.get_terminator().unwrap());
+    builder.set_current_debug_location(&location);
+
+    assert_eq!(builder.get_current_debug_location(), Some(location));
+
+    let x = function.get_first_param().unwrap().into_int_value();
+    let z = builder.build_int_mul(&x, &x, "z");
+
+    assert_eq!(z.as_instruction().unwrap().get_metadata(dbg_kind_id), Some(location));
+
+    builder.unset_current_debug_location();
+
+    let w = builder.build_int_mul(&x, &x, "w");
+
+    assert!(builder.get_current_debug_location().is_none());
+    assert!(w.as_instruction().unwrap().get_metadata(dbg_kind_id).is_none());
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_checked_builder_operands() {
+    let context = Context::create();
+    let module = context.create_module("checked");
+    let builder = context.create_checked_builder();
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let struct_type = context.struct_type(&[&i32_type, &i64_type], false);
+    let fn_type = i32_type.fn_type(&[&i32_type, &i64_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let x = function.get_first_param().unwrap().into_int_value();
+    let y = function.get_nth_param(1).unwrap().into_int_value();
+
+    assert_eq!(builder.build_int_add(&x, &x, "sum"), Err(BuilderError::UnsetPosition));
+
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    assert_eq!(builder.build_int_add(&x, &y, "sum"), Err(BuilderError::TypeMismatch {
+        expected: "i32".into(),
+        found: "i64".into(),
+    }));
+    assert_eq!(builder.build_int_s_extend(&y, &i32_type, "ext"), Err(BuilderError::InvalidCast {
+        from: "i64".into(),
+        to: "i32".into(),
+    }));
+    assert_eq!(builder.build_call(&function, &[&x], "call"), Err(BuilderError::ArgumentCountMismatch {
+        expected: 2,
+        found: 1,
+    }));
+    assert_eq!(builder.build_conditional_branch(&x, &entry, &entry), Err(BuilderError::TypeMismatch {
+        expected: "i1".into(),
+        found: "i32".into(),
+    }));
+
+    let ptr = builder.build_alloca(&struct_type, "ptr").unwrap();
+
+    assert_eq!(builder.build_struct_gep(&ptr, 2, "gep"), Err(BuilderError::IndexOutOfRange { index: 2, len: 2 }));
+
+    let field = builder.build_struct_gep(&ptr, 1, "gep").unwrap();
+
+    assert_eq!(builder.build_store(&field, &x), Err(BuilderError::TypeMismatch {
+        expected: "i64".into(),
+        found: "i32".into(),
+    }));
+    assert!(builder.build_store(&field, &y).is_ok());
+    assert_eq!(builder.build_return(None), Err(BuilderError::TypeMismatch {
+        expected: "i32".into(),
+        found: "void".into(),
+    }));
+
+    let sum = builder.build_int_add(&x, &x, "sum").unwrap();
+
+    assert!(builder.build_return(Some(&sum)).is_ok());
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_checked_builder_casts_and_indices() {
+    let context = Context::create();
+    let module = context.create_module("checked");
+    let builder = context.create_checked_builder();
+    let i8_type = context.i8_type();
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let f128_type = context.f128_type();
+    let ppc_f128_type = context.ppc_f128_type();
+    let struct_type = context.struct_type(&[&i32_type, &i64_type], false);
+    let i8_ptr_type = i8_type.ptr_type(AddressSpace::Generic);
+    let fn_type = context.void_type().fn_type(&[&i32_type, &f128_type, &i8_ptr_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let x = function.get_first_param().unwrap().into_int_value();
+    let f = function.get_nth_param(1).unwrap().into_float_value();
+    let i8_ptr = function.get_nth_param(2).unwrap().into_pointer_value();
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    assert_eq!(builder.build_cast(LLVMOpcode::LLVMZExt, &x, &i8_type, "cast").err(), Some(BuilderError::InvalidCast {
+        from: "i32".into(),
+        to: "i8".into(),
+    }));
+    assert_eq!(builder.build_cast(LLVMOpcode::LLVMFPToSI, &x, &i64_type, "cast").err(), Some(BuilderError::InvalidCast {
+        from: "i32".into(),
+        to: "i64".into(),
+    }));
+    assert_eq!(builder.build_cast(LLVMOpcode::LLVMBitCast, &x, &i64_type, "cast").err(), Some(BuilderError::InvalidCast {
+        from: "i32".into(),
+        to: "i64".into(),
+    }));
+    assert_eq!(builder.build_cast(LLVMOpcode::LLVMAdd, &x, &i64_type, "cast").err(), Some(BuilderError::InvalidCast {
+        from: "i32".into(),
+        to: "i64".into(),
+    }));
+    assert!(builder.build_cast(LLVMOpcode::LLVMSExt, &x, &i64_type, "cast").is_ok());
+    assert!(builder.build_cast(LLVMOpcode::LLVMPtrToInt, &i8_ptr, &i64_type, "cast").is_ok());
+    assert!(builder.build_cast(LLVMOpcode::LLVMBitCast, &f, &ppc_f128_type, "cast").is_ok());
+    assert_eq!(builder.build_float_ext(&f, &ppc_f128_type, "ext"), Err(BuilderError::InvalidCast {
+        from: "fp128".into(),
+        to: "ppc_fp128".into(),
+    }));
+    assert_eq!(builder.build_float_trunc(&f, &ppc_f128_type, "trunc"), Err(BuilderError::InvalidCast {
+        from: "fp128".into(),
+        to: "ppc_fp128".into(),
+    }));
+
+    let ptr = builder.build_alloca(&struct_type, "ptr").unwrap();
+    let zero = i32_type.const_int(0, false);
+    let two = i32_type.const_int(2, false);
+    let one_i64 = i64_type.const_int(1, false);
+
+    assert_eq!(builder.build_gep(&ptr, &[&zero, &two], "gep"), Err(BuilderError::IndexOutOfRange { index: 2, len: 2 }));
+    assert_eq!(builder.build_gep(&ptr, &[&zero, &x], "gep"), Err(BuilderError::TypeMismatch {
+        expected: "a constant i32 struct index".into(),
+        found: "i32".into(),
+    }));
+    assert_eq!(builder.build_in_bounds_gep(&ptr, &[&zero, &one_i64], "gep"), Err(BuilderError::TypeMismatch {
+        expected: "a constant i32 struct index".into(),
+        found: "i64".into(),
+    }));
+    assert_eq!(builder.build_gep(&i8_ptr, &[&zero, &zero], "gep"), Err(BuilderError::TypeMismatch {
+        expected: "an array, vector or struct to index into".into(),
+        found: "i8".into(),
+    }));
+    assert!(builder.build_gep(&ptr, &[&x, &zero], "gep").is_ok());
+
+    let vector = i32_type.vec_type(2).get_undef();
+
+    assert_eq!(builder.build_extract_element(&vector, &two, "extract").err(), Some(BuilderError::IndexOutOfRange { index: 2, len: 2 }));
+    assert_eq!(builder.build_insert_element(&vector, &x, &two, "insert").err(), Some(BuilderError::IndexOutOfRange { index: 2, len: 2 }));
+    assert!(builder.build_extract_element(&vector, &x, "extract").is_ok());
+
+    let i8_value = i8_type.const_int(0, false);
+    let i8_len = i8_type.const_int(4, false);
+
+    assert_eq!(builder.build_memcpy(&i8_ptr, &i8_ptr, &i8_len, 1, false), Err(BuilderError::TypeMismatch {
+        expected: "an i32 or i64 length".into(),
+        found: "i8".into(),
+    }));
+    assert_eq!(builder.build_memset(&i8_ptr, &i8_value, &i8_len, 1, false), Err(BuilderError::TypeMismatch {
+        expected: "an i32 or i64 length".into(),
+        found: "i8".into(),
+    }));
+    assert!(builder.build_is_null(&i8_ptr, "is_null").is_ok());
+    assert!(builder.build_return(None).is_ok());
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_checked_builder_aggregates_and_exceptions() {
+    let context = Context::create();
+    let module = context.create_module("checked");
+    let builder = context.create_checked_builder();
+    let i8_type = context.i8_type();
+    let i32_type = context.i32_type();
+    let i8_ptr_type = i8_type.ptr_type(AddressSpace::Generic);
+    let pair_type = context.struct_type(&[&i8_ptr_type, &i32_type], false);
+    let fn_type = pair_type.fn_type(&[&i32_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let personality = module.add_function("__gxx_personality_v0", &i32_type.fn_type(&[], true), None);
+    let x = function.get_first_param().unwrap().into_int_value();
+    let entry = function.append_basic_block("entry");
+    let lpad = function.append_basic_block("lpad");
+
+    builder.position_at_end(&entry);
+
+    assert_eq!(builder.build_aggregate_return(&[&x]), Err(BuilderError::ReturnCountMismatch {
+        expected: 2,
+        found: 1,
+    }));
+    assert!(builder.build_aggregate_return(&[&i8_ptr_type.const_null(), &x]).is_ok());
+
+    builder.position_at_end(&lpad);
+
+    assert_eq!(builder.build_landing_pad(&pair_type, &personality, &[], false, "lpad").err(), Some(BuilderError::EmptyLandingPad));
+    assert_eq!(builder.build_landing_pad(&pair_type, &personality, &[&x], false, "lpad").err(), Some(BuilderError::TypeMismatch {
+        expected: "a constant pointer or array clause".into(),
+        found: "i32".into(),
+    }));
+
+    let landing_pad = builder.build_landing_pad(&pair_type, &personality, &[&i8_ptr_type.const_null()], false, "lpad").unwrap();
+
+    assert_eq!(builder.build_resume(&x), Err(BuilderError::TypeMismatch {
+        expected: "{ i8*, i32 }".into(),
+        found: "i32".into(),
+    }));
+    assert!(builder.build_resume(&landing_pad).is_ok());
+}
+
+#[test]
+fn test_checked_builder_position() {
+    let context = Context::create();
+    let module = context.create_module("checked");
+    let builder = context.create_checked_builder();
+    let i32_type = context.i32_type();
+    let fn_type = context.void_type().fn_type(&[&i32_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let other_function = module.add_function("other_fn", &fn_type, None);
+    let x = function.get_first_param().unwrap().into_int_value();
+    let entry = function.append_basic_block("entry");
+    let next = function.append_basic_block("next");
+    let other_entry = other_function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    assert_eq!(builder.build_unconditional_branch(&other_entry), Err(BuilderError::ForeignBasicBlock));
+
+    let branch = builder.build_unconditional_branch(&next).unwrap();
+
+    assert_eq!(builder.build_int_add(&x, &x, "sum"), Err(BuilderError::BlockTerminated));
+    assert_eq!(builder.position_at(&next, &branch), Err(BuilderError::ForeignInstruction));
+
+    builder.position_before(&branch).unwrap();
+
+    assert!(builder.build_int_add(&x, &x, "sum").is_ok());
+    assert_eq!(builder.build_return(None), Err(BuilderError::MisplacedTerminator));
+    assert_eq!(builder.build_phi(&i32_type, "phi"), Err(BuilderError::MisplacedPhi));
+
+    builder.position_at_end(&next);
+
+    let phi = builder.build_phi(&i32_type, "phi").unwrap();
+
+    phi.add_incoming(&[(&x, &entry)]);
+
+    assert!(builder.build_return(None).is_ok());
+
+    builder.position_at_end(&other_entry);
 
-    // 2nd Context drops fine
-    // Builds drops fine
+    assert!(builder.build_return(None).is_ok());
+    assert!(module.verify().is_ok());
 }
@@ -1,8 +1,16 @@
 extern crate inkwell;
 
-use self::inkwell::context::Context;
+use self::inkwell::OptimizationLevel;
+use self::inkwell::context::{Context, Diagnostic, DiagnosticSeverity};
+use self::inkwell::memory_buffer::MemoryBuffer;
+use self::inkwell::module::Module;
+use self::inkwell::targets::{InitializationConfig, Target};
 use self::inkwell::types::IntType;
 
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
 #[test]
 fn test_no_context_double_free() {
     let context = Context::create();
@@ -19,11 +27,12 @@ fn test_no_context_double_free2() {
     let int = context.i8_type();
     let context2 = int.get_context();
 
+    context2.i8_type().const_int(0, false);
+
     fn move_(_: Context) {}
 
+    drop(context2);
     move_(context);
-
-    context2.i8_type().const_int(0, false);
 }
 
 #[test]
@@ -52,9 +61,138 @@ fn test_basic_block_context() {
     let fn_value = module.add_function("my_fn", &fn_type, None);
     let basic_block = fn_value.append_basic_block("entry");
 
-    assert_eq!(basic_block.get_context(), Context::get_global());
+    assert_eq!(*basic_block.get_context(), context);
+    assert_ne!(basic_block.get_context(), Context::get_global());
 
     let basic_block2 = context.append_basic_block(&fn_value, "entry2");
 
     assert_eq!(*basic_block2.get_context(), context);
 }
+
+#[test]
+fn test_diagnostic_handler() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let diagnostics: Rc<RefCell<Vec<Diagnostic>>> = Rc::new(RefCell::new(Vec::new()));
+    let handler_diagnostics = diagnostics.clone();
+
+    context.set_diagnostic_handler(move |diagnostic| handler_diagnostics.borrow_mut().push(diagnostic.clone()));
+
+    let ir = "define void @bad_asm() {\n  call void asm sideeffect \"not_an_instruction\", \"\"()\n  ret void\n}\n";
+    let memory_buffer = MemoryBuffer::create_from_memory_range_copy(ir, "bad_asm");
+    let module = context.create_module_from_ir(memory_buffer).unwrap();
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    // Code generation reports the bad instruction to the handler rather than exiting
+    let _ = execution_engine.get_function_address("bad_asm");
+
+    let diagnostics = diagnostics.borrow();
+
+    assert!(!diagnostics.is_empty());
+    assert_eq!(diagnostics[0].get_severity(), DiagnosticSeverity::Error);
+    assert!(diagnostics[0].get_message().contains("not_an_instruction"));
+
+    context.remove_diagnostic_handler();
+}
+
+#[test]
+fn test_diagnostic_handler_panic() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+
+    context.set_diagnostic_handler(|diagnostic| panic!("diagnostic: {}", diagnostic.get_message()));
+
+    let ir = "define void @bad_asm() {\n  call void asm sideeffect \"not_an_instruction\", \"\"()\n  ret void\n}\n";
+    let memory_buffer = MemoryBuffer::create_from_memory_range_copy(ir, "bad_asm");
+    let module = context.create_module_from_ir(memory_buffer).unwrap();
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    // The panic is resumed once code generation has returned, rather than unwinding through LLVM
+    let result = panic::catch_unwind(AssertUnwindSafe(|| execution_engine.get_function_address("bad_asm")));
+    let payload = result.unwrap_err();
+
+    assert!(payload.downcast_ref::<String>().unwrap().contains("not_an_instruction"));
+
+    // Nothing is left over to be resumed by later calls
+    let _ = execution_engine.get_function_address("bad_asm");
+
+    context.remove_diagnostic_handler();
+}
+
+#[test]
+fn test_diagnostic_handler_panic_stays_with_its_context() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let other_context = Context::create();
+
+    context.set_diagnostic_handler(|diagnostic| panic!("diagnostic: {}", diagnostic.get_message()));
+    other_context.set_diagnostic_handler(|_| ());
+
+    // Running the constructors compiles them, but doesn't resume the handler's panic
+    let ir = "@llvm.global_ctors = appending global [1 x { i32, void ()*, i8* }] [{ i32, void ()*, i8* } { i32 65535, void ()* @bad_asm, i8* null }]\n\
+              define void @bad_asm() {\n  call void asm sideeffect \"not_an_instruction\", \"\"()\n  ret void\n}\n";
+    let memory_buffer = MemoryBuffer::create_from_memory_range_copy(ir, "bad_asm");
+    let module = context.create_module_from_ir(memory_buffer).unwrap();
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    unsafe {
+        execution_engine.run_static_constructors();
+    }
+
+    // Calls on another context aren't affected by the panic
+    let other_module = other_context.create_module("other");
+    let void_type = other_context.void_type();
+    let function = other_module.add_function("my_fn", &void_type.fn_type(&[], false), None);
+    let builder = other_context.create_builder();
+
+    builder.position_at_end(&other_context.append_basic_block(&function, "entry"));
+    builder.build_return(None);
+
+    let other_execution_engine = other_module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    assert!(other_execution_engine.get_function_address("my_fn").is_ok());
+
+    // The next call on the context it was raised on resumes it
+    let result = panic::catch_unwind(AssertUnwindSafe(|| execution_engine.get_function_address("bad_asm")));
+    let payload = result.unwrap_err();
+
+    assert!(payload.downcast_ref::<String>().unwrap().contains("not_an_instruction"));
+}
+
+#[test]
+fn test_scoped_context() {
+    let (num_functions, ir) = Context::scope(|context| {
+        let module = context.create_module("my_mod");
+        let builder = context.create_builder();
+        let i32_type = context.i32_type();
+        let fn_type = i32_type.fn_type(&[&i32_type], false);
+        let function = module.add_function("my_fn", &fn_type, None);
+        let entry = context.append_basic_block(&function, "entry");
+
+        builder.position_at_end(&entry);
+        builder.build_return(Some(&function.get_first_param().unwrap()));
+
+        assert_eq!(*module.get_context(), *context);
+        assert!(module.verify().is_ok());
+
+        (module.functions().count(), module.print_to_string().to_string())
+    });
+
+    assert_eq!(num_functions, 1);
+    assert!(ir.contains("define i32 @my_fn(i32"));
+}
+
+#[test]
+fn test_global_context_values() {
+    let context = Context::get_global();
+    let module = Module::create("my_mod");
+    let fn_type = context.void_type().fn_type(&[], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let basic_block = function.append_basic_block("entry");
+
+    assert_eq!(module.get_context(), context);
+    assert_eq!(basic_block.get_context(), context);
+}
@@ -1,9 +1,18 @@
 extern crate inkwell;
 
-use self::inkwell::OptimizationLevel;
+use self::inkwell::{OptimizationLevel, install_fatal_error_handler, reset_fatal_error_handler};
 use self::inkwell::context::Context;
-use self::inkwell::execution_engine::FunctionLookupError;
-use self::inkwell::targets::{InitializationConfig, Target};
+use self::inkwell::execution_engine::{ExecutionEngine, FunctionLookupError, PerfMapListener};
+use self::inkwell::memory_buffer::MemoryBuffer;
+use self::inkwell::memory_manager::PageMemoryManager;
+use self::inkwell::module::Module;
+use self::inkwell::module::Linkage::{InternalLinkage, PrivateLinkage};
+use self::inkwell::targets::{CodeModel, InitializationConfig, Target};
+
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::process::Command;
 
 #[test]
 fn test_get_function_address() {
@@ -39,6 +48,355 @@ fn test_get_function_address() {
     assert!(execution_engine.get_function_address("func").is_ok());
 }
 
+#[test]
+fn test_get_function() {
+    let context = Context::create();
+    let module = context.create_module("jit");
+    let builder = context.create_builder();
+    let i64_type = context.i64_type();
+    let fn_type = i64_type.fn_type(&[&i64_type, &i64_type, &i64_type], false);
+
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let function = module.add_function("sum", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+
+    let x = function.get_nth_param(0).unwrap().into_int_value();
+    let y = function.get_nth_param(1).unwrap().into_int_value();
+    let z = function.get_nth_param(2).unwrap().into_int_value();
+
+    let sum = builder.build_int_add(&x, &y, "sum");
+    let sum = builder.build_int_add(&sum, &z, "sum");
+
+    builder.build_return(Some(&sum));
+
+    let void_type = context.void_type();
+    let function = module.add_function("nothing", &void_type.fn_type(&[], false), None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+    builder.build_return(None);
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    unsafe {
+        assert_eq!(execution_engine.get_function::<unsafe extern "C" fn()>("errors"), Err(FunctionLookupError::FunctionNotFound));
+        assert_eq!(execution_engine.get_function::<unsafe extern "C" fn(u64, u64) -> u64>("sum"), Err(FunctionLookupError::SignatureMismatch));
+        assert_eq!(execution_engine.get_function::<unsafe extern "C" fn(u64, u64, u64) -> u32>("sum"), Err(FunctionLookupError::SignatureMismatch));
+        assert_eq!(execution_engine.get_function::<unsafe extern "C" fn(u64, u64, u8) -> u64>("sum"), Err(FunctionLookupError::SignatureMismatch));
+        assert_eq!(execution_engine.get_function::<unsafe extern "C" fn() -> u64>("nothing"), Err(FunctionLookupError::SignatureMismatch));
+
+        let nothing = execution_engine.get_function::<unsafe extern "C" fn()>("nothing").unwrap();
+        let sum = execution_engine.get_function::<unsafe extern "C" fn(u64, u64, u64) -> u64>("sum").unwrap();
+
+        // The functions keep the engine's machine code alive
+        drop(execution_engine);
+
+        nothing.call();
+
+        assert_eq!(sum.call(1, 2, 3), 6);
+        assert_eq!((sum.as_raw())(4, 5, 6), 15);
+    }
+}
+
+#[test]
+fn test_interpreter_execution_engine() {
+    let context = Context::create();
+    let module = context.create_module("interpreted");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type], false);
+
+    let function = module.add_function("add_one", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+
+    let param = function.get_first_param().unwrap().into_int_value();
+    let sum = builder.build_int_add(&param, &i32_type.const_int(1, false), "sum");
+
+    builder.build_return(Some(&sum));
+
+    let execution_engine = module.create_interpreter_execution_engine().unwrap();
+    let arg = i32_type.create_generic_value(41, false);
+    let result = unsafe { execution_engine.run_function(&function, &[&arg]) };
+
+    assert_eq!(result.as_int(false), 42);
+    assert_eq!(execution_engine.get_function_address("add_one"), Err(FunctionLookupError::JITNotEnabled));
+    assert!(execution_engine.add_module(&module).is_err());
+
+    // The module already belongs to the interpreter
+    assert_eq!(module.create_interpreter_execution_engine().err(), Some("This module is already owned by an ExecutionEngine".into()));
+    assert!(module.create_jit_execution_engine(OptimizationLevel::None).is_err());
+    assert!(module.create_mcjit_execution_engine_with_options(OptimizationLevel::None, CodeModel::JITDefault, false, false).is_err());
+    assert!(module.create_mcjit_execution_engine_with_memory_manager(PageMemoryManager::new(), OptimizationLevel::None, CodeModel::JITDefault, false, false).is_err());
+}
+
+#[test]
+fn test_mcjit_execution_engine_with_options() {
+    let context = Context::create();
+    let module = context.create_module("mcjit");
+    let builder = context.create_builder();
+    let i64_type = context.i64_type();
+    let fn_type = i64_type.fn_type(&[&i64_type], false);
+
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let function = module.add_function("double", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+
+    let param = function.get_first_param().unwrap().into_int_value();
+    let sum = builder.build_int_add(&param, &param, "sum");
+
+    builder.build_return(Some(&sum));
+
+    let execution_engine = module.create_mcjit_execution_engine_with_options(OptimizationLevel::Less, CodeModel::JITDefault, true, true).unwrap();
+
+    assert!(execution_engine.add_module(&module).is_err());
+
+    unsafe {
+        let double = execution_engine.get_function::<unsafe extern "C" fn(u64) -> u64>("double").unwrap();
+
+        assert_eq!(double.call(21), 42);
+    }
+}
+
+#[test]
+fn test_failed_execution_engine_creation() {
+    let context = Context::create();
+
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let create_engines: Vec<for<'ctx> fn(&Module<'ctx>) -> Result<ExecutionEngine<'ctx>, String>> = vec![
+        |module| module.create_jit_execution_engine(OptimizationLevel::None),
+        |module| module.create_mcjit_execution_engine_with_options(OptimizationLevel::None, CodeModel::JITDefault, false, false),
+        |module| module.create_mcjit_execution_engine_with_memory_manager(PageMemoryManager::new(), OptimizationLevel::None, CodeModel::JITDefault, false, false),
+    ];
+
+    for create_engine in create_engines {
+        let module = context.create_module("no_target");
+        let builder = context.create_builder();
+        let function = module.add_function("my_fn", &context.void_type().fn_type(&[], false), None);
+        let basic_block = context.append_basic_block(&function, "entry");
+
+        builder.position_at_end(&basic_block);
+        builder.build_return(None);
+
+        // No target is registered for this triple
+        module.set_target("bogus-unknown-unknown");
+
+        let err = create_engine(&module).err().unwrap();
+
+        assert!(err.contains("bogus-unknown-unknown"), "{}", err);
+
+        // The module is left untouched by the failed attempt
+        assert_eq!(module.get_function("my_fn"), Some(function));
+        assert_eq!(function.get_first_basic_block(), Some(basic_block));
+        assert!(module.verify().is_ok());
+
+        module.set_target("");
+
+        let execution_engine = create_engine(&module).unwrap();
+
+        assert!(execution_engine.get_function_address("my_fn").is_ok());
+    }
+}
+
+#[test]
+fn test_modules_keep_execution_engine_alive() {
+    let context = Context::create();
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let module = context.create_module("owned");
+    let other_module = context.create_module("added");
+    let function = module.add_function("owned_fn", &void_type.fn_type(&[], false), None);
+    let other_function = other_module.add_function("added_fn", &void_type.fn_type(&[], false), None);
+
+    for function in &[function, other_function] {
+        let basic_block = context.append_basic_block(function, "entry");
+
+        builder.position_at_end(&basic_block);
+        builder.build_return(None);
+    }
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    execution_engine.add_module(&other_module).unwrap();
+
+    assert!(execution_engine.get_function_address("added_fn").is_ok());
+
+    // The engine and the modules it owns are only disposed of once both modules are dropped
+    drop(execution_engine);
+
+    assert_eq!(function.get_name().to_str(), Ok("owned_fn"));
+    assert_eq!(other_function.get_name().to_str(), Ok("added_fn"));
+    assert!(function.get_first_basic_block().is_some());
+
+    drop(module);
+
+    assert!(other_function.get_first_basic_block().is_some());
+}
+
+#[test]
+fn test_perf_map_listener() {
+    let context = Context::create();
+    let module = context.create_module("perf_map");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type], false);
+
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let functions = [
+        ("perf_map_first", None),
+        ("perf_map_internal", Some(&InternalLinkage)),
+        ("perf_map_private", Some(&PrivateLinkage)),
+        ("perf_map_last", None),
+    ];
+
+    for &(name, linkage) in &functions {
+        let function = module.add_function(name, &fn_type, linkage);
+        let basic_block = context.append_basic_block(&function, "entry");
+
+        builder.position_at_end(&basic_block);
+
+        let param = function.get_first_param().unwrap().into_int_value();
+        let product = builder.build_int_mul(&param, &param, "product");
+
+        builder.build_return(Some(&product));
+    }
+
+    let perf_map = PerfMapListener::create().unwrap();
+    let execution_engine = module.create_mcjit_execution_engine_with_perf_map(&perf_map, PageMemoryManager::new(), OptimizationLevel::None, CodeModel::JITDefault, false, false).unwrap();
+
+    assert_eq!(perf_map.get_path().to_str().unwrap(), format!("/tmp/perf-{}.map", std::process::id()));
+
+    // Only one function is looked up, but the whole module is compiled along with it
+    let last_address = execution_engine.get_function_address("perf_map_last").unwrap();
+
+    // Repeated lookups don't add entries
+    execution_engine.get_function_address("perf_map_first").unwrap();
+
+    assert!(perf_map.take_error().is_none());
+
+    let mut contents = String::new();
+
+    File::open(perf_map.get_path()).unwrap().read_to_string(&mut contents).unwrap();
+    fs::remove_file(perf_map.get_path()).unwrap();
+
+    let mut entries: Vec<(u64, u64, &str)> = contents.lines()
+                                                     .map(|line| {
+                                                         let fields: Vec<&str> = line.splitn(3, ' ').collect();
+
+                                                         (u64::from_str_radix(fields[0], 16).unwrap(), u64::from_str_radix(fields[1], 16).unwrap(), fields[2])
+                                                     })
+                                                     .filter(|&(_, _, name)| name.starts_with("perf_map_"))
+                                                     .collect();
+
+    entries.sort();
+
+    let mut names: Vec<&str> = entries.iter().map(|&(_, _, name)| name).collect();
+
+    names.sort();
+
+    // Private functions have no symbol to resolve
+    assert_eq!(names, vec!["perf_map_first", "perf_map_internal", "perf_map_last"]);
+
+    let last = entries.iter().find(|&&(_, _, name)| name == "perf_map_last").unwrap();
+
+    assert_eq!(last.0, last_address);
+
+    // Each function extends up to the next one, and they all share a code section
+    for window in entries.windows(2) {
+        assert!(window[0].1 > 0);
+        assert_eq!(window[0].0 + window[0].1, window[1].0);
+    }
+
+    assert!(entries.last().unwrap().1 > 0);
+}
+
+#[test]
+fn test_run_function_as_main() {
+    // Function pointers can't be made into constants yet, so the
+    // constructor list is written in textual IR
+    let ir = r#"
+        @counter = global i32 0
+        @llvm.global_ctors = appending global [1 x { i32, void ()*, i8* }] [{ i32, void ()*, i8* } { i32 65535, void ()* @init, i8* null }]
+        @llvm.global_dtors = appending global [1 x { i32, void ()*, i8* }] [{ i32, void ()*, i8* } { i32 65535, void ()* @fini, i8* null }]
+
+        define void @init() {
+        entry:
+            store i32 7, i32* @counter
+            ret void
+        }
+
+        define void @fini() {
+        entry:
+            store i32 -1, i32* @counter
+            ret void
+        }
+
+        define i32 @get_counter() {
+        entry:
+            %counter = load i32, i32* @counter
+            ret i32 %counter
+        }
+
+        ; Returns counter + argc * 10 + argv[1][0] * 100 + envp[0][0] * 10000
+        define i32 @main(i32 %argc, i8** %argv, i8** %envp) {
+        entry:
+            %counter = load i32, i32* @counter
+            %argc_part = mul i32 %argc, 10
+            %arg1_ptr = getelementptr i8*, i8** %argv, i64 1
+            %arg1 = load i8*, i8** %arg1_ptr
+            %arg1_char = load i8, i8* %arg1
+            %arg1_int = zext i8 %arg1_char to i32
+            %arg1_part = mul i32 %arg1_int, 100
+            %env0 = load i8*, i8** %envp
+            %env0_char = load i8, i8* %env0
+            %env0_int = zext i8 %env0_char to i32
+            %env0_part = mul i32 %env0_int, 10000
+            %sum1 = add i32 %counter, %argc_part
+            %sum2 = add i32 %sum1, %arg1_part
+            %sum3 = add i32 %sum2, %env0_part
+            ret i32 %sum3
+        }
+    "#;
+
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let memory_buffer = MemoryBuffer::create_from_memory_range_copy(ir, "main");
+    let module = context.create_module_from_ir(memory_buffer).unwrap();
+    let main = module.get_function("main").unwrap();
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    unsafe {
+        let get_counter = execution_engine.get_function::<unsafe extern "C" fn() -> i32>("get_counter").unwrap();
+
+        assert_eq!(get_counter.call(), 0);
+
+        execution_engine.run_static_constructors();
+
+        assert_eq!(get_counter.call(), 7);
+
+        let exit_code = execution_engine.run_function_as_main(&main, &["prog", "A"], &["B=1", "C=2"]);
+
+        assert_eq!(exit_code, 7 + 2 * 10 + 'A' as i32 * 100 + 'B' as i32 * 10000);
+
+        execution_engine.run_static_destructors();
+
+        assert_eq!(get_counter.call(), -1);
+    }
+}
+
 // #[test]
 // fn test_get_function_value() {
 //     let context = Context::create();
@@ -67,3 +425,55 @@ fn test_get_function_address() {
 
 //     assert!(execution_engine.get_function_value("func").is_ok());
 // }
+
+#[test]
+fn test_fatal_error_handler() {
+    // LLVM exits the process after a fatal error, so the error is triggered
+    // in a child process running just this test
+    if env::var("INKWELL_TRIGGER_FATAL_ERROR").is_err() {
+        let output = Command::new(env::current_exe().unwrap())
+            .args(&["test_fatal_error_handler", "--exact", "--nocapture"])
+            .env("INKWELL_TRIGGER_FATAL_ERROR", "1")
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(!output.status.success());
+        assert!(stderr.contains("Fatal LLVM error: MCJIT::runFunction does not support full-featured argument passing"), "{}", stderr);
+        assert!(stderr.contains("The fatal error handler panicked"), "{}", stderr);
+
+        return;
+    }
+
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    // The handler can reset itself, and its panic doesn't unwind into LLVM
+    install_fatal_error_handler(|reason| {
+        eprintln!("Fatal LLVM error: {}", reason);
+
+        reset_fatal_error_handler();
+
+        panic!("The fatal error handler panicked");
+    });
+
+    let context = Context::create();
+    let module = context.create_module("fatal");
+    let builder = context.create_builder();
+    let f64_type = context.f64_type();
+    let fn_type = context.void_type().fn_type(&[&f64_type], false);
+    let function = module.add_function("takes_f64", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+    builder.build_return(None);
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+    let arg = f64_type.create_generic_value(1.0);
+
+    // MCJIT can only run a few main-like signatures, anything else is a fatal error
+    unsafe {
+        execution_engine.run_function(&function, &[&arg]);
+    }
+
+    unreachable!("LLVM should have exited");
+}
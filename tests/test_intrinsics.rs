@@ -0,0 +1,282 @@
+extern crate inkwell;
+
+use self::inkwell::{AddressSpace, OptimizationLevel};
+use self::inkwell::context::Context;
+use self::inkwell::intrinsics::Intrinsic;
+use self::inkwell::targets::{InitializationConfig, Target};
+use self::inkwell::values::InstructionOpcode;
+
+use std::mem::transmute;
+
+#[test]
+fn test_intrinsic_names() {
+    let context = Context::create();
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let f64_type = context.f64_type();
+    let f32_vec_type = context.f32_type().vec_type(4);
+
+    assert_eq!(Intrinsic::Memcpy.get_name(&[i8_ptr_type.into(), i8_ptr_type.into(), i64_type.into()]), "llvm.memcpy.p0i8.p0i8.i64");
+    assert_eq!(Intrinsic::Memset.get_name(&[i8_ptr_type.into(), i32_type.into()]), "llvm.memset.p0i8.i32");
+    assert_eq!(Intrinsic::Sqrt.get_name(&[f64_type.into()]), "llvm.sqrt.f64");
+    assert_eq!(Intrinsic::Sqrt.get_name(&[f32_vec_type.into()]), "llvm.sqrt.v4f32");
+    assert_eq!(Intrinsic::Ctpop.get_name(&[i32_type.into()]), "llvm.ctpop.i32");
+    assert_eq!(Intrinsic::SAddWithOverflow.get_name(&[i64_type.into()]), "llvm.sadd.with.overflow.i64");
+    assert_eq!(Intrinsic::Trap.get_name(&[]), "llvm.trap");
+    assert_eq!(Intrinsic::LifetimeStart.get_name(&[]), "llvm.lifetime.start");
+}
+
+#[test]
+#[should_panic]
+fn test_intrinsic_missing_overloaded_type() {
+    Intrinsic::Sqrt.get_name(&[]);
+}
+
+#[test]
+fn test_get_intrinsic_function() {
+    let context = Context::create();
+    let module = context.create_module("intrinsics");
+    let i8_ptr_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    let i64_type = context.i64_type();
+    let f64_type = context.f64_type();
+
+    let sqrt = module.get_intrinsic_function(Intrinsic::Sqrt, &[f64_type.into()]);
+
+    assert_eq!(*sqrt.get_name(), *module.get_function("llvm.sqrt.f64").unwrap().get_name());
+    assert_eq!(sqrt, module.get_intrinsic_function(Intrinsic::Sqrt, &[f64_type.into()]));
+    assert_ne!(sqrt.get_intrinsic_id(), 0);
+    assert_eq!(sqrt.count_params(), 1);
+
+    let memcpy = module.get_intrinsic_function(Intrinsic::Memcpy, &[i8_ptr_type.into(), i8_ptr_type.into(), i64_type.into()]);
+
+    assert_eq!(memcpy.count_params(), 5);
+
+    let trap = module.get_intrinsic_function(Intrinsic::Trap, &[]);
+
+    assert_eq!(trap.count_params(), 0);
+    assert_ne!(trap.get_intrinsic_id(), 0);
+}
+
+#[test]
+fn test_mem_intrinsic_builders() {
+    let context = Context::create();
+    let module = context.create_module("intrinsics");
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+    let i8_type = context.i8_type();
+    let i8_ptr_type = i8_type.ptr_type(AddressSpace::Generic);
+    let i64_type = context.i64_type();
+    let fn_type = void_type.fn_type(&[&i8_ptr_type, &i8_ptr_type, &i64_type], false);
+    let function = module.add_function("copy_and_clear", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let dest = function.get_nth_param(0).unwrap().into_pointer_value();
+    let src = function.get_nth_param(1).unwrap().into_pointer_value();
+    let size = function.get_nth_param(2).unwrap().into_int_value();
+
+    let memcpy = builder.build_memcpy(&dest, &src, &size, 1, false);
+    let memmove = builder.build_memmove(&dest, &src, &size, 1, false);
+    let memset = builder.build_memset(&src, &i8_type.const_int(0, false), &size, 1, false);
+
+    builder.build_return(None);
+
+    assert_eq!(memcpy.get_opcode(), InstructionOpcode::Call);
+    assert_eq!(memmove.get_opcode(), InstructionOpcode::Call);
+    assert_eq!(memset.get_opcode(), InstructionOpcode::Call);
+    assert!(module.get_function("llvm.memcpy.p0i8.p0i8.i64").is_some());
+    assert!(module.get_function("llvm.memmove.p0i8.p0i8.i64").is_some());
+    assert!(module.get_function("llvm.memset.p0i8.i64").is_some());
+}
+
+#[test]
+fn test_dbg_intrinsic_builders() {
+    let context = Context::create();
+    let module = context.create_module("intrinsics");
+    let builder = context.create_builder();
+    let void_type = context.void_type();
+    let i32_type = context.i32_type();
+    let fn_type = void_type.fn_type(&[&i32_type], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    // Stand ins for a DILocalVariable and DIExpression, which can only come from parsed IR
+    let variable = context.metadata_node(&[]);
+    let expression = context.metadata_string("expression");
+    let x = builder.build_alloca(&i32_type, "x");
+    let param = function.get_first_param().unwrap();
+
+    let declare = builder.build_dbg_declare(&x, &variable, &expression);
+    let value = builder.build_dbg_value(&param, 0, &variable, &expression);
+
+    builder.build_return(None);
+
+    assert_eq!(declare.get_opcode(), InstructionOpcode::Call);
+    assert_eq!(value.get_opcode(), InstructionOpcode::Call);
+    assert!(module.get_function("llvm.dbg.declare").is_some());
+    assert!(module.get_function("llvm.dbg.value").is_some());
+
+    let ir = module.print_to_string().to_string();
+
+    // The variable's location is passed directly rather than in a node
+    assert!(ir.contains("call void @llvm.dbg.declare(metadata i32* %x, metadata !0, metadata !\"expression\")"));
+    assert!(ir.contains("call void @llvm.dbg.value(metadata i32 %0, i64 0, metadata !0, metadata !\"expression\")"));
+}
+
+#[test]
+fn test_dbg_value_constant() {
+    let context = Context::create();
+    let module = context.create_module("intrinsics");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let fn_type = context.void_type().fn_type(&[], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+    let variable = context.metadata_node(&[]);
+    let expression = context.metadata_string("expression");
+
+    builder.position_at_end(&entry);
+
+    let value = builder.build_dbg_value(&i32_type.const_int(1, false), 0, &variable, &expression);
+
+    builder.build_return(None);
+
+    // The constant is passed directly, and nothing else is left in the block
+    assert_eq!(entry.get_first_instruction(), Some(value));
+
+    let ir = module.print_to_string().to_string();
+
+    assert!(ir.contains("call void @llvm.dbg.value(metadata i32 1, i64 0, metadata !0, metadata !\"expression\")"), "{}", ir);
+}
+
+#[test]
+fn test_overflow_builders() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = context.create_module("intrinsics");
+    let builder = context.create_builder();
+    let bool_type = context.bool_type();
+    let i8_type = context.i8_type();
+    let fn_type = bool_type.fn_type(&[&i8_type, &i8_type], false);
+
+    let signed_add = module.add_function("signed_add_overflows", &fn_type, None);
+    let entry = signed_add.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let lhs = signed_add.get_nth_param(0).unwrap().into_int_value();
+    let rhs = signed_add.get_nth_param(1).unwrap().into_int_value();
+    let (_, overflow) = builder.build_int_add_with_overflow(&lhs, &rhs, true, "sum");
+
+    builder.build_return(Some(&overflow));
+
+    let unsigned_mul = module.add_function("unsigned_mul_overflows", &fn_type, None);
+    let entry = unsigned_mul.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let lhs = unsigned_mul.get_nth_param(0).unwrap().into_int_value();
+    let rhs = unsigned_mul.get_nth_param(1).unwrap().into_int_value();
+    let (_, overflow) = builder.build_int_mul_with_overflow(&lhs, &rhs, false, "product");
+
+    builder.build_return(Some(&overflow));
+
+    let signed_sub = module.add_function("signed_sub", &i8_type.fn_type(&[&i8_type, &i8_type], false), None);
+    let entry = signed_sub.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let lhs = signed_sub.get_nth_param(0).unwrap().into_int_value();
+    let rhs = signed_sub.get_nth_param(1).unwrap().into_int_value();
+    let (difference, _) = builder.build_int_sub_with_overflow(&lhs, &rhs, true, "difference");
+
+    builder.build_return(Some(&difference));
+
+    assert!(module.verify().is_ok());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    let signed_add_overflows: extern "C" fn(i8, i8) -> bool = unsafe { transmute(execution_engine.get_function_address("signed_add_overflows").unwrap()) };
+    let unsigned_mul_overflows: extern "C" fn(u8, u8) -> bool = unsafe { transmute(execution_engine.get_function_address("unsigned_mul_overflows").unwrap()) };
+    let signed_sub: extern "C" fn(i8, i8) -> i8 = unsafe { transmute(execution_engine.get_function_address("signed_sub").unwrap()) };
+
+    assert!(!signed_add_overflows(100, 27));
+    assert!(signed_add_overflows(100, 28));
+    assert!(signed_add_overflows(-100, -29));
+    assert!(!unsigned_mul_overflows(15, 17));
+    assert!(unsigned_mul_overflows(16, 16));
+    assert_eq!(signed_sub(-100, 29), 127);
+}
+
+#[test]
+fn test_overflow_builder_names() {
+    let context = Context::create();
+    let module = context.create_module("intrinsics");
+    let builder = context.create_builder();
+    let i8_type = context.i8_type();
+    let fn_type = i8_type.fn_type(&[&i8_type, &i8_type], false);
+    let function = module.add_function("add", &fn_type, None);
+    let entry = function.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let lhs = function.get_nth_param(0).unwrap().into_int_value();
+    let rhs = function.get_nth_param(1).unwrap().into_int_value();
+    let (sum, overflow) = builder.build_int_add_with_overflow(&lhs, &rhs, true, "sum");
+
+    assert_eq!(sum.get_name().to_str(), Ok("sum"));
+    assert_eq!(overflow.get_name().to_str(), Ok("sum.overflow"));
+
+    let (sum, overflow) = builder.build_int_add_with_overflow(&lhs, &rhs, true, "");
+
+    assert_eq!(sum.get_name().to_str(), Ok(""));
+    assert_eq!(overflow.get_name().to_str(), Ok(""));
+}
+
+#[test]
+fn test_call_math_intrinsics() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = context.create_module("intrinsics");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let f64_type = context.f64_type();
+
+    let ctpop = module.get_intrinsic_function(Intrinsic::Ctpop, &[i32_type.into()]);
+    let popcount = module.add_function("popcount", &i32_type.fn_type(&[&i32_type], false), None);
+    let entry = popcount.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let x = popcount.get_first_param().unwrap();
+    let count = builder.build_call(&ctpop, &[&x], "count").try_as_basic_value().left().unwrap();
+
+    builder.build_return(Some(&count));
+
+    let sqrt = module.get_intrinsic_function(Intrinsic::Sqrt, &[f64_type.into()]);
+    let square_root = module.add_function("square_root", &f64_type.fn_type(&[&f64_type], false), None);
+    let entry = square_root.append_basic_block("entry");
+
+    builder.position_at_end(&entry);
+
+    let x = square_root.get_first_param().unwrap();
+    let root = builder.build_call(&sqrt, &[&x], "root").try_as_basic_value().left().unwrap();
+
+    builder.build_return(Some(&root));
+
+    assert!(module.verify().is_ok());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+
+    let popcount: extern "C" fn(u32) -> u32 = unsafe { transmute(execution_engine.get_function_address("popcount").unwrap()) };
+    let square_root: extern "C" fn(f64) -> f64 = unsafe { transmute(execution_engine.get_function_address("square_root").unwrap()) };
+
+    assert_eq!(popcount(0b1011_0110), 5);
+    assert_eq!(square_root(16.), 4.);
+}
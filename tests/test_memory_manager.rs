@@ -0,0 +1,186 @@
+extern crate inkwell;
+
+use self::inkwell::OptimizationLevel;
+use self::inkwell::context::Context;
+use self::inkwell::memory_manager::{McJitMemoryManager, PageMemoryManager, SectionKind};
+use self::inkwell::module::Module;
+use self::inkwell::targets::{CodeModel, InitializationConfig, Target};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn build_add_global<'ctx>(context: &'ctx Context) -> Module<'ctx> {
+    let module = context.create_module("memory_manager");
+    let builder = context.create_builder();
+    let i64_type = context.i64_type();
+    let fn_type = i64_type.fn_type(&[&i64_type], false);
+
+    let global = module.add_global(&i64_type, None, "counter");
+
+    global.set_initializer(&i64_type.const_int(40, false));
+
+    let function = module.add_function("add_counter", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+
+    let param = function.get_first_param().unwrap().into_int_value();
+    let counter = builder.build_load(&global.as_pointer_value(), "counter").into_int_value();
+    let sum = builder.build_int_add(&param, &counter, "sum");
+
+    builder.build_store(&global.as_pointer_value(), &sum);
+    builder.build_return(Some(&sum));
+
+    module
+}
+
+#[test]
+fn test_page_memory_manager() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = build_add_global(&context);
+    let memory_manager = PageMemoryManager::new();
+    let usage_tracker = memory_manager.get_usage_tracker();
+
+    assert_eq!(usage_tracker.get_total_mapped_bytes(), 0);
+
+    let execution_engine = module.create_mcjit_execution_engine_with_memory_manager(memory_manager, OptimizationLevel::None, CodeModel::JITDefault, false, false).unwrap();
+
+    unsafe {
+        let add_counter = execution_engine.get_function::<unsafe extern "C" fn(u64) -> u64>("add_counter").unwrap();
+
+        // The writable global is still writable after finalization
+        assert_eq!(add_counter.call(2), 42);
+        assert_eq!(add_counter.call(2), 44);
+    }
+
+    let sections = usage_tracker.get_sections();
+    let code_bytes = usage_tracker.get_mapped_bytes(SectionKind::Code);
+    let data_bytes = usage_tracker.get_mapped_bytes(SectionKind::ReadWriteData);
+
+    assert!(sections.iter().any(|section| section.kind == SectionKind::Code && section.size > 0));
+    assert!(sections.iter().all(|section| section.mapped_size >= section.size));
+    assert!(code_bytes > 0);
+    assert!(data_bytes > 0);
+    assert!(usage_tracker.get_total_mapped_bytes() >= code_bytes + data_bytes);
+
+    drop(execution_engine);
+    drop(module);
+
+    // The memory is unmapped once the ExecutionEngine is disposed of
+    assert!(usage_tracker.get_sections().is_empty());
+    assert_eq!(usage_tracker.get_total_mapped_bytes(), 0);
+}
+
+struct LoggingMemoryManager {
+    inner: PageMemoryManager,
+    log: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl McJitMemoryManager for LoggingMemoryManager {
+    fn allocate_code_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str) -> *mut u8 {
+        self.log.borrow_mut().push("code");
+        self.inner.allocate_code_section(size, alignment, section_id, section_name)
+    }
+
+    fn allocate_data_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str, is_read_only: bool) -> *mut u8 {
+        self.log.borrow_mut().push("data");
+        self.inner.allocate_data_section(size, alignment, section_id, section_name, is_read_only)
+    }
+
+    fn finalize_memory(&mut self) -> Result<(), String> {
+        self.log.borrow_mut().push("finalize");
+        self.inner.finalize_memory()
+    }
+
+    fn destroy(&mut self) {
+        self.log.borrow_mut().push("destroy");
+        self.inner.destroy()
+    }
+}
+
+#[test]
+fn test_custom_memory_manager() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = build_add_global(&context);
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let memory_manager = LoggingMemoryManager {
+        inner: PageMemoryManager::new(),
+        log: log.clone(),
+    };
+
+    let execution_engine = module.create_mcjit_execution_engine_with_memory_manager(memory_manager, OptimizationLevel::None, CodeModel::JITDefault, false, false).unwrap();
+
+    assert!(log.borrow().is_empty());
+
+    unsafe {
+        let add_counter = execution_engine.get_function::<unsafe extern "C" fn(u64) -> u64>("add_counter").unwrap();
+
+        assert_eq!(add_counter.call(1), 41);
+    }
+
+    assert!(log.borrow().contains(&"code"));
+    assert!(log.borrow().contains(&"data"));
+    assert_eq!(log.borrow().last(), Some(&"finalize"));
+
+    drop(execution_engine);
+    drop(module);
+
+    assert_eq!(log.borrow().last(), Some(&"destroy"));
+}
+
+struct PanickingMemoryManager {
+    inner: PageMemoryManager,
+    log: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl McJitMemoryManager for PanickingMemoryManager {
+    fn allocate_code_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str) -> *mut u8 {
+        self.inner.allocate_code_section(size, alignment, section_id, section_name)
+    }
+
+    fn allocate_data_section(&mut self, size: usize, alignment: u32, section_id: u32, section_name: &str, is_read_only: bool) -> *mut u8 {
+        self.inner.allocate_data_section(size, alignment, section_id, section_name, is_read_only)
+    }
+
+    fn finalize_memory(&mut self) -> Result<(), String> {
+        self.log.borrow_mut().push("finalize");
+        self.inner.finalize_memory().unwrap();
+
+        panic!("finalize_memory panicked");
+    }
+
+    fn destroy(&mut self) {
+        self.log.borrow_mut().push("destroy");
+        self.inner.destroy();
+
+        panic!("destroy panicked");
+    }
+}
+
+#[test]
+fn test_panicking_memory_manager() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = build_add_global(&context);
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let memory_manager = PanickingMemoryManager {
+        inner: PageMemoryManager::new(),
+        log: log.clone(),
+    };
+
+    let execution_engine = module.create_mcjit_execution_engine_with_memory_manager(memory_manager, OptimizationLevel::None, CodeModel::JITDefault, false, false).unwrap();
+
+    // The panics are caught at the boundary with LLVM instead of aborting the process
+    assert!(execution_engine.get_function_address("add_counter").is_ok());
+    assert_eq!(log.borrow().last(), Some(&"finalize"));
+
+    drop(execution_engine);
+    drop(module);
+
+    assert_eq!(log.borrow().last(), Some(&"destroy"));
+}
@@ -2,13 +2,15 @@ extern crate inkwell;
 
 use self::inkwell::context::Context;
 use self::inkwell::memory_buffer::MemoryBuffer;
-use self::inkwell::module::Module;
+use self::inkwell::module::{LinkingError, Module};
 use self::inkwell::OptimizationLevel;
 use self::inkwell::targets::{InitializationConfig, Target};
 
 use std::env::temp_dir;
+use std::error::Error;
 use std::fs::{File, remove_file};
 use std::io::Read;
+use std::mem::transmute;
 
 #[test]
 fn test_write_bitcode_to_path() {
@@ -160,26 +162,242 @@ fn test_get_type_global_context() {
 
 #[test]
 fn test_module_no_double_free() {
-    let _module = {
-        let context = Context::create();
-
-        context.create_module("my_mod")
-    };
+    let context = Context::create();
+    let _module = context.create_module("my_mod");
 
-    // Context will live on in the module until here
+    drop(_module);
+    drop(context);
 }
 
 #[test]
 fn test_owned_module_dropped_ee_and_context() {
     Target::initialize_native(&InitializationConfig::default()).unwrap();
 
+    let context = Context::create();
     let _module = {
-        let context = Context::create();
         let module = context.create_module("my_mod");
 
         module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
         module
     };
 
-    // Context and EE will live on in the module until here
+    // EE will live on in the module until here
+}
+
+#[test]
+fn test_link_in_module() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[&i32_type], false);
+
+    // main.c: declares `add_one`, defines `add_two` in terms of it
+    let module = context.create_module("main");
+    let add_one = module.add_function("add_one", &fn_type, None);
+    let add_two = module.add_function("add_two", &fn_type, None);
+    let entry = context.append_basic_block(&add_two, "entry");
+    let arg = add_two.get_first_param().unwrap().into_int_value();
+
+    builder.position_at_end(&entry);
+
+    let once = builder.build_call(&add_one, &[&arg], "once").try_as_basic_value().left().unwrap();
+    let twice = builder.build_call(&add_one, &[&once], "twice").try_as_basic_value().left().unwrap();
+
+    builder.build_return(Some(&twice));
+
+    // other.c: defines `add_one`
+    let other = context.create_module("other");
+    let add_one = other.add_function("add_one", &fn_type, None);
+    let entry = context.append_basic_block(&add_one, "entry");
+    let arg = add_one.get_first_param().unwrap().into_int_value();
+
+    builder.position_at_end(&entry);
+
+    let sum = builder.build_int_add(&arg, &i32_type.const_int(1, false), "sum");
+
+    builder.build_return(Some(&sum));
+
+    assert!(module.link_in_module(other).is_ok());
+    assert!(module.get_function("add_one").unwrap().get_first_basic_block().is_some());
+    assert!(module.verify().is_ok());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+    let address = execution_engine.get_function_address("add_two").unwrap();
+
+    unsafe {
+        let add_two: extern "C" fn(i32) -> i32 = transmute(address);
+
+        assert_eq!(add_two(40), 42);
+    }
+}
+
+#[test]
+fn test_link_in_module_symbol_conflict() {
+    let other_context = Context::create();
+    let context = Context::create();
+    let builder = context.create_builder();
+    let fn_type = context.void_type().fn_type(&[], false);
+    let module = context.create_module("main");
+    let other = context.create_module("other");
+
+    for module in &[&module, &other] {
+        let function = module.add_function("my_fn", &fn_type, None);
+        let entry = context.append_basic_block(&function, "entry");
+
+        builder.position_at_end(&entry);
+        builder.build_return(None);
+    }
+
+    assert_eq!(module.link_in_module(other), Err(LinkingError::SymbolConflict("my_fn".into())));
+
+    // Modules from another context cannot be linked in
+    let other = other_context.create_module("other");
+
+    assert_eq!(module.link_in_module(other), Err(LinkingError::ContextMismatch));
+}
+
+#[test]
+fn test_parse_bitcode_from_buffer() {
+    let context = Context::create();
+    let module = context.create_module("my_module");
+    let builder = context.create_builder();
+    let fn_type = context.void_type().fn_type(&[], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+    builder.build_return(None);
+
+    let buffer = module.write_bitcode_to_memory();
+    let module2 = Module::parse_bitcode_from_buffer(&buffer, &context).unwrap();
+
+    assert_eq!(*module2.get_context(), context);
+    assert_eq!(module2.get_function("my_fn").unwrap().print_to_string(), function.print_to_string());
+
+    let garbage = MemoryBuffer::create_from_memory_range("garbage bitcode", "my_buffer");
+
+    assert!(Module::parse_bitcode_from_buffer(&garbage, &context).is_err());
+    assert!(Module::parse_bitcode_lazily_from_buffer(garbage, &context).is_err());
+}
+
+#[test]
+fn test_parse_bitcode_from_path() {
+    let mut path = temp_dir();
+
+    path.push("temp_parse.bc");
+
+    let context = Context::create();
+    let module = context.create_module("my_module");
+    let fn_type = context.void_type().fn_type(&[], false);
+
+    module.add_function("my_fn", &fn_type, None);
+
+    assert!(module.write_bitcode_to_path(&path));
+
+    let module2 = Module::parse_bitcode_from_path(&path, &context).unwrap();
+
+    assert!(module2.get_function("my_fn").is_some());
+
+    remove_file(&path).unwrap();
+
+    assert!(Module::parse_bitcode_from_path(&path, &context).is_err());
+}
+
+#[test]
+fn test_parse_bitcode_lazily() {
+    Target::initialize_native(&InitializationConfig::default()).expect("Failed to initialize native target");
+
+    let context = Context::create();
+    let module = context.create_module("runtime");
+    let builder = context.create_builder();
+    let i32_type = context.i32_type();
+    let fn_type = i32_type.fn_type(&[], false);
+    let function = module.add_function("answer", &fn_type, None);
+    let basic_block = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&basic_block);
+    builder.build_return(Some(&i32_type.const_int(42, false)));
+
+    let buffer = module.write_bitcode_to_memory();
+    let module = Module::parse_bitcode_lazily_from_buffer(buffer, &context).unwrap();
+    let function = module.get_function("answer").unwrap();
+
+    // The body has not been read yet
+    assert!(function.get_first_basic_block().is_none());
+
+    let execution_engine = module.create_jit_execution_engine(OptimizationLevel::None).unwrap();
+    let address = execution_engine.get_function_address("answer").unwrap();
+
+    unsafe {
+        let answer: extern "C" fn() -> i32 = transmute(address);
+
+        assert_eq!(answer(), 42);
+    }
+}
+
+#[test]
+fn test_verify_error_message() {
+    let context = Context::create();
+    let module = context.create_module("my_module");
+    let fn_type = context.void_type().fn_type(&[], false);
+    let function = module.add_function("my_fn", &fn_type, None);
+
+    function.append_basic_block("entry");
+
+    let module_err = module.verify().unwrap_err();
+    let function_err = function.verify().unwrap_err();
+
+    assert!(module_err.get_message().contains("Basic Block in function 'my_fn' does not have terminator!"), "{}", module_err.get_message());
+    assert_eq!(function_err, module_err);
+    assert_eq!(module_err.to_string(), module_err.get_message());
+
+    let boxed: Box<Error> = Box::new(module_err);
+
+    assert!(boxed.to_string().contains("does not have terminator!"));
+}
+
+#[test]
+fn test_functions_and_globals_iter() {
+    let context = Context::create();
+    let module = context.create_module("my_mod");
+    let void_type = context.void_type();
+    let i32_type = context.i32_type();
+    let fn_type = void_type.fn_type(&[], false);
+
+    assert_eq!(module.functions().count(), 0);
+    assert_eq!(module.globals().count(), 0);
+
+    let fn1 = module.add_function("fn1", &fn_type, None);
+    let fn2 = module.add_function("fn2", &fn_type, None);
+    let fn3 = module.add_function("fn3", &fn_type, None);
+
+    assert_eq!(module.functions().collect::<Vec<_>>(), vec![fn1, fn2, fn3]);
+    assert_eq!(module.functions().rev().collect::<Vec<_>>(), vec![fn3, fn2, fn1]);
+
+    let mut iter = module.functions();
+
+    assert_eq!(iter.next(), Some(fn1));
+    assert_eq!(iter.next_back(), Some(fn3));
+    assert_eq!(iter.next_back(), Some(fn2));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    // Deleting the yielded function doesn't invalidate the iterator
+    for function in module.functions() {
+        if function == fn2 {
+            unsafe {
+                function.delete();
+            }
+        }
+    }
+
+    assert_eq!(module.functions().collect::<Vec<_>>(), vec![fn1, fn3]);
+
+    let global1 = module.add_global(&i32_type, None, "global1");
+    let global2 = module.add_global(&i32_type, None, "global2");
+
+    assert_eq!(module.globals().collect::<Vec<_>>(), vec![global1, global2]);
+    assert_eq!(module.globals().rev().collect::<Vec<_>>(), vec![global2, global1]);
 }
@@ -66,6 +66,12 @@ fn test_init_all_passes_for_module() {
     pass_manager.add_scoped_no_alias_aa_pass();
     pass_manager.add_basic_alias_analysis_pass();
 
+    #[cfg(feature = "llvm4-0")]
+    {
+        pass_manager.add_new_gvn_pass();
+        pass_manager.add_early_cse_mem_ssa_pass();
+    }
+
     assert!(!pass_manager.initialize());
     assert!(!pass_manager.finalize());
 
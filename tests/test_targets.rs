@@ -77,7 +77,7 @@ fn test_target_data() {
 
     module.set_data_layout(&data_layout);
 
-    assert_eq!(*module.get_data_layout(), data_layout);
+    assert_eq!(module.get_data_layout(), data_layout);
 
     let i32_type = context.i32_type();
     let i64_type = context.i64_type();
@@ -264,33 +264,33 @@ fn test_const_null() {
     let vec_zero = vec_type.const_null();
     let array_zero = array_type.const_null();
 
-    assert_eq!(bool_zero.print_to_string(), &*CString::new("i1 false").unwrap());
+    assert_eq!(bool_zero.print_to_string().to_string(), "i1 false");
     assert!(bool_zero.is_null());
-    assert_eq!(i8_zero.print_to_string(), &*CString::new("i8 0").unwrap());
+    assert_eq!(i8_zero.print_to_string().to_string(), "i8 0");
     assert!(i8_zero.is_null());
-    assert_eq!(i16_zero.print_to_string(), &*CString::new("i16 0").unwrap());
+    assert_eq!(i16_zero.print_to_string().to_string(), "i16 0");
     assert!(i16_zero.is_null());
-    assert_eq!(i32_zero.print_to_string(), &*CString::new("i32 0").unwrap());
+    assert_eq!(i32_zero.print_to_string().to_string(), "i32 0");
     assert!(i32_zero.is_null());
-    assert_eq!(i64_zero.print_to_string(), &*CString::new("i64 0").unwrap());
+    assert_eq!(i64_zero.print_to_string().to_string(), "i64 0");
     assert!(i64_zero.is_null());
-    assert_eq!(i128_zero.print_to_string(), &*CString::new("i128 0").unwrap());
+    assert_eq!(i128_zero.print_to_string().to_string(), "i128 0");
     assert!(i128_zero.is_null());
-    assert_eq!(f16_zero.print_to_string(), &*CString::new("half 0xH0000").unwrap());
+    assert_eq!(f16_zero.print_to_string().to_string(), "half 0xH0000");
     assert!(f16_zero.is_null());
-    assert_eq!(f32_zero.print_to_string(), &*CString::new("float 0.000000e+00").unwrap());
+    assert_eq!(f32_zero.print_to_string().to_string(), "float 0.000000e+00");
     assert!(f32_zero.is_null());
-    assert_eq!(f64_zero.print_to_string(), &*CString::new("double 0.000000e+00").unwrap());
+    assert_eq!(f64_zero.print_to_string().to_string(), "double 0.000000e+00");
     assert!(f64_zero.is_null());
-    assert_eq!(f128_zero.print_to_string(), &*CString::new("fp128 0xL00000000000000000000000000000000").unwrap());
+    assert_eq!(f128_zero.print_to_string().to_string(), "fp128 0xL00000000000000000000000000000000");
     assert!(f128_zero.is_null());
-    assert_eq!(struct_zero.print_to_string(), &*CString::new("{ i8, fp128 } zeroinitializer").unwrap());
+    assert_eq!(struct_zero.print_to_string().to_string(), "{ i8, fp128 } zeroinitializer");
     assert!(struct_zero.is_null());
-    assert_eq!(ptr_zero.print_to_string(), &*CString::new("double* null").unwrap());
+    assert_eq!(ptr_zero.print_to_string().to_string(), "double* null");
     assert!(ptr_zero.is_null());
-    assert_eq!(vec_zero.print_to_string(), &*CString::new("<42 x double> zeroinitializer").unwrap());
+    assert_eq!(vec_zero.print_to_string().to_string(), "<42 x double> zeroinitializer");
     assert!(vec_zero.is_null());
-    assert_eq!(array_zero.print_to_string(), &*CString::new("[42 x double] zeroinitializer").unwrap());
+    assert_eq!(array_zero.print_to_string().to_string(), "[42 x double] zeroinitializer");
     assert!(array_zero.is_null());
 }
 
@@ -311,3 +311,15 @@ fn test_type_copies() {
 
     assert_eq!(i8_type, i8_type_copy);
 }
+
+#[test]
+fn test_print_to_string() {
+    let context = Context::create();
+    let i8_type = context.i8_type();
+    let llvm_string = i8_type.print_to_string();
+
+    assert_eq!(llvm_string.to_bytes(), b"i8");
+    assert_eq!(format!("{}", llvm_string), "i8");
+    assert_eq!(llvm_string, i8_type.print_to_string());
+    assert_ne!(llvm_string, context.i16_type().print_to_string());
+}
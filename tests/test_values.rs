@@ -2,8 +2,9 @@ extern crate inkwell;
 
 use self::inkwell::{DLLStorageClass, FloatPredicate, GlobalVisibility, ThreadLocalMode, AddressSpace};
 use self::inkwell::context::Context;
+use self::inkwell::memory_buffer::MemoryBuffer;
 use self::inkwell::module::Linkage::*;
-use self::inkwell::types::{StructType, VectorType};
+use self::inkwell::types::VectorType;
 use self::inkwell::values::InstructionOpcode::*;
 use self::inkwell::values::{MetadataValue, FIRST_CUSTOM_METADATA_KIND_ID};
 
@@ -70,6 +71,175 @@ fn test_instructions() {
     assert_eq!(instruction_clone, instruction_clone_copy);
 }
 
+#[test]
+fn test_instruction_operands() {
+    let context = Context::create();
+    let module = context.create_module("testing");
+    let builder = context.create_builder();
+
+    let void_type = context.void_type();
+    let f32_type = context.f32_type();
+    let f32_ptr_type = f32_type.ptr_type(AddressSpace::Generic);
+    let fn_type = void_type.fn_type(&[&f32_ptr_type], false);
+
+    let function = module.add_function("take_f32_ptr", &fn_type, None);
+    let entry = context.append_basic_block(&function, "entry");
+    let exit = context.append_basic_block(&function, "exit");
+
+    builder.position_at_end(&entry);
+
+    let arg1 = function.get_first_param().unwrap().into_pointer_value();
+    let f32_val = f32_type.const_float(::std::f64::consts::PI);
+    let store_instruction = builder.build_store(&arg1, &f32_val);
+    let branch_instruction = builder.build_unconditional_branch(&exit);
+
+    builder.position_at_end(&exit);
+
+    let return_instruction = builder.build_return(None);
+
+    assert_eq!(store_instruction.get_num_operands(), 2);
+    assert_eq!(store_instruction.get_operand(0).unwrap().left().unwrap().into_float_value(), f32_val);
+    assert_eq!(store_instruction.get_operand(1).unwrap().left().unwrap().into_pointer_value(), arg1);
+    assert!(store_instruction.get_operand(2).is_none());
+    assert!(store_instruction.get_operand_use(2).is_none());
+
+    assert_eq!(branch_instruction.get_num_operands(), 1);
+    assert_eq!(branch_instruction.get_operand(0).unwrap().right().unwrap(), exit);
+
+    assert_eq!(return_instruction.get_num_operands(), 0);
+    assert!(return_instruction.get_operand(0).is_none());
+
+    let f32_val2 = f32_type.const_float(2.0);
+
+    assert!(store_instruction.set_operand(0, &f32_val2));
+    assert!(!store_instruction.set_operand(2, &f32_val2));
+    assert_eq!(store_instruction.get_operand(0).unwrap().left().unwrap().into_float_value(), f32_val2);
+
+    let operand_use = store_instruction.get_operand_use(1).unwrap();
+
+    assert_eq!(operand_use.get_operand_index(), 1);
+    assert_eq!(operand_use.get_user().into_instruction_value(), store_instruction);
+    assert_eq!(operand_use.get_used_value().left().unwrap().into_pointer_value(), arg1);
+}
+
+#[test]
+fn test_metadata_operands() {
+    let context = Context::create();
+    let ir = "declare void @use_md(metadata)\n\ndefine void @my_fn() {\nentry:\n  call void @use_md(metadata !\"my_string\")\n  ret void\n}\n";
+    let memory_buffer = MemoryBuffer::create_from_memory_range_copy(ir, "my_module");
+    let module = context.create_module_from_ir(memory_buffer).unwrap();
+    let call = module.get_function("my_fn").unwrap().get_first_basic_block().unwrap().get_first_instruction().unwrap();
+
+    assert_eq!(call.get_opcode(), Call);
+
+    let operand = call.get_operand(0).unwrap().left().unwrap().into_metadata_value();
+
+    assert_eq!(operand.get_string_value().unwrap().to_str(), Ok("my_string"));
+
+    let operand_use = call.get_operand_use(0).unwrap();
+
+    assert_eq!(operand_use.get_used_value().left().unwrap().into_metadata_value(), operand);
+    assert_eq!(operand_use.get_user().into_instruction_value(), call);
+}
+
+#[test]
+fn test_uses() {
+    let context = Context::create();
+    let module = context.create_module("testing");
+    let builder = context.create_builder();
+
+    let void_type = context.void_type();
+    let f32_type = context.f32_type();
+    let f32_ptr_type = f32_type.ptr_type(AddressSpace::Generic);
+    let fn_type = void_type.fn_type(&[&f32_ptr_type], false);
+
+    let function = module.add_function("take_f32_ptr", &fn_type, None);
+    let entry = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&entry);
+
+    let arg1 = function.get_first_param().unwrap().into_pointer_value();
+
+    assert!(arg1.get_first_use().is_none());
+
+    let loaded = builder.build_load(&arg1, "loaded").into_float_value();
+    let sum = builder.build_float_add(&loaded, &loaded, "sum");
+    let store_instruction = builder.build_store(&arg1, &sum);
+
+    builder.build_return(None);
+
+    // The most recently added use comes first
+    let first_use = arg1.get_first_use().unwrap();
+
+    assert_eq!(first_use.get_user().into_instruction_value(), store_instruction);
+    assert_eq!(first_use.get_operand_index(), 1);
+
+    let second_use = first_use.get_next_use().unwrap();
+
+    assert_eq!(second_use.get_user().into_instruction_value(), loaded.as_instruction().unwrap());
+    assert_eq!(second_use.get_operand_index(), 0);
+    assert!(second_use.get_next_use().is_none());
+
+    // Both of sum's operands use the loaded value
+    let mut operand_indices = Vec::new();
+    let mut use_ = loaded.get_first_use();
+
+    while let Some(value_use) = use_ {
+        assert_eq!(value_use.get_user().into_instruction_value(), sum.as_instruction().unwrap());
+
+        operand_indices.push(value_use.get_operand_index());
+        use_ = value_use.get_next_use();
+    }
+
+    operand_indices.sort();
+
+    assert_eq!(operand_indices, vec![0, 1]);
+    assert_eq!(sum.get_first_use().unwrap().get_user().into_instruction_value(), store_instruction);
+    assert!(store_instruction.get_first_use().is_none());
+}
+
+#[test]
+fn test_uses_iter() {
+    let context = Context::create();
+    let module = context.create_module("testing");
+    let builder = context.create_builder();
+
+    let void_type = context.void_type();
+    let f32_type = context.f32_type();
+    let f32_ptr_type = f32_type.ptr_type(AddressSpace::Generic);
+    let fn_type = void_type.fn_type(&[&f32_ptr_type], false);
+
+    let function = module.add_function("take_f32_ptr", &fn_type, None);
+    let entry = context.append_basic_block(&function, "entry");
+
+    builder.position_at_end(&entry);
+
+    let arg1 = function.get_first_param().unwrap().into_pointer_value();
+
+    assert_eq!(arg1.uses().count(), 0);
+
+    let loaded = builder.build_load(&arg1, "loaded").into_float_value();
+    let sum = builder.build_float_add(&loaded, &loaded, "sum");
+    let store_instruction = builder.build_store(&arg1, &sum);
+
+    builder.build_return(None);
+
+    // Uses come in the same order as following get_next_use from get_first_use
+    let users: Vec<_> = arg1.uses().map(|use_| use_.get_user().into_instruction_value()).collect();
+
+    assert_eq!(users, vec![store_instruction, loaded.as_instruction().unwrap()]);
+    assert_eq!(arg1.uses().next(), arg1.get_first_use());
+
+    let mut operand_indices: Vec<u32> = loaded.uses().map(|use_| use_.get_operand_index()).collect();
+
+    operand_indices.sort();
+
+    assert_eq!(operand_indices, vec![0, 1]);
+    assert_eq!(sum.uses().count(), 1);
+    assert_eq!(store_instruction.uses().count(), 0);
+    assert_eq!(function.uses().count(), 0);
+}
+
 #[test]
 fn test_tail_call() {
     let context = Context::create();
@@ -81,13 +251,13 @@ fn test_tail_call() {
 
     let function = module.add_function("do_nothing", &fn_type, None);
 
-    let call_instruction = builder.build_call(&function, &[], "to_infinity_and_beyond", false);
+    let call_site = builder.build_call(&function, &[], "to_infinity_and_beyond");
 
-    assert_eq!(call_instruction.right().unwrap().is_tail_call(), false);
+    assert_eq!(call_site.try_as_basic_value().right().unwrap().is_tail_call(), false);
 
-    let call_instruction = builder.build_call(&function, &[], "to_infinity_and_beyond", true);
+    call_site.set_tail_call(true);
 
-    assert_eq!(call_instruction.right().unwrap().is_tail_call(), true);
+    assert_eq!(call_site.try_as_basic_value().right().unwrap().is_tail_call(), true);
 }
 
 #[test]
@@ -282,7 +452,7 @@ fn test_undef() {
     let f128_undef = f128_type.get_undef();
     let ptr_undef = bool_type.ptr_type(AddressSpace::Generic).get_undef();
     let array_undef = array_type.get_undef();
-    let struct_undef = StructType::struct_type(&[&bool_type], false).get_undef();
+    let struct_undef = context.struct_type(&[&bool_type], false).get_undef();
     let vec_undef = bool_type.vec_type(1).get_undef();
     let ppc_f128_undef = ppc_f128_type.get_undef();
 
@@ -338,16 +508,14 @@ fn test_verify_fn() {
 
     let function = module.add_function("fn", &fn_type, None);
 
-    assert!(!function.verify(false));
+    assert!(function.verify().is_err());
 
     let basic_block = context.append_basic_block(&function, "entry");
 
     builder.position_at_end(&basic_block);
     builder.build_return(None);
 
-    assert!(function.verify(false));
-
-    // TODO: Verify other verify modes
+    assert!(function.verify().is_ok());
 }
 
 #[test]
@@ -419,7 +587,7 @@ fn test_metadata() {
     assert_eq!(module.get_global_metadata_size("my_string_md"), 0);
     assert_eq!(module.get_global_metadata("my_string_md").len(), 0);
 
-    let md_string = MetadataValue::create_string("lots of metadata here");
+    let md_string = context.metadata_string("lots of metadata here");
 
     assert_eq!(md_string.get_node_size(), 0);
     assert_eq!(md_string.get_node_values().len(), 0);
@@ -456,7 +624,7 @@ fn test_metadata() {
     let vec_val = VectorType::const_vector(&[&i8_val]);
     let fn_val = module.add_function("my_fn", &fn_type, None);
 
-    let md_node = MetadataValue::create_node(&[&bool_val, &f32_val]);
+    let md_node = context.metadata_node(&[&bool_val, &f32_val]);
 
     let node_values = md_node.get_node_values();
 
@@ -627,27 +795,27 @@ fn test_floats() {
     let f64_two = f64_type.const_float(2.);
     let neg_two = f64_two.const_neg();
 
-    assert_eq!(neg_two.print_to_string(), &*CString::new("double -2.000000e+00").unwrap());
+    assert_eq!(neg_two.print_to_string().to_string(), "double -2.000000e+00");
 
     let neg_three = neg_two.const_sub(&f64_one);
 
-    assert_eq!(neg_three.print_to_string(), &*CString::new("double -3.000000e+00").unwrap());
+    assert_eq!(neg_three.print_to_string().to_string(), "double -3.000000e+00");
 
     let pos_six = neg_three.const_mul(&neg_two);
 
-    assert_eq!(pos_six.print_to_string(), &*CString::new("double 6.000000e+00").unwrap());
+    assert_eq!(pos_six.print_to_string().to_string(), "double 6.000000e+00");
 
     let pos_eight = pos_six.const_add(&f64_two);
 
-    assert_eq!(pos_eight.print_to_string(), &*CString::new("double 8.000000e+00").unwrap());
+    assert_eq!(pos_eight.print_to_string().to_string(), "double 8.000000e+00");
 
     let pos_four = pos_eight.const_div(&f64_two);
 
-    assert_eq!(pos_four.print_to_string(), &*CString::new("double 4.000000e+00").unwrap());
+    assert_eq!(pos_four.print_to_string().to_string(), "double 4.000000e+00");
 
     let rem = pos_six.const_remainder(&pos_four);
 
-    assert_eq!(rem.print_to_string(), &*CString::new("double 2.000000e+00").unwrap());
+    assert_eq!(rem.print_to_string().to_string(), "double 2.000000e+00");
 
     assert!(f64_one.const_compare(FloatPredicate::PredicateFalse, &f64_two).is_null());
     assert!(!f64_one.const_compare(FloatPredicate::PredicateTrue, &f64_two).is_null());
@@ -675,8 +843,7 @@ fn test_function_value_no_params() {
     let fn_type = void_type.fn_type(&[], false);
     let fn_value = module.add_function("no_params", &fn_type, None);
 
-    // REVIEW: According to this, fn_value.get_type() is a void ptr??
-    // assert_eq!(fn_value.get_type(), fn_type);
+    assert_eq!(fn_value.get_type(), fn_type);
     assert_eq!(fn_value.count_params(), 0);
     assert_eq!(fn_value.params().collect::<Vec<_>>().len(), 0);
     assert!(fn_value.get_first_param().is_none());
@@ -693,21 +860,21 @@ fn test_int_from_string() {
     let i8_type = context.i8_type();
     let i8_val = i8_type.const_int_from_string("0121", 10);
 
-    assert_eq!(i8_val.print_to_string(), &*CString::new("i8 121").unwrap());
+    assert_eq!(i8_val.print_to_string().to_string(), "i8 121");
 
     let i8_val = i8_type.const_int_from_string("0121", 3);
 
-    assert_eq!(i8_val.print_to_string(), &*CString::new("i8 16").unwrap());
+    assert_eq!(i8_val.print_to_string().to_string(), "i8 16");
 
     // LLVM will not throw an error, just parse until it can parse no more (and
     // possibly spit out something completely unexpected):
     let i8_val = i8_type.const_int_from_string("0121", 2);
 
-    assert_eq!(i8_val.print_to_string(), &*CString::new("i8 3").unwrap());
+    assert_eq!(i8_val.print_to_string().to_string(), "i8 3");
 
     let i8_val = i8_type.const_int_from_string("ABCD", 2);
 
-    assert_eq!(i8_val.print_to_string(), &*CString::new("i8 -15").unwrap());
+    assert_eq!(i8_val.print_to_string().to_string(), "i8 -15");
 }
 
 #[test]
@@ -836,7 +1003,7 @@ fn test_phi_values() {
     assert!(phi.as_basic_value().is_int_value());
     assert_eq!(phi.as_instruction().get_opcode(), Phi);
     assert_eq!(phi.count_incoming(), 0);
-    assert_eq!(phi.print_to_string(), &*CString::new("  %if = phi i1 ").unwrap());
+    assert_eq!(phi.print_to_string().to_string(), "  %if = phi i1 ");
 
     phi.add_incoming(&[
         (&false_val, &then_block),
@@ -844,7 +1011,7 @@ fn test_phi_values() {
     ]);
 
     assert_eq!(phi.count_incoming(), 2);
-    assert_eq!(phi.print_to_string(), &*CString::new("  %if = phi i1 [ false, %then ], [ true, %else ]").unwrap());
+    assert_eq!(phi.print_to_string().to_string(), "  %if = phi i1 [ false, %then ], [ true, %else ]");
 
     let (then_val, then_bb) = phi.get_incoming(0).unwrap();
     let (else_val, else_bb) = phi.get_incoming(1).unwrap();
@@ -864,19 +1031,19 @@ fn test_allocations() {
     let builder = context.create_builder();
     let stack_ptr = builder.build_alloca(&i32_type, "stack_ptr");
 
-    assert_eq!(stack_ptr.get_type().print_to_string(), &*CString::new("i32*").unwrap());
+    assert_eq!(stack_ptr.get_type().print_to_string().to_string(), "i32*");
 
     let stack_array = builder.build_array_alloca(&i32_type, &i32_three, "stack_array");
 
-    assert_eq!(stack_array.get_type().print_to_string(), &*CString::new("i32*").unwrap());
+    assert_eq!(stack_array.get_type().print_to_string().to_string(), "i32*");
 
     // REVIEW: Heap allocations are not working:
 
     // let heap_ptr = builder.build_malloc(&i32_type, "heap_ptr");
 
-    // assert_eq!(heap_ptr.get_type().print_to_string(), &*CString::new("i32*").unwrap());
+    // assert_eq!(heap_ptr.get_type().print_to_string().to_string(), "i32*");
 
     // let heap_array = builder.build_array_malloc(&i32_type, &i32_three, "heap_array");
 
-    // assert_eq!(heap_array.get_type().print_to_string(), &*CString::new("i32*").unwrap());
+    // assert_eq!(heap_array.get_type().print_to_string().to_string(), "i32*");
 }